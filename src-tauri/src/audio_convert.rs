@@ -0,0 +1,256 @@
+//! Audio Format Conversion
+//!
+//! Whisper, the VADs and the wake word detectors all expect 16 kHz mono audio, but
+//! microphones capture at their native rate and channel count (often 44.1/48 kHz
+//! stereo). This module converts between the two:
+//! - Downmix interleaved channels to mono by averaging
+//! - Resample with area averaging when downsampling (doubles as the anti-aliasing
+//!   filter) and linear interpolation when upsampling
+//! - `AudioConverter` does both incrementally on capture callbacks and re-chunks the
+//!   result into fixed-size frames
+
+/// Average interleaved channels into mono samples
+pub fn downmix(interleaved: &[f32], channels: u16) -> Vec<f32> {
+    let channels = channels.max(1) as usize;
+    if channels == 1 {
+        return interleaved.to_vec();
+    }
+
+    interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect()
+}
+
+/// Resample a complete mono signal
+pub fn resample(samples: &[f32], input_rate: u32, output_rate: u32) -> Vec<f32> {
+    let mut resampler = Resampler::new(input_rate, output_rate);
+    let mut output = Vec::with_capacity(samples.len() * output_rate as usize / input_rate.max(1) as usize + 1);
+    resampler.process(samples, &mut output);
+    output
+}
+
+/// Streaming sample rate converter for mono audio
+///
+/// Keeps its position between calls, so a signal fed in arbitrary chunks gives the
+/// same output as when fed at once.
+pub struct Resampler {
+    /// Input samples per output sample
+    step: f64,
+    /// Downsampling works in integer units so positions don't drift: one input sample
+    /// spans `input_span` units, one output sample `output_span`
+    input_span: u64,
+    output_span: u64,
+    /// Units from the start of the next input sample to the end of the current output
+    /// sample
+    boundary: u64,
+    sum: f64,
+    weight: u64,
+    /// Upsampling: position of the next output sample, relative to `previous`
+    position: f64,
+    previous: Option<f32>,
+}
+
+impl Resampler {
+    pub fn new(input_rate: u32, output_rate: u32) -> Self {
+        let (input_rate, output_rate) = (input_rate.max(1) as u64, output_rate.max(1) as u64);
+        let divisor = gcd(input_rate, output_rate);
+        let (input_span, output_span) = (output_rate / divisor, input_rate / divisor);
+
+        Self {
+            step: input_rate as f64 / output_rate as f64,
+            input_span,
+            output_span,
+            boundary: output_span,
+            sum: 0.0,
+            weight: 0,
+            position: 0.0,
+            previous: None,
+        }
+    }
+
+    /// Convert the next chunk, appending to `output`
+    pub fn process(&mut self, input: &[f32], output: &mut Vec<f32>) {
+        if self.step == 1.0 {
+            output.extend_from_slice(input);
+        } else if self.step > 1.0 {
+            self.downsample(input, output);
+        } else {
+            self.upsample(input, output);
+        }
+    }
+
+    /// Each output sample is the average of the input it covers
+    fn downsample(&mut self, input: &[f32], output: &mut Vec<f32>) {
+        for &sample in input {
+            let sample = sample as f64;
+            let mut start = 0;
+
+            // Output samples ending within this input sample
+            while self.boundary <= self.input_span {
+                let weight = self.boundary - start;
+                self.sum += sample * weight as f64;
+                self.weight += weight;
+                output.push((self.sum / self.weight as f64) as f32);
+
+                self.sum = 0.0;
+                self.weight = 0;
+                start = self.boundary;
+                self.boundary += self.output_span;
+            }
+
+            let weight = self.input_span - start;
+            self.sum += sample * weight as f64;
+            self.weight += weight;
+            self.boundary -= self.input_span;
+        }
+    }
+
+    /// Linear interpolation between neighbouring input samples
+    fn upsample(&mut self, input: &[f32], output: &mut Vec<f32>) {
+        let mut samples = Vec::with_capacity(input.len() + 1);
+        samples.extend(self.previous);
+        samples.extend_from_slice(input);
+        if samples.len() < 2 {
+            self.previous = samples.last().copied();
+            return;
+        }
+
+        while self.position + 1.0 < samples.len() as f64 {
+            let index = self.position.floor() as usize;
+            let fraction = (self.position - index as f64) as f32;
+            output.push(samples[index] + (samples[index + 1] - samples[index]) * fraction);
+            self.position += self.step;
+        }
+
+        // The last sample becomes index 0 of the next chunk
+        self.position -= (samples.len() - 1) as f64;
+        self.previous = samples.last().copied();
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Converts captured audio to mono frames at the target rate
+pub struct AudioConverter {
+    channels: u16,
+    resampler: Resampler,
+    frame_size: usize,
+    resampled: Vec<f32>,
+    pending: Vec<f32>,
+}
+
+impl AudioConverter {
+    /// # Arguments
+    /// * `input_rate` / `channels` - Native format of the capture device
+    /// * `output_rate` - Target sample rate (16 kHz for the voice pipeline)
+    /// * `frame_size` - Samples per frame handed to `on_frame`
+    pub fn new(input_rate: u32, channels: u16, output_rate: u32, frame_size: usize) -> Self {
+        Self {
+            channels,
+            resampler: Resampler::new(input_rate, output_rate),
+            frame_size: frame_size.max(1),
+            resampled: Vec::new(),
+            pending: Vec::with_capacity(frame_size * 2),
+        }
+    }
+
+    /// Convert interleaved capture data and call `on_frame` for every complete frame
+    ///
+    /// Leftover samples are kept for the next call.
+    pub fn process(&mut self, interleaved: &[f32], mut on_frame: impl FnMut(&[f32])) {
+        let mono = downmix(interleaved, self.channels);
+
+        self.resampled.clear();
+        self.resampler.process(&mono, &mut self.resampled);
+        self.pending.extend_from_slice(&self.resampled);
+
+        let complete = self.pending.len() / self.frame_size * self.frame_size;
+        for frame in self.pending[..complete].chunks(self.frame_size) {
+            on_frame(frame);
+        }
+        self.pending.drain(..complete);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(frequency: f32, rate: u32, seconds: f32) -> Vec<f32> {
+        (0..(rate as f32 * seconds) as usize)
+            .map(|i| (2.0 * std::f32::consts::PI * frequency * i as f32 / rate as f32).sin() * 0.5)
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn test_downmix_averages_channels() {
+        assert_eq!(downmix(&[0.2, 0.4, -1.0, 1.0], 2), vec![0.3, 0.0]);
+        assert_eq!(downmix(&[0.1, 0.2], 1), vec![0.1, 0.2]);
+    }
+
+    #[test]
+    fn test_resample_lengths() {
+        assert_eq!(resample(&vec![0.0; 48000], 48000, 16000).len(), 16000);
+        assert_eq!(resample(&vec![0.0; 44100], 44100, 16000).len(), 16000);
+        assert_eq!(resample(&vec![0.0; 16000], 16000, 16000).len(), 16000);
+        let upsampled = resample(&vec![0.0; 8000], 8000, 16000).len();
+        assert!((15998..=16000).contains(&upsampled), "upsampled to {}", upsampled);
+    }
+
+    #[test]
+    fn test_resample_keeps_speech_and_filters_aliases() {
+        // A 440 Hz tone passes through with its level intact
+        let tone = resample(&sine(440.0, 48000, 1.0), 48000, 16000);
+        assert!((rms(&tone) - rms(&sine(440.0, 16000, 1.0))).abs() < 0.02);
+
+        // 12 kHz can't be represented at 16 kHz and folds down to 4 kHz; the averaging
+        // has to attenuate it to at most a third
+        let input = sine(12000.0, 48000, 1.0);
+        let alias = resample(&input, 48000, 16000);
+        assert!(rms(&alias) <= rms(&input) / 3.0 + 1e-3, "alias rms {}", rms(&alias));
+    }
+
+    #[test]
+    fn test_streaming_matches_one_shot() {
+        for (input_rate, output_rate) in [(48000, 16000), (44100, 16000), (8000, 16000)] {
+            let signal = sine(300.0, input_rate, 0.5);
+            let expected = resample(&signal, input_rate, output_rate);
+
+            let mut resampler = Resampler::new(input_rate, output_rate);
+            let mut streamed = Vec::new();
+            for chunk in signal.chunks(441) {
+                resampler.process(chunk, &mut streamed);
+            }
+
+            assert_eq!(streamed.len(), expected.len());
+            for (a, b) in streamed.iter().zip(&expected) {
+                assert!((a - b).abs() < 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn test_converter_emits_fixed_frames() {
+        let mut converter = AudioConverter::new(48000, 2, 16000, 512);
+        let stereo: Vec<f32> = sine(440.0, 48000, 0.25).iter().flat_map(|&s| [s, s]).collect();
+
+        let mut frames = Vec::new();
+        for chunk in stereo.chunks(960) {
+            converter.process(chunk, |frame| frames.push(frame.len()));
+        }
+
+        // 0.25s at 16 kHz = 4000 samples = 7 full frames of 512
+        assert_eq!(frames, vec![512; 7]);
+    }
+}
@@ -0,0 +1,386 @@
+//! Barge-In
+//!
+//! Lets the user interrupt a spoken answer. While the assistant speaks, the voice
+//! pipeline keeps watching the microphone; when the user talks over the playback it
+//! stops the audio, cancels the generation and starts recording the new request.
+//!
+//! The microphone also hears the assistant's own voice, so detection is gated on
+//! the level of the audio being played: `PlaybackMonitor` knows the envelope of the
+//! current TTS output, and `BargeInDetector` only fires when the microphone is
+//! clearly louder than that echo (and well above the normal VAD threshold).
+//!
+//! Selected by the `barge_in_mode` setting:
+//! - "off" (default): microphone ignored during playback
+//! - "voice": any sustained speech louder than the playback interrupts
+//! - "wake_word": only a wake phrase interrupts (more robust to loud speakers)
+
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+const ENVELOPE_WINDOW_MS: u32 = 32;          // Same as a capture chunk at 16kHz
+const ECHO_LATENCY_MS: u32 = 250;            // Output + input buffering between playback and the mic
+const THRESHOLD_MULTIPLIER: f32 = 3.0;       // Barge-in needs 3× the VAD threshold
+const ECHO_GATE_RATIO: f32 = 0.5;            // ...and more than half the playback level
+pub const TRIGGER_FRAMES: usize = 8;         // ~250ms of loud speech before interrupting
+
+/// How the user can interrupt TTS playback
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BargeInMode {
+    Off,
+    Voice,
+    WakeWord,
+}
+
+impl BargeInMode {
+    /// Parse the `barge_in_mode` setting (unknown values disable barge-in)
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "voice" => Self::Voice,
+            "wake_word" => Self::WakeWord,
+            "off" | "" => Self::Off,
+            other => {
+                log::warn!("Unknown barge-in mode '{}', barge-in disabled", other);
+                Self::Off
+            }
+        }
+    }
+}
+
+/// Playback currently in progress
+struct Playback {
+    /// When the envelope started playing (moved forward by the time spent paused)
+    started: Instant,
+    paused_at: Option<Instant>,
+    /// RMS level per `ENVELOPE_WINDOW_MS` of the played audio
+    envelope: Vec<f32>,
+    stop: Box<dyn Fn() + Send>,
+}
+
+impl Playback {
+    /// How far into the envelope the playback is
+    fn elapsed_ms(&self) -> u32 {
+        self.paused_at.unwrap_or_else(Instant::now).duration_since(self.started).as_millis() as u32
+    }
+}
+
+type PlaybackListener = Box<dyn Fn(bool) + Send>;
+
+/// TTS playback state shared between the TTS engine and the voice pipeline
+///
+/// Cloning shares the same state.
+#[derive(Clone, Default)]
+pub struct PlaybackMonitor {
+    current: Arc<Mutex<Option<Playback>>>,
+    listener: Arc<Mutex<Option<PlaybackListener>>>,
+}
+
+impl PlaybackMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register audio that starts playing now
+    ///
+    /// # Arguments
+    /// * `samples` - The audio being played (normalized to -1.0..1.0)
+    /// * `sample_rate` - Sample rate of `samples`
+    /// * `stop` - Stops the playback (called on barge-in)
+    pub fn begin(&self, samples: &[f32], sample_rate: u32, stop: impl Fn() + Send + 'static) {
+        if let Ok(mut current) = self.current.lock() {
+            *current = Some(Playback {
+                started: Instant::now(),
+                paused_at: None,
+                envelope: envelope(samples, sample_rate),
+                stop: Box::new(stop),
+            });
+        }
+        self.notify(true);
+    }
+
+    /// Register audio queued behind the current playback (streamed TTS)
+    ///
+    /// Returns false if nothing is playing anymore, e.g. after a barge-in stopped it.
+    pub fn extend(&self, samples: &[f32], sample_rate: u32) -> bool {
+        let Ok(mut current) = self.current.lock() else {
+            return false;
+        };
+        let Some(playback) = current.as_mut() else {
+            return false;
+        };
+
+        // If the queue ran dry before this audio arrived, it only starts playing now
+        let elapsed_windows = (playback.elapsed_ms() / ENVELOPE_WINDOW_MS) as usize;
+        if playback.envelope.len() < elapsed_windows {
+            playback.envelope.resize(elapsed_windows, 0.0);
+        }
+        playback.envelope.extend(envelope(samples, sample_rate));
+        true
+    }
+
+    /// Playback paused: no echo until `resume`, which continues the envelope where it
+    /// left off
+    pub fn pause(&self) {
+        if let Ok(mut current) = self.current.lock() {
+            if let Some(playback) = current.as_mut() {
+                playback.paused_at.get_or_insert_with(Instant::now);
+            }
+        }
+    }
+
+    pub fn resume(&self) {
+        if let Ok(mut current) = self.current.lock() {
+            if let Some(playback) = current.as_mut() {
+                if let Some(paused_at) = playback.paused_at.take() {
+                    playback.started += paused_at.elapsed();
+                }
+            }
+        }
+    }
+
+    /// Playback finished (or was stopped)
+    pub fn end(&self) {
+        let ended = self.current.lock().ok().and_then(|mut current| current.take());
+        if ended.is_some() {
+            self.notify(false);
+        }
+    }
+
+    /// Whether TTS audio is currently playing
+    pub fn is_active(&self) -> bool {
+        self.current.lock().map(|current| current.is_some()).unwrap_or(false)
+    }
+
+    /// Call `listener` with true when playback begins and false when it ends or is
+    /// stopped
+    ///
+    /// Replaces the previous listener (there is one voice pipeline at a time).
+    pub fn set_listener(&self, listener: impl Fn(bool) + Send + 'static) {
+        if let Ok(mut current) = self.listener.lock() {
+            *current = Some(Box::new(listener));
+        }
+    }
+
+    fn notify(&self, playing: bool) {
+        if let Ok(listener) = self.listener.lock() {
+            if let Some(listener) = listener.as_ref() {
+                listener(playing);
+            }
+        }
+    }
+
+    /// Loudest playback level that can currently reach the microphone (0.0 if silent)
+    pub fn echo_level(&self) -> f32 {
+        self.current
+            .lock()
+            .ok()
+            .and_then(|current| {
+                current
+                    .as_ref()
+                    .filter(|p| p.paused_at.is_none())
+                    .map(|p| envelope_level(&p.envelope, p.elapsed_ms()))
+            })
+            .unwrap_or(0.0)
+    }
+
+    /// Stop the current playback; returns false if nothing was playing
+    pub fn stop(&self) -> bool {
+        let playback = self.current.lock().ok().and_then(|mut current| current.take());
+        match playback {
+            Some(playback) => {
+                (playback.stop)();
+                self.notify(false);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// RMS level per `ENVELOPE_WINDOW_MS` of `samples`
+fn envelope(samples: &[f32], sample_rate: u32) -> Vec<f32> {
+    let window = (sample_rate * ENVELOPE_WINDOW_MS / 1000).max(1) as usize;
+    samples.chunks(window).map(rms).collect()
+}
+
+/// Maximum envelope level over the windows heard `elapsed_ms` into the playback,
+/// allowing for `ECHO_LATENCY_MS` of delay
+fn envelope_level(envelope: &[f32], elapsed_ms: u32) -> f32 {
+    let first = elapsed_ms.saturating_sub(ECHO_LATENCY_MS) / ENVELOPE_WINDOW_MS;
+    let last = elapsed_ms / ENVELOPE_WINDOW_MS + 1;
+
+    envelope
+        .iter()
+        .skip(first as usize)
+        .take((last - first + 1) as usize)
+        .fold(0.0, |max, &level| f32::max(max, level))
+}
+
+/// Detects the user talking over TTS playback (`BargeInMode::Voice`)
+#[derive(Debug, Default)]
+pub struct BargeInDetector {
+    loud_frames: usize,
+}
+
+impl BargeInDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed the next microphone frame; returns true when the user barges in
+    ///
+    /// # Arguments
+    /// * `frame` - 16kHz mono microphone audio
+    /// * `vad_threshold` - Normal speech energy threshold (`vad_sensitivity`)
+    /// * `echo_level` - Current playback level (`PlaybackMonitor::echo_level`)
+    pub fn process(&mut self, frame: &[f32], vad_threshold: f32, echo_level: f32) -> bool {
+        let threshold = f32::max(vad_threshold * THRESHOLD_MULTIPLIER, echo_level * ECHO_GATE_RATIO);
+
+        if rms(frame) > threshold {
+            self.loud_frames += 1;
+        } else {
+            self.loud_frames = 0;
+        }
+
+        if self.loud_frames >= TRIGGER_FRAMES {
+            self.loud_frames = 0;
+            return true;
+        }
+        false
+    }
+
+    pub fn reset(&mut self) {
+        self.loud_frames = 0;
+    }
+}
+
+fn rms(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+
+    (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    const FRAME: usize = 512;
+
+    fn frames(level: f32, count: usize) -> Vec<Vec<f32>> {
+        vec![vec![level; FRAME]; count]
+    }
+
+    fn triggers(detector: &mut BargeInDetector, frames: &[Vec<f32>], echo_level: f32) -> bool {
+        frames.iter().any(|frame| detector.process(frame, 0.02, echo_level))
+    }
+
+    #[test]
+    fn test_sustained_speech_barges_in() {
+        let mut detector = BargeInDetector::new();
+        // Normal speech level isn't enough, it has to stand out
+        assert!(!triggers(&mut detector, &frames(0.04, 20), 0.0));
+        // A short loud burst (a cough, a door) isn't either
+        assert!(!triggers(&mut detector, &frames(0.2, TRIGGER_FRAMES - 1), 0.0));
+        detector.reset();
+        assert!(triggers(&mut detector, &frames(0.2, TRIGGER_FRAMES), 0.0));
+    }
+
+    #[test]
+    fn test_playback_echo_is_gated() {
+        let mut detector = BargeInDetector::new();
+        // The assistant's own voice picked up by the mic stays below the gate
+        assert!(!triggers(&mut detector, &frames(0.2, 40), 0.5));
+        // The user talking louder than the playback gets through
+        assert!(triggers(&mut detector, &frames(0.3, 40), 0.5));
+    }
+
+    #[test]
+    fn test_envelope_level_allows_for_latency() {
+        // 32ms windows: silence, then loud from 320ms on
+        let mut envelope = vec![0.0; 10];
+        envelope.extend(vec![0.5; 10]);
+
+        // Loud audio starts playing just after now, or was played within the latency
+        assert_eq!(envelope_level(&envelope, 0), 0.0);
+        assert_eq!(envelope_level(&envelope, 300), 0.5);
+        assert_eq!(envelope_level(&envelope, 500), 0.5);
+        // Past the end of the playback
+        assert_eq!(envelope_level(&envelope, 2000), 0.0);
+    }
+
+    #[test]
+    fn test_monitor_stops_playback_once() {
+        let monitor = PlaybackMonitor::new();
+        assert!(!monitor.stop());
+        assert_eq!(monitor.echo_level(), 0.0);
+
+        let stopped = Arc::new(AtomicBool::new(false));
+        let flag = stopped.clone();
+        monitor.begin(&vec![0.5; 22050], 22050, move || flag.store(true, Ordering::Relaxed));
+        assert!((monitor.echo_level() - 0.5).abs() < 1e-6);
+
+        assert!(monitor.stop());
+        assert!(stopped.load(Ordering::Relaxed));
+        assert!(!monitor.stop());
+        assert_eq!(monitor.echo_level(), 0.0);
+    }
+
+    #[test]
+    fn test_monitor_notifies_listener() {
+        let monitor = PlaybackMonitor::new();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorded = events.clone();
+        monitor.set_listener(move |playing| recorded.lock().unwrap().push(playing));
+
+        monitor.begin(&[], 22050, || {});
+        assert!(monitor.is_active());
+        monitor.end();
+        monitor.end();
+        monitor.begin(&[], 22050, || {});
+        monitor.stop();
+        assert!(!monitor.is_active());
+
+        assert_eq!(*events.lock().unwrap(), vec![true, false, true, false]);
+    }
+
+    #[test]
+    fn test_monitor_extends_queued_playback() {
+        let monitor = PlaybackMonitor::new();
+        assert!(!monitor.extend(&[0.5; 1000], 22050));
+
+        monitor.begin(&[], 22050, || {});
+        assert_eq!(monitor.echo_level(), 0.0);
+        assert!(monitor.extend(&vec![0.5; 22050], 22050));
+        assert!((monitor.echo_level() - 0.5).abs() < 1e-6);
+
+        monitor.stop();
+        assert!(!monitor.extend(&[0.5; 1000], 22050));
+    }
+
+    #[test]
+    fn test_monitor_pause_freezes_envelope() {
+        let monitor = PlaybackMonitor::new();
+        // 0.5s of silence, then loud
+        let mut samples = vec![0.0; 11025];
+        samples.extend(vec![0.5; 11025]);
+        monitor.begin(&samples, 22050, || {});
+
+        monitor.pause();
+        std::thread::sleep(std::time::Duration::from_millis(600));
+        // Nothing plays while paused, and the loud part is still ahead on resume
+        assert_eq!(monitor.echo_level(), 0.0);
+        monitor.resume();
+        assert_eq!(monitor.echo_level(), 0.0);
+        assert!(monitor.is_active());
+    }
+
+    #[test]
+    fn test_parse_mode() {
+        assert_eq!(BargeInMode::parse("voice"), BargeInMode::Voice);
+        assert_eq!(BargeInMode::parse("wake_word"), BargeInMode::WakeWord);
+        assert_eq!(BargeInMode::parse("off"), BargeInMode::Off);
+        assert_eq!(BargeInMode::parse("sometimes"), BargeInMode::Off);
+    }
+}
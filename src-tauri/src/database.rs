@@ -10,6 +10,24 @@ pub struct Conversation {
     pub id: i64,
     pub title: String,
     pub created_at: String,
+    pub persona_id: Option<i64>, // Persona used for generation (None = default assistant)
+}
+
+/// Represents a named persona: system prompt and generation parameters for a conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Persona {
+    #[serde(default)]
+    pub id: i64,                     // Ignored on create
+    pub name: String,
+    pub system_prompt: String,       // Replaces the default Aura system prompt (empty = keep default)
+    pub temperature: f32,            // Sampling temperature (0.0-2.0)
+    pub max_tokens: u32,             // Maximum tokens generated per response
+    pub top_p: Option<f32>,          // Nucleus sampling (None = server default)
+    #[serde(default)]
+    pub stop: Vec<String>,           // Stop sequences (stored as a JSON array)
+    pub model_name: Option<String>,  // Model override (None = model from Settings)
+    #[serde(default)]
+    pub created_at: String,
 }
 
 /// Represents a message in the database
@@ -22,6 +40,15 @@ pub struct Message {
     pub timestamp: String,
 }
 
+/// Rolling summary of a conversation's older turns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationSummary {
+    pub conversation_id: i64,
+    pub summary: String,
+    pub summarized_through_id: i64, // Last message ID covered by the summary
+    pub updated_at: String,
+}
+
 /// Represents a user's Home Assistant shortcut (scene or script)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserHAShortcut {
@@ -44,18 +71,37 @@ pub struct UserHAPreferences {
     pub updated_at: String,
 }
 
+/// Represents a user's TTS preferences (None = the voice's default)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserTtsPreferences {
+    pub user_id: i64,
+    pub speaker: Option<String>,          // Speaker name from the voice's speaker_id_map
+    pub length_scale: Option<f32>,        // Speech rate (larger is slower)
+    pub noise_scale: Option<f32>,         // Voice variability
+    pub sentence_silence: Option<f32>,    // Pause after each sentence in seconds
+    pub updated_at: String,
+}
+
 /// Represents application settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settings {
     pub llm_provider: String,      // "local" or "api" (kept for backward compatibility)
     pub server_address: String,    // Remote server address for gRPC (legacy field)
     pub wake_word_enabled: bool,   // Enable/disable wake word detection
+    pub wake_phrases: String,      // JSON list of wake phrases, each {"phrase", "sensitivity" (0.0-1.0)}
+    pub input_device_name: String, // Microphone to capture from (empty = system default)
     pub api_base_url: String,      // Base URL for OpenAI-compatible API (e.g., "http://localhost:1234/v1")
     pub model_name: String,        // Model name to use (e.g., "llama3", "phi3:instruct")
     pub vad_sensitivity: f32,      // Voice activity detection sensitivity (RMS energy threshold, 0.0-1.0)
     pub vad_timeout_ms: u32,       // Silence timeout in milliseconds before ending recording
+    pub vad_engine: String,        // End-of-speech detection: "energy" (default) or "silero" (neural VAD)
+    pub barge_in_mode: String,     // Interrupting TTS playback: "off" (default), "voice" or "wake_word"
     pub stt_model_name: String,    // STT (Whisper) model filename (e.g., "ggml-base.en.bin", "ggml-small.en.bin")
+    pub stt_language: String,      // Whisper language: "auto" (detect) or an ISO 639-1 code (e.g., "de")
+    pub stt_partial_interval_ms: u32, // Re-transcribe interval for live partial transcripts (0 = off)
+    pub debug_capture_enabled: bool, // Save each utterance (WAV + JSON sidecar) for diagnosis and replay
     pub voice_preference: String,  // TTS voice preference ("male" or "female", maps to lessac-medium or amy-medium)
+    pub voice_model: String,       // Active Piper voice id from the voice catalog (empty = use voice_preference)
 
     // RAG / Online Mode Settings
     pub online_mode_enabled: bool,          // Enable/disable web search for RAG (default: false, requires explicit opt-in)
@@ -74,6 +120,13 @@ pub struct Settings {
     pub ha_base_url: String,                // Home Assistant base URL (e.g., "http://homeassistant.local:8123")
     pub ha_auto_sync: bool,                 // Auto-sync entities on connect (default: true)
     pub ha_onboarding_dismissed: bool,      // Whether user has dismissed the onboarding guide
+
+    // LLM Conversation Settings
+    pub context_token_budget: u32,          // Max prompt tokens for system prompt + history + question (default: 2048)
+    pub llm_fallback_backends: String,      // JSON list of fallback backends tried when api_base_url is unavailable
+    pub llm_context_length: u32,            // Model context window in tokens (0 = detect via Ollama)
+    pub ollama_sidecar_host: String,        // Host the bundled Ollama last ran on (api_base_url follows it when the port changes)
+    pub llm_timeout_secs: u32,              // Request timeout of the primary backend (api_base_url) before failing over
 }
 
 /// Database manager for Aura Desktop
@@ -145,6 +198,59 @@ impl Database {
             )
             .map_err(|e| format!("Failed to create index: {}", e))?;
 
+        // Create personas table (named system prompt + generation parameters)
+        self.conn
+            .execute(
+                "CREATE TABLE IF NOT EXISTS personas (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    name TEXT NOT NULL UNIQUE,
+                    system_prompt TEXT NOT NULL DEFAULT '',
+                    temperature REAL NOT NULL DEFAULT 0.7,
+                    max_tokens INTEGER NOT NULL DEFAULT 512,
+                    top_p REAL,
+                    stop TEXT NOT NULL DEFAULT '[]',
+                    model_name TEXT,
+                    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+                )",
+                [],
+            )
+            .map_err(|e| format!("Failed to create personas table: {}", e))?;
+
+        // Attach personas to conversations (ALTER TABLE is safe for existing databases)
+        let persona_column_exists: i32 = self
+            .conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('conversations') WHERE name = 'persona_id'",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("Failed to inspect conversations table: {}", e))?;
+
+        if persona_column_exists == 0 {
+            self.conn
+                .execute(
+                    "ALTER TABLE conversations ADD COLUMN persona_id INTEGER DEFAULT NULL
+                     REFERENCES personas(id) ON DELETE SET NULL",
+                    [],
+                )
+                .map_err(|e| format!("Failed to add persona_id column: {}", e))?;
+            log::info!("Added column 'persona_id' to conversations table");
+        }
+
+        // Create conversation_summaries table (rolling summary of older turns)
+        self.conn
+            .execute(
+                "CREATE TABLE IF NOT EXISTS conversation_summaries (
+                    conversation_id INTEGER PRIMARY KEY,
+                    summary TEXT NOT NULL,
+                    summarized_through_id INTEGER NOT NULL,
+                    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                    FOREIGN KEY (conversation_id) REFERENCES conversations(id) ON DELETE CASCADE
+                )",
+                [],
+            )
+            .map_err(|e| format!("Failed to create conversation_summaries table: {}", e))?;
+
         // Create settings table (key-value store)
         self.conn
             .execute(
@@ -292,6 +398,22 @@ impl Database {
 
         log::info!("User Home Assistant personalization tables initialized");
 
+        // Per-user speaker and speech settings for TTS
+        self.conn
+            .execute(
+                "CREATE TABLE IF NOT EXISTS user_tts_preferences (
+                    user_id INTEGER PRIMARY KEY,
+                    speaker TEXT,
+                    length_scale REAL,
+                    noise_scale REAL,
+                    sentence_silence REAL,
+                    updated_at TEXT NOT NULL,
+                    FOREIGN KEY (user_id) REFERENCES user_profiles(id) ON DELETE CASCADE
+                )",
+                [],
+            )
+            .map_err(|e| format!("Failed to create user_tts_preferences table: {}", e))?;
+
         // Insert default settings if they don't exist
         self.conn
             .execute(
@@ -444,6 +566,98 @@ impl Database {
             )
             .map_err(|e| format!("Failed to insert default ha_onboarding_dismissed: {}", e))?;
 
+        // LLM Conversation Settings
+        self.conn
+            .execute(
+                "INSERT OR IGNORE INTO settings (key, value) VALUES ('context_token_budget', '2048')",
+                [],
+            )
+            .map_err(|e| format!("Failed to insert default context_token_budget: {}", e))?;
+
+        self.conn
+            .execute(
+                "INSERT OR IGNORE INTO settings (key, value) VALUES ('llm_fallback_backends', '[]')",
+                [],
+            )
+            .map_err(|e| format!("Failed to insert default llm_fallback_backends: {}", e))?;
+
+        self.conn
+            .execute(
+                "INSERT OR IGNORE INTO settings (key, value) VALUES ('llm_context_length', '0')",
+                [],
+            )
+            .map_err(|e| format!("Failed to insert default llm_context_length: {}", e))?;
+
+        self.conn
+            .execute(
+                "INSERT OR IGNORE INTO settings (key, value) VALUES ('stt_language', 'auto')",
+                [],
+            )
+            .map_err(|e| format!("Failed to insert default stt_language: {}", e))?;
+
+        self.conn
+            .execute(
+                "INSERT OR IGNORE INTO settings (key, value) VALUES ('stt_partial_interval_ms', '500')",
+                [],
+            )
+            .map_err(|e| format!("Failed to insert default stt_partial_interval_ms: {}", e))?;
+
+        self.conn
+            .execute(
+                r#"INSERT OR IGNORE INTO settings (key, value) VALUES ('wake_phrases', '[{"phrase":"hey aura","sensitivity":0.75}]')"#,
+                [],
+            )
+            .map_err(|e| format!("Failed to insert default wake_phrases: {}", e))?;
+
+        self.conn
+            .execute(
+                "INSERT OR IGNORE INTO settings (key, value) VALUES ('vad_engine', 'energy')",
+                [],
+            )
+            .map_err(|e| format!("Failed to insert default vad_engine: {}", e))?;
+
+        self.conn
+            .execute(
+                "INSERT OR IGNORE INTO settings (key, value) VALUES ('input_device_name', '')",
+                [],
+            )
+            .map_err(|e| format!("Failed to insert default input_device_name: {}", e))?;
+
+        self.conn
+            .execute(
+                "INSERT OR IGNORE INTO settings (key, value) VALUES ('barge_in_mode', 'off')",
+                [],
+            )
+            .map_err(|e| format!("Failed to insert default barge_in_mode: {}", e))?;
+
+        self.conn
+            .execute(
+                "INSERT OR IGNORE INTO settings (key, value) VALUES ('debug_capture_enabled', 'false')",
+                [],
+            )
+            .map_err(|e| format!("Failed to insert default debug_capture_enabled: {}", e))?;
+
+        self.conn
+            .execute(
+                "INSERT OR IGNORE INTO settings (key, value) VALUES ('voice_model', '')",
+                [],
+            )
+            .map_err(|e| format!("Failed to insert default voice_model: {}", e))?;
+
+        self.conn
+            .execute(
+                "INSERT OR IGNORE INTO settings (key, value) VALUES ('ollama_sidecar_host', '127.0.0.1:11434')",
+                [],
+            )
+            .map_err(|e| format!("Failed to insert default ollama_sidecar_host: {}", e))?;
+
+        self.conn
+            .execute(
+                "INSERT OR IGNORE INTO settings (key, value) VALUES ('llm_timeout_secs', '120')",
+                [],
+            )
+            .map_err(|e| format!("Failed to insert default llm_timeout_secs: {}", e))?;
+
         log::info!("Database tables initialized");
 
         Ok(())
@@ -453,7 +667,7 @@ impl Database {
     pub fn load_conversations(&self) -> Result<Vec<Conversation>, String> {
         let mut stmt = self
             .conn
-            .prepare("SELECT id, title, created_at FROM conversations ORDER BY created_at DESC")
+            .prepare("SELECT id, title, created_at, persona_id FROM conversations ORDER BY created_at DESC")
             .map_err(|e| format!("Failed to prepare statement: {}", e))?;
 
         let conversations = stmt
@@ -462,6 +676,7 @@ impl Database {
                     id: row.get(0)?,
                     title: row.get(1)?,
                     created_at: row.get(2)?,
+                    persona_id: row.get(3)?,
                 })
             })
             .map_err(|e| format!("Failed to query conversations: {}", e))?
@@ -588,6 +803,232 @@ impl Database {
         Ok(())
     }
 
+    /// Attach a persona to a conversation (None restores the default assistant)
+    pub fn set_conversation_persona(&self, conversation_id: i64, persona_id: Option<i64>) -> Result<(), String> {
+        let updated = self
+            .conn
+            .execute(
+                "UPDATE conversations SET persona_id = ?1 WHERE id = ?2",
+                params![persona_id, conversation_id],
+            )
+            .map_err(|e| format!("Failed to set conversation persona: {}", e))?;
+
+        if updated == 0 {
+            return Err(format!("Conversation {} not found", conversation_id));
+        }
+
+        log::info!("Set persona of conversation {} to {:?}", conversation_id, persona_id);
+
+        Ok(())
+    }
+
+    /// Get the persona attached to a conversation, if any
+    pub fn get_conversation_persona(&self, conversation_id: i64) -> Result<Option<Persona>, String> {
+        let persona_id: Option<i64> = self
+            .conn
+            .query_row(
+                "SELECT persona_id FROM conversations WHERE id = ?1",
+                params![conversation_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("Failed to load conversation {}: {}", conversation_id, e))?;
+
+        match persona_id {
+            Some(id) => self.get_persona(id).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Get the rolling summary of a conversation, if one has been written
+    pub fn get_conversation_summary(&self, conversation_id: i64) -> Result<Option<ConversationSummary>, String> {
+        let result = self.conn.query_row(
+            "SELECT conversation_id, summary, summarized_through_id, updated_at
+             FROM conversation_summaries
+             WHERE conversation_id = ?1",
+            params![conversation_id],
+            |row| {
+                Ok(ConversationSummary {
+                    conversation_id: row.get(0)?,
+                    summary: row.get(1)?,
+                    summarized_through_id: row.get(2)?,
+                    updated_at: row.get(3)?,
+                })
+            },
+        );
+
+        match result {
+            Ok(summary) => Ok(Some(summary)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(format!("Failed to load conversation summary: {}", e)),
+        }
+    }
+
+    /// Store the rolling summary of a conversation (replaces the previous one)
+    ///
+    /// # Arguments
+    /// * `summarized_through_id` - ID of the newest message covered by the summary
+    pub fn save_conversation_summary(
+        &self,
+        conversation_id: i64,
+        summary: &str,
+        summarized_through_id: i64,
+    ) -> Result<(), String> {
+        self.conn
+            .execute(
+                "INSERT INTO conversation_summaries (conversation_id, summary, summarized_through_id, updated_at)
+                 VALUES (?1, ?2, ?3, CURRENT_TIMESTAMP)
+                 ON CONFLICT(conversation_id) DO UPDATE SET
+                    summary = excluded.summary,
+                    summarized_through_id = excluded.summarized_through_id,
+                    updated_at = excluded.updated_at",
+                params![conversation_id, summary, summarized_through_id],
+            )
+            .map_err(|e| format!("Failed to save conversation summary: {}", e))?;
+
+        log::info!(
+            "Saved summary of conversation {} (through message {})",
+            conversation_id,
+            summarized_through_id
+        );
+
+        Ok(())
+    }
+
+    /// Load all personas, ordered by name
+    pub fn load_personas(&self) -> Result<Vec<Persona>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT id, name, system_prompt, temperature, max_tokens, top_p, stop, model_name, created_at
+                 FROM personas
+                 ORDER BY name ASC",
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let personas = stmt
+            .query_map([], Self::persona_from_row)
+            .map_err(|e| format!("Failed to query personas: {}", e))?
+            .collect::<SqlResult<Vec<_>>>()
+            .map_err(|e| format!("Failed to collect personas: {}", e))?;
+
+        log::info!("Loaded {} personas", personas.len());
+
+        Ok(personas)
+    }
+
+    /// Load a single persona by ID
+    pub fn get_persona(&self, persona_id: i64) -> Result<Persona, String> {
+        self.conn
+            .query_row(
+                "SELECT id, name, system_prompt, temperature, max_tokens, top_p, stop, model_name, created_at
+                 FROM personas
+                 WHERE id = ?1",
+                params![persona_id],
+                Self::persona_from_row,
+            )
+            .map_err(|e| format!("Failed to load persona {}: {}", persona_id, e))
+    }
+
+    /// Create a new persona and return its ID
+    ///
+    /// The `id` and `created_at` fields of `persona` are ignored.
+    pub fn create_persona(&self, persona: &Persona) -> Result<i64, String> {
+        let stop = serde_json::to_string(&persona.stop)
+            .map_err(|e| format!("Failed to serialize stop sequences: {}", e))?;
+
+        self.conn
+            .execute(
+                "INSERT INTO personas (name, system_prompt, temperature, max_tokens, top_p, stop, model_name)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    persona.name,
+                    persona.system_prompt,
+                    persona.temperature,
+                    persona.max_tokens,
+                    persona.top_p,
+                    stop,
+                    persona.model_name,
+                ],
+            )
+            .map_err(|e| format!("Failed to create persona: {}", e))?;
+
+        let id = self.conn.last_insert_rowid();
+
+        log::info!("Created persona: {} (id: {})", persona.name, id);
+
+        Ok(id)
+    }
+
+    /// Update an existing persona (matched by `persona.id`)
+    pub fn update_persona(&self, persona: &Persona) -> Result<(), String> {
+        let stop = serde_json::to_string(&persona.stop)
+            .map_err(|e| format!("Failed to serialize stop sequences: {}", e))?;
+
+        let updated = self
+            .conn
+            .execute(
+                "UPDATE personas
+                 SET name = ?1, system_prompt = ?2, temperature = ?3, max_tokens = ?4,
+                     top_p = ?5, stop = ?6, model_name = ?7
+                 WHERE id = ?8",
+                params![
+                    persona.name,
+                    persona.system_prompt,
+                    persona.temperature,
+                    persona.max_tokens,
+                    persona.top_p,
+                    stop,
+                    persona.model_name,
+                    persona.id,
+                ],
+            )
+            .map_err(|e| format!("Failed to update persona: {}", e))?;
+
+        if updated == 0 {
+            return Err(format!("Persona {} not found", persona.id));
+        }
+
+        log::info!("Updated persona: {} (id: {})", persona.name, persona.id);
+
+        Ok(())
+    }
+
+    /// Delete a persona (conversations using it fall back to the default assistant)
+    pub fn delete_persona(&self, persona_id: i64) -> Result<(), String> {
+        // Detach explicitly in case foreign key enforcement is disabled in this SQLite build
+        self.conn
+            .execute(
+                "UPDATE conversations SET persona_id = NULL WHERE persona_id = ?1",
+                params![persona_id],
+            )
+            .map_err(|e| format!("Failed to detach persona from conversations: {}", e))?;
+
+        self.conn
+            .execute("DELETE FROM personas WHERE id = ?1", params![persona_id])
+            .map_err(|e| format!("Failed to delete persona: {}", e))?;
+
+        log::info!("Deleted persona {}", persona_id);
+
+        Ok(())
+    }
+
+    /// Map a `personas` row (in the column order used by the queries above)
+    fn persona_from_row(row: &rusqlite::Row) -> SqlResult<Persona> {
+        let stop: String = row.get(6)?;
+
+        Ok(Persona {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            system_prompt: row.get(2)?,
+            temperature: row.get(3)?,
+            max_tokens: row.get(4)?,
+            top_p: row.get(5)?,
+            stop: serde_json::from_str(&stop).unwrap_or_default(),
+            model_name: row.get(7)?,
+            created_at: row.get(8)?,
+        })
+    }
+
     /// Get the total number of conversations
     pub fn count_conversations(&self) -> Result<i64, String> {
         let count: i64 = self
@@ -817,6 +1258,134 @@ impl Database {
 
         let ha_onboarding_dismissed = ha_onboarding_dismissed_str == "true";
 
+        // Load LLM conversation settings
+        let context_token_budget: u32 = self
+            .conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = 'context_token_budget'",
+                [],
+                |row| row.get(0),
+            )
+            .ok()
+            .and_then(|s: String| s.parse().ok())
+            .unwrap_or(2048);
+
+        let llm_fallback_backends: String = self
+            .conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = 'llm_fallback_backends'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap_or_else(|_| "[]".to_string());
+
+        let llm_context_length: u32 = self
+            .conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = 'llm_context_length'",
+                [],
+                |row| row.get(0),
+            )
+            .ok()
+            .and_then(|s: String| s.parse().ok())
+            .unwrap_or(0);
+
+        let stt_language: String = self
+            .conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = 'stt_language'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap_or_else(|_| "auto".to_string());
+
+        let stt_partial_interval_ms: u32 = self
+            .conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = 'stt_partial_interval_ms'",
+                [],
+                |row| row.get(0),
+            )
+            .ok()
+            .and_then(|s: String| s.parse().ok())
+            .unwrap_or(500);
+
+        let wake_phrases: String = self
+            .conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = 'wake_phrases'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap_or_else(|_| r#"[{"phrase":"hey aura","sensitivity":0.75}]"#.to_string());
+
+        let vad_engine: String = self
+            .conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = 'vad_engine'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap_or_else(|_| "energy".to_string());
+
+        let input_device_name: String = self
+            .conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = 'input_device_name'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap_or_default();
+
+        let barge_in_mode: String = self
+            .conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = 'barge_in_mode'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap_or_else(|_| "off".to_string());
+
+        let debug_capture_enabled_str: String = self
+            .conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = 'debug_capture_enabled'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap_or_else(|_| "false".to_string());
+
+        let debug_capture_enabled = debug_capture_enabled_str == "true";
+
+        let voice_model: String = self
+            .conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = 'voice_model'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap_or_else(|_| String::new());
+
+        let ollama_sidecar_host: String = self
+            .conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = 'ollama_sidecar_host'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap_or_else(|_| "127.0.0.1:11434".to_string());
+
+        let llm_timeout_secs: u32 = self
+            .conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = 'llm_timeout_secs'",
+                [],
+                |row| row.get(0),
+            )
+            .ok()
+            .and_then(|s: String| s.parse().ok())
+            .unwrap_or(120);
+
         log::info!("Loaded settings: provider={}, server={}, wake_word={}, api_base_url={}, model={}, vad_sensitivity={}, vad_timeout_ms={}, stt_model={}, voice={}, online_mode={}, search_backend={}, max_results={}, spotify_connected={}, spotify_auto_play={}, ha_connected={}, ha_auto_sync={}, ha_onboarding_dismissed={}",
                    llm_provider, server_address, wake_word_enabled, api_base_url, model_name, vad_sensitivity, vad_timeout_ms, stt_model_name, voice_preference, online_mode_enabled, search_backend, max_search_results, spotify_connected, spotify_auto_play_enabled, ha_connected, ha_auto_sync, ha_onboarding_dismissed);
 
@@ -842,6 +1411,19 @@ impl Database {
             ha_base_url,
             ha_auto_sync,
             ha_onboarding_dismissed,
+            context_token_budget,
+            llm_fallback_backends,
+            llm_context_length,
+            stt_language,
+            stt_partial_interval_ms,
+            wake_phrases,
+            vad_engine,
+            input_device_name,
+            barge_in_mode,
+            debug_capture_enabled,
+            voice_model,
+            ollama_sidecar_host,
+            llm_timeout_secs,
         })
     }
 
@@ -999,6 +1581,99 @@ impl Database {
             )
             .map_err(|e| format!("Failed to save ha_onboarding_dismissed: {}", e))?;
 
+        // Save LLM conversation settings
+        self.conn
+            .execute(
+                "UPDATE settings SET value = ?1 WHERE key = 'context_token_budget'",
+                params![settings.context_token_budget.to_string()],
+            )
+            .map_err(|e| format!("Failed to save context_token_budget: {}", e))?;
+
+        self.conn
+            .execute(
+                "UPDATE settings SET value = ?1 WHERE key = 'llm_fallback_backends'",
+                params![&settings.llm_fallback_backends],
+            )
+            .map_err(|e| format!("Failed to save llm_fallback_backends: {}", e))?;
+
+        self.conn
+            .execute(
+                "UPDATE settings SET value = ?1 WHERE key = 'llm_context_length'",
+                params![settings.llm_context_length.to_string()],
+            )
+            .map_err(|e| format!("Failed to save llm_context_length: {}", e))?;
+
+        self.conn
+            .execute(
+                "UPDATE settings SET value = ?1 WHERE key = 'stt_language'",
+                params![&settings.stt_language],
+            )
+            .map_err(|e| format!("Failed to save stt_language: {}", e))?;
+
+        self.conn
+            .execute(
+                "UPDATE settings SET value = ?1 WHERE key = 'stt_partial_interval_ms'",
+                params![settings.stt_partial_interval_ms.to_string()],
+            )
+            .map_err(|e| format!("Failed to save stt_partial_interval_ms: {}", e))?;
+
+        self.conn
+            .execute(
+                "UPDATE settings SET value = ?1 WHERE key = 'wake_phrases'",
+                params![&settings.wake_phrases],
+            )
+            .map_err(|e| format!("Failed to save wake_phrases: {}", e))?;
+
+        self.conn
+            .execute(
+                "UPDATE settings SET value = ?1 WHERE key = 'vad_engine'",
+                params![&settings.vad_engine],
+            )
+            .map_err(|e| format!("Failed to save vad_engine: {}", e))?;
+
+        self.conn
+            .execute(
+                "UPDATE settings SET value = ?1 WHERE key = 'input_device_name'",
+                params![&settings.input_device_name],
+            )
+            .map_err(|e| format!("Failed to save input_device_name: {}", e))?;
+
+        self.conn
+            .execute(
+                "UPDATE settings SET value = ?1 WHERE key = 'barge_in_mode'",
+                params![&settings.barge_in_mode],
+            )
+            .map_err(|e| format!("Failed to save barge_in_mode: {}", e))?;
+
+        let debug_capture_enabled_str = if settings.debug_capture_enabled { "true" } else { "false" };
+        self.conn
+            .execute(
+                "UPDATE settings SET value = ?1 WHERE key = 'debug_capture_enabled'",
+                params![debug_capture_enabled_str],
+            )
+            .map_err(|e| format!("Failed to save debug_capture_enabled: {}", e))?;
+
+        self.conn
+            .execute(
+                "UPDATE settings SET value = ?1 WHERE key = 'voice_model'",
+                params![&settings.voice_model],
+            )
+            .map_err(|e| format!("Failed to save voice_model: {}", e))?;
+
+        self.conn
+            .execute(
+                "UPDATE settings SET value = ?1 WHERE key = 'ollama_sidecar_host'",
+                params![&settings.ollama_sidecar_host],
+            )
+            .map_err(|e| format!("Failed to save ollama_sidecar_host: {}", e))?;
+
+        self.conn
+            .execute(
+                "UPDATE settings SET value = ?1 WHERE key = 'llm_timeout_secs'",
+                params![settings.llm_timeout_secs.to_string()],
+            )
+            .map_err(|e| format!("Failed to save llm_timeout_secs: {}", e))?;
+
         log::info!("Saved settings: provider={}, server={}, wake_word={}, api_base_url={}, model={}, vad_sensitivity={}, vad_timeout_ms={}, stt_model={}, voice={}, online_mode={}, search_backend={}, max_results={}, spotify_connected={}, spotify_auto_play={}, ha_connected={}, ha_auto_sync={}, ha_onboarding_dismissed={}",
                    settings.llm_provider, settings.server_address, settings.wake_word_enabled,
                    settings.api_base_url, settings.model_name, settings.vad_sensitivity, settings.vad_timeout_ms, settings.stt_model_name, settings.voice_preference, settings.online_mode_enabled, settings.search_backend, settings.max_search_results, settings.spotify_connected, settings.spotify_auto_play_enabled, settings.ha_connected, settings.ha_auto_sync, settings.ha_onboarding_dismissed);
@@ -1152,4 +1827,65 @@ mod tests {
         assert_eq!(db.count_conversations().unwrap(), 0);
         assert_eq!(db.count_messages().unwrap(), 0); // CASCADE delete
     }
+
+    #[test]
+    fn test_persona_crud_and_conversation_attachment() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path().to_path_buf()).unwrap();
+
+        let mut persona = Persona {
+            id: 0,
+            name: "Coder".to_string(),
+            system_prompt: "You are a senior Rust engineer.".to_string(),
+            temperature: 0.2,
+            max_tokens: 4096,
+            top_p: Some(0.9),
+            stop: vec!["</answer>".to_string()],
+            model_name: Some("qwen2.5-coder".to_string()),
+            created_at: String::new(),
+        };
+        let persona_id = db.create_persona(&persona).unwrap();
+
+        let conv_id = db.create_conversation(Some("Test".to_string())).unwrap();
+        assert!(db.get_conversation_persona(conv_id).unwrap().is_none());
+
+        db.set_conversation_persona(conv_id, Some(persona_id)).unwrap();
+        let attached = db.get_conversation_persona(conv_id).unwrap().unwrap();
+        assert_eq!(attached.max_tokens, 4096);
+        assert_eq!(attached.top_p, Some(0.9));
+        assert_eq!(attached.stop, vec!["</answer>".to_string()]);
+        assert_eq!(attached.model_name.as_deref(), Some("qwen2.5-coder"));
+        assert_eq!(db.load_conversations().unwrap()[0].persona_id, Some(persona_id));
+
+        persona.id = persona_id;
+        persona.max_tokens = 2048;
+        persona.model_name = None;
+        db.update_persona(&persona).unwrap();
+        let updated = db.get_persona(persona_id).unwrap();
+        assert_eq!(updated.max_tokens, 2048);
+        assert!(updated.model_name.is_none());
+
+        db.delete_persona(persona_id).unwrap();
+        assert!(db.load_personas().unwrap().is_empty());
+        assert!(db.get_conversation_persona(conv_id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_conversation_summary_upsert_and_cascade() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = Database::new(temp_file.path().to_path_buf()).unwrap();
+
+        let conv_id = db.create_conversation(Some("Test".to_string())).unwrap();
+        assert!(db.get_conversation_summary(conv_id).unwrap().is_none());
+
+        db.save_conversation_summary(conv_id, "User asked about the weather.", 4).unwrap();
+        db.save_conversation_summary(conv_id, "User asked about the weather and trains.", 8).unwrap();
+
+        let summary = db.get_conversation_summary(conv_id).unwrap().unwrap();
+        assert_eq!(summary.summary, "User asked about the weather and trains.");
+        assert_eq!(summary.summarized_through_id, 8);
+
+        db.delete_conversation(conv_id).unwrap();
+        assert!(db.get_conversation_summary(conv_id).unwrap().is_none());
+    }
 }
@@ -0,0 +1,353 @@
+//! Debug Capture of Utterances
+//!
+//! Opt-in diagnostics (`debug_capture_enabled` setting): every recording the voice
+//! pipeline finishes is written to the captures folder as a 16 kHz mono WAV file with
+//! a JSON sidecar holding the transcription, the VAD parameters, energy statistics
+//! and the speaker match. Only the newest `MAX_CAPTURES` are kept.
+//!
+//! `replay_debug_capture` runs STT and speaker identification again on a saved
+//! capture, optionally with different VAD parameters, so misrecognitions and
+//! `vad_sensitivity` tuning can be reproduced.
+
+use crate::native_voice::SpeakerInfo;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Captures kept on disk (oldest are deleted first)
+pub const MAX_CAPTURES: usize = 50;
+
+const SAMPLE_RATE: u32 = 16000;
+const FRAME_SAMPLES: usize = 512; // 32ms, same as the capture chunk size
+
+pub type DebugCaptureState = Arc<DebugCapture>;
+
+/// End-of-speech detection settings a recording was made with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VadParameters {
+    pub sensitivity: f32,
+    pub timeout_ms: u32,
+    pub engine: String,
+}
+
+/// Level statistics of a recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnergyStats {
+    pub rms: f32,
+    pub peak: f32,
+    /// Share of 32ms frames louder than the VAD sensitivity (0.0-1.0)
+    pub speech_frame_ratio: f32,
+}
+
+impl EnergyStats {
+    pub fn measure(samples: &[f32], vad_sensitivity: f32) -> Self {
+        let frame_rms = |frame: &[f32]| (frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32).sqrt();
+
+        let frames = samples.chunks(FRAME_SAMPLES).count();
+        let loud_frames = samples
+            .chunks(FRAME_SAMPLES)
+            .filter(|frame| frame_rms(frame) > vad_sensitivity)
+            .count();
+
+        Self {
+            rms: if samples.is_empty() { 0.0 } else { frame_rms(samples) },
+            peak: samples.iter().fold(0.0, |peak: f32, s| peak.max(s.abs())),
+            speech_frame_ratio: if frames == 0 { 0.0 } else { loud_frames as f32 / frames as f32 },
+        }
+    }
+}
+
+/// JSON sidecar of a capture
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureMetadata {
+    /// File name without extension (sortable timestamp)
+    pub id: String,
+    pub captured_at: String,
+    pub duration_seconds: f32,
+    pub transcription: Option<String>,
+    pub language: Option<String>,
+    /// Why transcription failed (e.g., "No speech detected in audio")
+    pub error: Option<String>,
+    pub vad: VadParameters,
+    pub energy: EnergyStats,
+    pub speaker: Option<SpeakerInfo>,
+}
+
+/// Writes, lists and loads captures in a rotating folder
+pub struct DebugCapture {
+    dir: PathBuf,
+    max_captures: usize,
+    enabled: AtomicBool,
+    /// Sidecar of the latest capture, completed with the speaker match afterwards
+    last_sidecar: Mutex<Option<PathBuf>>,
+}
+
+impl DebugCapture {
+    pub fn new(dir: PathBuf, max_captures: usize, enabled: bool) -> Self {
+        Self {
+            dir,
+            max_captures: max_captures.max(1),
+            enabled: AtomicBool::new(enabled),
+            last_sidecar: Mutex::new(None),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        if self.enabled.swap(enabled, Ordering::Relaxed) != enabled {
+            log::info!("Debug capture {} ({:?})", if enabled { "enabled" } else { "disabled" }, self.dir);
+        }
+    }
+
+    /// Save a finished recording with its transcription result
+    ///
+    /// # Arguments
+    /// * `samples` - The recording (16kHz mono)
+    /// * `transcription` - Text and language, or the transcription error
+    /// * `vad` - Settings the recording was made with
+    pub fn save(
+        &self,
+        samples: &[f32],
+        transcription: Result<(&str, Option<&str>), &str>,
+        vad: VadParameters,
+    ) -> Result<CaptureMetadata, String> {
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create capture directory {:?}: {}", self.dir, e))?;
+
+        let now = chrono::Local::now();
+        let metadata = CaptureMetadata {
+            id: self.unused_id(&now.format("%Y%m%d-%H%M%S-%6f").to_string()),
+            captured_at: now.to_rfc3339(),
+            duration_seconds: samples.len() as f32 / SAMPLE_RATE as f32,
+            transcription: transcription.ok().map(|(text, _)| text.to_string()),
+            language: transcription.ok().and_then(|(_, language)| language.map(|l| l.to_string())),
+            error: transcription.err().map(|e| e.to_string()),
+            energy: EnergyStats::measure(samples, vad.sensitivity),
+            vad,
+            speaker: None,
+        };
+
+        write_wav(&self.dir.join(format!("{}.wav", metadata.id)), samples)?;
+        let sidecar = self.dir.join(format!("{}.json", metadata.id));
+        write_sidecar(&sidecar, &metadata)?;
+        if let Ok(mut last) = self.last_sidecar.lock() {
+            *last = Some(sidecar);
+        }
+
+        self.rotate();
+        log::debug!("Saved debug capture {} ({:.1}s)", metadata.id, metadata.duration_seconds);
+        Ok(metadata)
+    }
+
+    /// Add the speaker match to the latest capture
+    pub fn record_speaker(&self, speaker: Option<SpeakerInfo>) {
+        let Some(sidecar) = self.last_sidecar.lock().ok().and_then(|mut last| last.take()) else {
+            return;
+        };
+
+        let result = read_sidecar(&sidecar).and_then(|mut metadata| {
+            metadata.speaker = speaker;
+            write_sidecar(&sidecar, &metadata)
+        });
+        if let Err(e) = result {
+            log::warn!("Failed to record speaker in debug capture: {}", e);
+        }
+    }
+
+    /// Saved captures, newest first
+    pub fn list(&self) -> Result<Vec<CaptureMetadata>, String> {
+        let mut captures: Vec<CaptureMetadata> = self
+            .sidecars()?
+            .iter()
+            .filter_map(|path| match read_sidecar(path) {
+                Ok(metadata) => Some(metadata),
+                Err(e) => {
+                    log::warn!("Skipping debug capture: {}", e);
+                    None
+                }
+            })
+            .collect();
+        captures.reverse();
+        Ok(captures)
+    }
+
+    /// Audio (16kHz mono) and sidecar of a saved capture
+    pub fn load(&self, id: &str) -> Result<(Vec<f32>, CaptureMetadata), String> {
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(format!("Invalid capture id '{}'", id));
+        }
+
+        let metadata = read_sidecar(&self.dir.join(format!("{}.json", id)))?;
+        let samples = read_wav(&self.dir.join(format!("{}.wav", id)))?;
+        Ok((samples, metadata))
+    }
+
+    /// Sidecar paths, oldest first
+    fn sidecars(&self) -> Result<Vec<PathBuf>, String> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(format!("Failed to read capture directory {:?}: {}", self.dir, e)),
+        };
+
+        let mut sidecars: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("json"))
+            .collect();
+        sidecars.sort_by(|a, b| a.file_stem().cmp(&b.file_stem()));
+        Ok(sidecars)
+    }
+
+    /// Delete the oldest captures beyond `max_captures`
+    fn rotate(&self) {
+        let sidecars = match self.sidecars() {
+            Ok(sidecars) => sidecars,
+            Err(e) => {
+                log::warn!("Failed to rotate debug captures: {}", e);
+                return;
+            }
+        };
+
+        let excess = sidecars.len().saturating_sub(self.max_captures);
+        for sidecar in &sidecars[..excess] {
+            for path in [sidecar.with_extension("wav"), sidecar.clone()] {
+                if let Err(e) = std::fs::remove_file(&path) {
+                    log::warn!("Failed to delete old debug capture {:?}: {}", path, e);
+                }
+            }
+        }
+    }
+
+    /// Capture ids are timestamps; add a suffix in the unlikely case of a clash
+    fn unused_id(&self, timestamp: &str) -> String {
+        let mut id = timestamp.to_string();
+        let mut suffix = 1;
+        while self.dir.join(format!("{}.json", id)).exists() {
+            id = format!("{}-{}", timestamp, suffix);
+            suffix += 1;
+        }
+        id
+    }
+}
+
+fn write_wav(path: &Path, samples: &[f32]) -> Result<(), String> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+
+    let mut writer = hound::WavWriter::create(path, spec)
+        .map_err(|e| format!("Failed to create WAV file {:?}: {}", path, e))?;
+    for &sample in samples {
+        writer
+            .write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+            .map_err(|e| format!("Failed to write WAV sample: {}", e))?;
+    }
+    writer
+        .finalize()
+        .map_err(|e| format!("Failed to finalize WAV file {:?}: {}", path, e))
+}
+
+fn read_wav(path: &Path) -> Result<Vec<f32>, String> {
+    let mut reader = hound::WavReader::open(path)
+        .map_err(|e| format!("Failed to open WAV file {:?}: {}", path, e))?;
+
+    let spec = reader.spec();
+    if spec.sample_rate != SAMPLE_RATE || spec.channels != 1 || spec.bits_per_sample != 16 {
+        return Err(format!("Unexpected capture format in {:?}: {:?}", path, spec));
+    }
+
+    reader
+        .samples::<i16>()
+        .map(|sample| sample.map(|s| s as f32 / i16::MAX as f32))
+        .collect::<Result<_, _>>()
+        .map_err(|e| format!("Failed to read WAV file {:?}: {}", path, e))
+}
+
+fn write_sidecar(path: &Path, metadata: &CaptureMetadata) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(metadata)
+        .map_err(|e| format!("Failed to serialize capture metadata: {}", e))?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+fn read_sidecar(path: &Path) -> Result<CaptureMetadata, String> {
+    let json = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+    serde_json::from_str(&json).map_err(|e| format!("Invalid capture metadata in {:?}: {}", path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vad() -> VadParameters {
+        VadParameters {
+            sensitivity: 0.02,
+            timeout_ms: 1280,
+            engine: "energy".to_string(),
+        }
+    }
+
+    fn capture_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aura-debug-capture-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_energy_stats() {
+        // 10 loud frames, 30 silent ones
+        let mut samples = vec![0.5; FRAME_SAMPLES * 10];
+        samples.extend(vec![0.0; FRAME_SAMPLES * 30]);
+
+        let stats = EnergyStats::measure(&samples, 0.02);
+        assert_eq!(stats.peak, 0.5);
+        assert!((stats.rms - 0.25).abs() < 1e-4);
+        assert!((stats.speech_frame_ratio - 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_save_load_and_record_speaker() {
+        let dir = capture_dir("roundtrip");
+        let capture = DebugCapture::new(dir.clone(), MAX_CAPTURES, true);
+        let samples: Vec<f32> = (0..16000).map(|i| (i as f32 * 0.01).sin() * 0.3).collect();
+
+        let saved = capture.save(&samples, Ok(("turn on the lights", Some("en"))), vad()).unwrap();
+        capture.record_speaker(Some(SpeakerInfo {
+            user_id: Some(1),
+            user_name: Some("Sam".to_string()),
+            similarity_score: 0.9,
+            identified: true,
+        }));
+
+        let (loaded, metadata) = capture.load(&saved.id).unwrap();
+        assert_eq!(loaded.len(), samples.len());
+        assert!(loaded.iter().zip(&samples).all(|(a, b)| (a - b).abs() < 1e-3));
+        assert_eq!(metadata.transcription.as_deref(), Some("turn on the lights"));
+        assert_eq!(metadata.language.as_deref(), Some("en"));
+        assert_eq!(metadata.speaker.and_then(|s| s.user_name).as_deref(), Some("Sam"));
+
+        assert!(capture.load("../settings").is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_rotation_keeps_newest() {
+        let dir = capture_dir("rotation");
+        let capture = DebugCapture::new(dir.clone(), 3, true);
+
+        let ids: Vec<String> = (0..5)
+            .map(|_| capture.save(&[0.0; 160], Err("No speech detected in audio"), vad()).unwrap().id)
+            .collect();
+
+        let listed: Vec<String> = capture.list().unwrap().into_iter().map(|m| m.id).collect();
+        assert_eq!(listed, ids[2..].iter().rev().cloned().collect::<Vec<_>>());
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 6);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
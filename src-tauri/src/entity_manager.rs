@@ -6,7 +6,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{watch, RwLock};
 use chrono::{DateTime, Utc};
 
 /// Represents a Home Assistant entity
@@ -73,6 +73,9 @@ pub struct EntityManager {
     // Indexes for fast lookups
     area_index: Arc<RwLock<HashMap<String, Vec<String>>>>,     // area_id -> entity_ids
     domain_index: Arc<RwLock<HashMap<String, Vec<String>>>>,   // domain -> entity_ids
+
+    // Number of completed full syncs (watched by consumers of the entity list)
+    syncs: watch::Sender<u64>,
 }
 
 impl EntityManager {
@@ -82,6 +85,7 @@ impl EntityManager {
             entities: Arc::new(RwLock::new(HashMap::new())),
             area_index: Arc::new(RwLock::new(HashMap::new())),
             domain_index: Arc::new(RwLock::new(HashMap::new())),
+            syncs: watch::channel(0).0,
         }
     }
 
@@ -104,10 +108,17 @@ impl EntityManager {
         // Rebuild indexes
         self.build_indexes().await;
 
+        self.syncs.send_modify(|count| *count += 1);
+
         log::info!("Entity sync complete");
         Ok(())
     }
 
+    /// Get notified after every full sync
+    pub fn subscribe_syncs(&self) -> watch::Receiver<u64> {
+        self.syncs.subscribe()
+    }
+
     /// Handle a state_changed event from WebSocket
     ///
     /// Updates a single entity in the cache and updates indexes if needed.
@@ -329,6 +340,7 @@ mod tests {
     #[tokio::test]
     async fn test_sync_entities() {
         let manager = EntityManager::new();
+        let mut syncs = manager.subscribe_syncs();
 
         let entities = vec![
             create_test_entity("light.kitchen", "on", Some("kitchen")),
@@ -339,6 +351,8 @@ mod tests {
         manager.sync_entities(entities).await.unwrap();
 
         assert_eq!(manager.get_entity_count().await, 3);
+        assert!(syncs.has_changed().unwrap());
+        assert_eq!(*syncs.borrow_and_update(), 1);
     }
 
     #[tokio::test]
@@ -1,10 +1,18 @@
 mod native_voice;
+mod audio_convert;
+mod barge_in;
+mod model_cache;
+mod partial_transcript;
+mod vad;
 mod tts;
 mod llm;
+mod prompt_builder;
+mod summarizer;
 mod database;
 mod secrets;
 mod error;
 mod ollama_sidecar;
+mod ollama_api;
 mod web_search;
 mod spotify_auth;
 mod spotify_client;
@@ -13,18 +21,37 @@ mod entity_manager;
 mod ha_client;
 mod smarthome_intent;
 mod voice_biometrics;
-
-use native_voice::{NativeVoicePipeline, TranscriptionResult, SpeakerInfo};
+mod wake_word;
+mod tools;
+mod sentence_splitter;
+mod stt_vocabulary;
+mod debug_capture;
+mod playback;
+mod piper_worker;
+mod piper_config;
+mod voice_catalog;
+
+use native_voice::{NativeVoicePipeline, TranscriptionResult, SpeakerInfo, WhisperCache};
 use tts::TextToSpeech;
-use llm::LLMEngine;
+use barge_in::PlaybackMonitor;
+use playback::{PlaybackController, PlaybackStatus};
+use piper_config::{SynthesisOptions, VoiceConfig};
+use voice_catalog::{VoiceCatalog, VoiceCatalogState, VoiceInfo};
+use stt_vocabulary::{SttVocabulary, SttVocabularyState};
+use sentence_splitter::SentenceSplitter;
+use debug_capture::{CaptureMetadata, DebugCapture, DebugCaptureState, EnergyStats, VadParameters};
+use llm::{LLMEngine, GenerationCanceller, GenerationParams, LlmResponse, LlmBackendConfig, BackendStatus};
+use prompt_builder::PromptContext;
 use ollama_sidecar::OllamaSidecar;
-use database::{Database, DatabaseState, Conversation, Message, Settings, UserHAShortcut, UserHAPreferences, get_database_path};
+use ollama_api::{OllamaClient, OllamaModel, OllamaModelInfo, RunningModel};
+use database::{Database, DatabaseState, Conversation, Message, Persona, Settings, UserHAShortcut, UserHAPreferences, UserTtsPreferences, get_database_path};
 use voice_biometrics::{VoiceBiometrics, UserProfile};
 use error::AuraError;
+use tools::{ToolRegistry, ToolRegistryState, HomeAssistantServiceTool, SpotifyPlaybackTool, WebSearchTool};
 use std::sync::Arc;
 use tokio::sync::Mutex as TokioMutex;
 use std::sync::Mutex as StdMutex;
-use tauri::{Manager, State, Emitter};
+use tauri::{Manager, State, Emitter, Listener};
 use serde::Serialize;
 
 // Type aliases for state management
@@ -43,53 +70,94 @@ fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
-#[tauri::command]
-async fn handle_user_prompt(
-    prompt: String,
-    llm_engine: State<'_, Arc<TokioMutex<LLMEngine>>>,
-    db: State<'_, DatabaseState>,
-) -> Result<String, AuraError> {
-    log::info!("Tauri command: handle_user_prompt called with: '{}'", prompt);
+/// Everything needed to send one user prompt to the LLM
+struct PreparedPrompt {
+    settings: Settings,
+    /// Prior turns of the conversation not covered by `summary` (excluding the current prompt)
+    history: Vec<Message>,
+    /// Rolling summary of the conversation's older turns
+    summary: Option<String>,
+    /// Web search results for the prompt when online mode is enabled
+    search_context: Option<String>,
+    /// Persona attached to the conversation (None = default assistant)
+    persona: Option<Persona>,
+}
 
-    // Load settings to check if online mode is enabled
-    let settings = {
+impl PreparedPrompt {
+    /// Generation parameters for this prompt (persona overrides or built-in defaults)
+    fn generation_params(&self) -> GenerationParams {
+        self.persona.as_ref().map(GenerationParams::from).unwrap_or_default()
+    }
+
+    /// History, summary and web search context for the prompt builder
+    fn context(&self) -> PromptContext<'_> {
+        PromptContext {
+            history: &self.history,
+            summary: self.summary.as_deref(),
+            search_context: self.search_context.as_deref(),
+        }
+    }
+
+    /// Apply this prompt's budget, persona and model context window to the engine
+    async fn configure(&self, llm: &mut LLMEngine) {
+        llm.set_context_token_budget(self.settings.context_token_budget);
+        llm.set_generation_params(self.generation_params());
+        llm.set_context_length(Some(self.settings.llm_context_length).filter(|&length| length > 0));
+        llm.refresh_context_length().await;
+    }
+}
+
+/// Load settings and conversation history, and perform RAG augmentation if enabled
+async fn prepare_prompt(
+    prompt: &str,
+    conversation_id: Option<i64>,
+    db: &DatabaseState,
+) -> Result<PreparedPrompt, AuraError> {
+    // Load settings (online mode, context budget), the conversation's persona, its prior
+    // turns and the rolling summary that replaces the oldest of them
+    let (settings, persona, history, summary) = {
         let database = db.lock().await;
-        database.load_settings()
-            .map_err(|e| AuraError::Internal(format!("Failed to load settings: {}", e)))?
+        let settings = database.load_settings()
+            .map_err(|e| AuraError::Internal(format!("Failed to load settings: {}", e)))?;
+
+        let persona = match conversation_id {
+            Some(id) => database.get_conversation_persona(id)
+                .map_err(|e| AuraError::Database(e))?,
+            None => None,
+        };
+
+        let mut history = match conversation_id {
+            Some(id) => database.load_messages(id)
+                .map_err(|e| AuraError::Database(e))?,
+            None => Vec::new(),
+        };
+
+        // The frontend saves the user message before calling us - don't replay it twice
+        if history.last().map(|m| m.role == "user" && m.content == prompt).unwrap_or(false) {
+            history.pop();
+        }
+
+        let summary = match conversation_id {
+            Some(id) => database.get_conversation_summary(id)
+                .map_err(|e| AuraError::Database(e))?,
+            None => None,
+        };
+        let history = summarizer::unsummarized(&history, summary.as_ref()).to_vec();
+
+        (settings, persona, history, summary.map(|s| s.summary))
     };
 
-    // Determine final prompt (with or without RAG)
-    let augmented_prompt = if settings.online_mode_enabled {
+    // Fetch web search context (RAG); the prompt builder fits it into the context window
+    let search_context = if settings.online_mode_enabled {
         log::info!("Online mode enabled, performing web search for RAG...");
 
         // Determine search backend from settings
-        let search_backend = match settings.search_backend.as_str() {
-            "searxng" => {
-                web_search::SearchBackend::SearXNG {
-                    instance_url: settings.searxng_instance_url.clone(),
-                }
-            }
-            "brave" => {
-                // Get Brave API key from settings
-                let api_key = settings.brave_search_api_key
-                    .clone()
-                    .ok_or_else(|| AuraError::Internal(
-                        "Brave Search selected but no API key configured. Please set it in Settings.".to_string()
-                    ))?;
-
-                web_search::SearchBackend::BraveSearch { api_key }
-            }
-            backend => {
-                log::warn!("Unknown search backend '{}', defaulting to SearXNG", backend);
-                web_search::SearchBackend::SearXNG {
-                    instance_url: "https://searx.be".to_string(),
-                }
-            }
-        };
+        let search_backend = web_search::SearchBackend::from_settings(&settings)
+            .map_err(|e| AuraError::Internal(e))?;
 
         // Perform web search
         match web_search::search_web(
-            &prompt,
+            prompt,
             search_backend,
             settings.max_search_results as usize,
         ).await {
@@ -97,41 +165,204 @@ async fn handle_user_prompt(
                 log::info!("✓ Web search successful: {} results found", results.len());
 
                 // Format search results as context
-                let context = web_search::format_search_context(&results);
-
-                // Augment prompt with search context
-                format!(
-                    "{}\nUser Question: {}",
-                    context,
-                    prompt
-                )
+                Some(web_search::format_search_context(&results))
             }
             Ok(_) => {
                 log::warn!("⚠ Web search returned 0 results, using offline mode");
-                prompt.clone()
+                None
             }
             Err(e) => {
                 log::warn!("⚠ Web search failed: {}, falling back to offline mode", e);
-                prompt.clone()
+                None
             }
         }
     } else {
         log::debug!("Online mode disabled, using offline LLM query");
-        prompt.clone()
+        None
     };
 
-    // Query LLM with (possibly augmented) prompt
-    let llm = llm_engine.inner().lock().await;
-    let result = llm.generate_response(&augmented_prompt).await
-        .map_err(|e| AuraError::Llm(e))?;
+    if let Some(persona) = &persona {
+        log::info!("Using persona '{}' for conversation {:?}", persona.name, conversation_id);
+    }
+
+    Ok(PreparedPrompt {
+        settings,
+        history,
+        summary,
+        search_context,
+        persona,
+    })
+}
+
+#[tauri::command]
+async fn handle_user_prompt(
+    prompt: String,
+    conversation_id: Option<i64>,
+    llm_engine: State<'_, Arc<TokioMutex<LLMEngine>>>,
+    db: State<'_, DatabaseState>,
+    tools: State<'_, ToolRegistryState>,
+) -> Result<LlmResponse, AuraError> {
+    log::info!("Tauri command: handle_user_prompt called with: '{}' (conversation: {:?})", prompt, conversation_id);
+
+    let prepared = prepare_prompt(&prompt, conversation_id, db.inner()).await?;
+
+    // Query LLM with (possibly augmented) prompt and the conversation so far,
+    // letting it call tools (Home Assistant, Spotify, web search) as needed
+    let result = {
+        let mut llm = llm_engine.inner().lock().await;
+        prepared.configure(&mut llm).await;
+        llm.generate_response_with_tools(&prepared.context(), &prompt, tools.inner().clone()).await
+            .map_err(|e| AuraError::Llm(e))?
+    };
+
+    spawn_conversation_summarizer(conversation_id, db.inner().clone(), llm_engine.inner().clone());
 
     Ok(result)
 }
 
+/// Streamed token payload for the frontend (emitted as `llm_stream_chunk`)
+#[derive(Serialize, Clone, Debug)]
+struct LlmStreamChunk {
+    conversation_id: Option<i64>,
+    /// Newly generated text (empty on the final event)
+    delta: String,
+    /// True once generation has finished, failed or been cancelled
+    done: bool,
+    /// Backend that answered (final event only, None if generation failed)
+    backend: Option<String>,
+}
+
+/// Same as `handle_user_prompt`, but streams the answer as it is generated
+///
+/// Each content delta is emitted as an `llm_stream_chunk` event tagged with the
+/// conversation ID, followed by a final event with `done: true`. The complete
+/// response is still returned so the frontend can persist it. `cancel_generation`
+/// stops the stream mid-way.
+///
+/// With `speak`, each sentence is also spoken as soon as it is complete (with the
+/// TTS preferences of `user_id`, if given), and the command returns once the spoken
+/// answer has finished playing.
+#[tauri::command]
+async fn handle_user_prompt_streaming(
+    app_handle: tauri::AppHandle,
+    prompt: String,
+    conversation_id: Option<i64>,
+    speak: Option<bool>,
+    user_id: Option<i64>,
+    llm_engine: State<'_, Arc<TokioMutex<LLMEngine>>>,
+    tts_engine: State<'_, Arc<TokioMutex<TextToSpeech>>>,
+    db: State<'_, DatabaseState>,
+) -> Result<LlmResponse, AuraError> {
+    log::info!("Tauri command: handle_user_prompt_streaming called with: '{}' (conversation: {:?}, speak: {:?})", prompt, conversation_id, speak);
+
+    let prepared = prepare_prompt(&prompt, conversation_id, db.inner()).await?;
+
+    // Speech runs on a blocking thread, fed with sentences while the answer streams in
+    let (speech_input, speech_task) = if speak.unwrap_or(false) {
+        let (sentence_tx, sentence_rx) = std::sync::mpsc::channel::<String>();
+        let options = load_synthesis_options(db.inner(), user_id).await;
+        let tts_engine = tts_engine.inner().clone();
+        let task = tokio::task::spawn_blocking(move || tts_engine.blocking_lock().speak_sentences(sentence_rx, &options));
+        (Some(Arc::new(StdMutex::new((SentenceSplitter::new(), Some(sentence_tx))))), Some(task))
+    } else {
+        (None, None)
+    };
+
+    let app_for_deltas = app_handle.clone();
+    let speech_for_deltas = speech_input.clone();
+    let on_delta = move |delta: &str| {
+        if let Some(speech_input) = &speech_for_deltas {
+            let (splitter, sentence_tx) = &mut *speech_input.lock().unwrap();
+            if let Some(sentence_tx) = sentence_tx {
+                for sentence in splitter.push(delta) {
+                    // Fails once a barge-in stopped the playback
+                    let _ = sentence_tx.send(sentence);
+                }
+            }
+        }
+
+        let chunk = LlmStreamChunk {
+            conversation_id,
+            delta: delta.to_string(),
+            done: false,
+            backend: None,
+        };
+        if let Err(e) = app_for_deltas.emit("llm_stream_chunk", chunk) {
+            log::error!("Failed to emit llm_stream_chunk: {}", e);
+        }
+    };
+
+    let result = {
+        let mut llm = llm_engine.inner().lock().await;
+        prepared.configure(&mut llm).await;
+        llm.generate_response_streaming(&prepared.context(), &prompt, on_delta).await
+    };
+
+    // Always close the stream so the UI can leave its "typing" state
+    let final_chunk = LlmStreamChunk {
+        conversation_id,
+        delta: String::new(),
+        done: true,
+        backend: result.as_ref().ok().map(|response| response.backend.clone()),
+    };
+    if let Err(e) = app_handle.emit("llm_stream_chunk", final_chunk) {
+        log::error!("Failed to emit final llm_stream_chunk: {}", e);
+    }
+
+    // Speak the unterminated end of the answer, then wait for the playback to finish
+    if let (Some(speech_input), Some(task)) = (speech_input, speech_task) {
+        {
+            let (splitter, sentence_tx) = &mut *speech_input.lock().unwrap();
+            // Dropping the sender ends the speech after the queued sentences
+            if let Some(sentence_tx) = sentence_tx.take() {
+                if let Some(rest) = splitter.finish() {
+                    let _ = sentence_tx.send(rest);
+                }
+            }
+        }
+
+        // The TTS engine is released once synthesis is done; only the playback is awaited
+        match task.await {
+            Ok(Ok(finished)) => finished.wait().await,
+            Ok(Err(e)) => log::error!("✗ Failed to speak streamed response: {}", e),
+            Err(e) => log::error!("✗ Speech task panicked: {}", e),
+        }
+    }
+
+    if result.is_ok() {
+        spawn_conversation_summarizer(conversation_id, db.inner().clone(), llm_engine.inner().clone());
+    }
+
+    result.map_err(|e| AuraError::Llm(e))
+}
+
+/// Fold the oldest turns of a long conversation into its rolling summary
+///
+/// Runs in the background after a response so the user never waits for it.
+fn spawn_conversation_summarizer(
+    conversation_id: Option<i64>,
+    db: DatabaseState,
+    llm_engine: Arc<TokioMutex<LLMEngine>>,
+) {
+    let conversation_id = match conversation_id {
+        Some(id) => id,
+        None => return,
+    };
+
+    tauri::async_runtime::spawn(async move {
+        match summarizer::summarize_if_needed(conversation_id, &db, &llm_engine).await {
+            Ok(true) => log::info!("✓ Updated summary of conversation {}", conversation_id),
+            Ok(false) => {}
+            Err(e) => log::warn!("⚠ Failed to summarize conversation {}: {}", conversation_id, e),
+        }
+    });
+}
+
 #[tauri::command]
 async fn listen_and_transcribe(
     voice_pipeline: State<'_, Arc<StdMutex<NativeVoicePipeline>>>,
     voice_biometrics: State<'_, VoiceBiometricsState>,
+    debug_capture: State<'_, DebugCaptureState>,
 ) -> Result<TranscriptionResult, AuraError> {
     log::info!("Tauri command: listen_and_transcribe called (Push-to-Talk)");
 
@@ -139,12 +370,12 @@ async fn listen_and_transcribe(
     // (required for audio thread compatibility)
     let voice_pipeline_clone = voice_pipeline.inner().clone();
 
-    let (transcription_text, audio_samples, audio_metadata) = tokio::task::spawn_blocking(move || {
+    let (transcript, audio_samples, audio_metadata) = tokio::task::spawn_blocking(move || {
         let pipeline = voice_pipeline_clone.lock()
             .map_err(|e| AuraError::Internal(format!("Failed to lock voice pipeline: {}", e)))?;
 
-        // Perform transcription - now returns both text and audio samples
-        let (text, samples) = pipeline.start_transcription()
+        // Perform transcription - now returns both the transcript and audio samples
+        let (transcript, samples) = pipeline.start_transcription()
             .map_err(|e| AuraError::VoicePipeline(e))?;
 
         // Calculate audio metadata from the samples
@@ -157,11 +388,11 @@ async fn listen_and_transcribe(
         };
         let metadata = (samples.len(), duration, avg_energy);
 
-        Ok::<_, AuraError>((text, samples, metadata))
+        Ok::<_, AuraError>((transcript, samples, metadata))
     }).await
     .map_err(|e| AuraError::Internal(format!("Task panicked: {}", e)))??;
 
-    log::info!("Transcription completed: \"{}\"", transcription_text);
+    log::info!("Transcription completed: \"{}\" (language: {:?})", transcript.text, transcript.language);
 
     // **AC1: Pipeline Hook** - Perform speaker identification asynchronously 
     // **AC3: Asynchronous Operation** - Non-blocking speaker ID with timeout
@@ -174,15 +405,15 @@ async fn listen_and_transcribe(
             std::time::Duration::from_millis(500), // 500ms timeout for speaker ID
             voice_biometrics.identify_speaker(&audio_samples)
         ).await {
-            Ok(Ok(Some(user_profile))) => {
+            Ok(Ok(Some(speaker))) => {
                 let identification_time = identification_start.elapsed();
                 log::info!("✅ Speaker identified: {} (took {:.1}ms)", 
-                          user_profile.name, identification_time.as_millis());
+                          speaker.profile.name, identification_time.as_millis());
                 
                 Some(SpeakerInfo {
-                    user_id: Some(user_profile.id),
-                    user_name: Some(user_profile.name),
-                    similarity_score: 0.85, // TODO: Get actual similarity score from identify_speaker
+                    user_id: Some(speaker.profile.id),
+                    user_name: Some(speaker.profile.name),
+                    similarity_score: speaker.similarity,
                     identified: true,
                 })
             }
@@ -213,11 +444,17 @@ async fn listen_and_transcribe(
         None
     };
 
+    // The pipeline saved the recording already; complete it with the speaker match
+    if debug_capture.is_enabled() {
+        debug_capture.record_speaker(speaker_info.clone());
+    }
+
     // **AC2: Context Passing** - Enhanced result with speaker information
     let enhanced_result = TranscriptionResult {
-        text: transcription_text.clone(),
+        text: transcript.text,
         duration_seconds: audio_metadata.1,
         sample_count: audio_metadata.0,
+        language: transcript.language,
         speaker_info,
     };
 
@@ -233,31 +470,422 @@ async fn listen_and_transcribe(
     Ok(enhanced_result)
 }
 
+/// Shortest segment worth running speaker identification on
+const MIN_SPEAKER_SEGMENT_SECONDS: f32 = 1.0;
+
+/// Transcribe an audio file (WAV, FLAC or MP3) with the configured Whisper model
+///
+/// Returns timestamped segments; with voice biometrics loaded, each segment long
+/// enough for identification is labelled with its speaker.
+#[tauri::command]
+async fn transcribe_file(
+    path: String,
+    voice_pipeline: State<'_, Arc<StdMutex<NativeVoicePipeline>>>,
+    voice_biometrics: State<'_, VoiceBiometricsState>,
+) -> Result<native_voice::FileTranscription, AuraError> {
+    log::info!("Tauri command: transcribe_file called ({})", path);
+
+    let transcriber = voice_pipeline.inner().lock()
+        .map_err(|e| AuraError::Internal(format!("Failed to lock voice pipeline: {}", e)))?
+        .transcriber()
+        .map_err(|e| AuraError::VoicePipeline(e))?;
+
+    // Decoding and Whisper are CPU-bound, and long files take a while
+    let (samples, mut segments, language) = tokio::task::spawn_blocking(move || {
+        let samples = native_voice::load_audio_file(std::path::Path::new(&path))?;
+        let (segments, language) = transcriber.transcribe(&samples, false)?;
+        Ok::<_, String>((samples, segments, language))
+    }).await
+    .map_err(|e| AuraError::Internal(format!("Task panicked: {}", e)))?
+    .map_err(|e| AuraError::VoicePipeline(e))?;
+
+    if voice_biometrics.is_model_loaded().await {
+        for segment in segments.iter_mut() {
+            if segment.end_seconds - segment.start_seconds < MIN_SPEAKER_SEGMENT_SECONDS {
+                continue;
+            }
+
+            let start = ((segment.start_seconds * 16000.0) as usize).min(samples.len());
+            let end = ((segment.end_seconds * 16000.0) as usize).clamp(start, samples.len());
+            segment.speaker = match identify_speaker_info(&voice_biometrics, &samples[start..end]).await {
+                Ok(speaker) => Some(speaker),
+                Err(e) => {
+                    log::warn!("Speaker identification failed for segment at {:.1}s: {}", segment.start_seconds, e);
+                    None
+                }
+            };
+        }
+    } else {
+        log::debug!("Speaker identification not available (model not loaded)");
+    }
+
+    let result = native_voice::FileTranscription {
+        text: segments.iter().map(|s| s.text.as_str()).collect::<Vec<_>>().join(" "),
+        language,
+        duration_seconds: samples.len() as f32 / 16000.0,
+        segments,
+    };
+
+    log::info!("File transcription complete: {} segments, {:.1}s, language: {:?}",
+               result.segments.len(), result.duration_seconds, result.language);
+    Ok(result)
+}
+
+/// Run speaker identification on offline audio (files, debug captures)
+async fn identify_speaker_info(voice_biometrics: &VoiceBiometrics, samples: &[f32]) -> Result<SpeakerInfo, String> {
+    match voice_biometrics.identify_speaker(samples).await {
+        Ok(Some(speaker)) => Ok(SpeakerInfo {
+            user_id: Some(speaker.profile.id),
+            user_name: Some(speaker.profile.name),
+            similarity_score: speaker.similarity,
+            identified: true,
+        }),
+        Ok(None) => Ok(SpeakerInfo {
+            user_id: None,
+            user_name: None,
+            similarity_score: 0.0,
+            identified: false,
+        }),
+        Err(e) => Err(format!("{:?}", e)),
+    }
+}
+
+/// List the saved debug captures, newest first
+#[tauri::command]
+async fn list_debug_captures(debug_capture: State<'_, DebugCaptureState>) -> Result<Vec<CaptureMetadata>, AuraError> {
+    debug_capture.list()
+        .map_err(|e| AuraError::VoicePipeline(e))
+}
+
+/// Result of replaying a debug capture
+#[derive(Serialize)]
+struct CaptureReplay {
+    /// Sidecar saved with the capture
+    capture: CaptureMetadata,
+    /// VAD parameters of the replay
+    vad: VadParameters,
+    energy: EnergyStats,
+    /// Speech the VAD finds in the capture with these parameters
+    speech_segments: Vec<vad::SpeechSegment>,
+    /// Audio the pipeline would have recorded: up to the end of the first speech
+    /// segment plus the silence timeout (the whole capture if no speech was found)
+    recorded_seconds: f32,
+    transcription: Option<String>,
+    language: Option<String>,
+    error: Option<String>,
+    speaker: Option<SpeakerInfo>,
+}
+
+/// Run VAD, STT and speaker identification again on a saved debug capture
+///
+/// Uses the VAD parameters the capture was recorded with unless overridden, so the
+/// effect of a different `vad_sensitivity` or `vad_timeout_ms` can be compared on
+/// the same audio.
+#[tauri::command]
+async fn replay_debug_capture(
+    id: String,
+    vad_sensitivity: Option<f32>,
+    vad_timeout_ms: Option<u32>,
+    voice_pipeline: State<'_, Arc<StdMutex<NativeVoicePipeline>>>,
+    voice_biometrics: State<'_, VoiceBiometricsState>,
+    debug_capture: State<'_, DebugCaptureState>,
+) -> Result<CaptureReplay, AuraError> {
+    log::info!("Tauri command: replay_debug_capture called ({}, vad_sensitivity: {:?}, vad_timeout_ms: {:?})",
+               id, vad_sensitivity, vad_timeout_ms);
+
+    let (samples, capture) = debug_capture.load(&id)
+        .map_err(|e| AuraError::VoicePipeline(e))?;
+
+    let (transcriber, model_path) = {
+        let pipeline = voice_pipeline.inner().lock()
+            .map_err(|e| AuraError::Internal(format!("Failed to lock voice pipeline: {}", e)))?;
+        let transcriber = pipeline.transcriber()
+            .map_err(|e| AuraError::VoicePipeline(e))?;
+        (transcriber, pipeline.model_path().to_path_buf())
+    };
+
+    let vad_parameters = VadParameters {
+        sensitivity: vad_sensitivity.unwrap_or(capture.vad.sensitivity),
+        timeout_ms: vad_timeout_ms.unwrap_or(capture.vad.timeout_ms),
+        engine: capture.vad.engine.clone(),
+    };
+
+    // VAD and Whisper are CPU-bound
+    let replay_vad = vad_parameters.clone();
+    let (samples, speech_segments, recorded, transcription) = tokio::task::spawn_blocking(move || {
+        let mut detector = vad::create_vad(&replay_vad.engine, &model_path, Arc::new(StdMutex::new(replay_vad.sensitivity)));
+        let speech_segments = vad::detect_segments(detector.as_mut(), &samples, replay_vad.timeout_ms);
+
+        let recorded = speech_segments.first()
+            .map(|s| ((s.end_seconds * 1000.0 + replay_vad.timeout_ms as f32) * 16.0) as usize)
+            .unwrap_or(samples.len())
+            .min(samples.len());
+        let transcription = transcriber.transcribe(&samples[..recorded], false);
+
+        (samples, speech_segments, recorded, transcription)
+    }).await
+    .map_err(|e| AuraError::Internal(format!("Task panicked: {}", e)))?;
+
+    let (transcription, language, error) = match transcription {
+        Ok((segments, _)) if segments.is_empty() => (None, None, Some("No speech detected in audio".to_string())),
+        Ok((segments, language)) => (
+            Some(segments.iter().map(|s| s.text.as_str()).collect::<Vec<_>>().join(" ")),
+            language,
+            None,
+        ),
+        Err(e) => (None, None, Some(e)),
+    };
+
+    let speaker = if voice_biometrics.is_model_loaded().await && recorded > 0 {
+        match identify_speaker_info(&voice_biometrics, &samples[..recorded]).await {
+            Ok(speaker) => Some(speaker),
+            Err(e) => {
+                log::warn!("Speaker identification failed for capture {}: {}", id, e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    log::info!("Replayed capture {}: {} speech segment(s), transcription: {:?} (saved: {:?})",
+               id, speech_segments.len(), transcription, capture.transcription);
+
+    Ok(CaptureReplay {
+        energy: EnergyStats::measure(&samples, vad_parameters.sensitivity),
+        capture,
+        vad: vad_parameters,
+        speech_segments,
+        recorded_seconds: recorded as f32 / 16000.0,
+        transcription,
+        language,
+        error,
+        speaker,
+    })
+}
+
+/// Speak text, with the speaker and speech settings of `user_id` (if identified)
 #[tauri::command]
-async fn speak_text(text: String, tts_engine: State<'_, Arc<TokioMutex<TextToSpeech>>>) -> Result<(), AuraError> {
-    log::info!("Tauri command: speak_text called ({} chars)", text.len());
+async fn speak_text(
+    text: String,
+    user_id: Option<i64>,
+    tts_engine: State<'_, Arc<TokioMutex<TextToSpeech>>>,
+    db: State<'_, DatabaseState>,
+) -> Result<(), AuraError> {
+    log::info!("Tauri command: speak_text called ({} chars, user_id: {:?})", text.len(), user_id);
     log::info!("Text to speak: '{}'", if text.len() > 100 { format!("{}...", &text[..100]) } else { text.clone() });
 
-    let mut tts = tts_engine.inner().lock().await;
+    let options = load_synthesis_options(db.inner(), user_id).await;
+    let result = {
+        let mut tts = tts_engine.inner().lock().await;
+
+        log::info!("TTS engine locked, calling speak()...");
+        tts.speak(&text, &options).map_err(|e| AuraError::Tts(e))
+    };
+
+    // The TTS engine is free again: later requests queue behind this one, and the
+    // playback can be stopped, paused or skipped while we wait
+    match result {
+        Ok(finished) => {
+            finished.wait().await;
+            log::info!("✓ TTS speak() completed successfully");
+            Ok(())
+        }
+        Err(e) => {
+            log::error!("✗ TTS speak() failed: {}", e);
+            Err(e)
+        }
+    }
+}
+
+/// Sample rate, speakers (`speaker_id_map`) and default settings of the current voice
+#[tauri::command]
+async fn tts_get_voice_config(tts_engine: State<'_, Arc<TokioMutex<TextToSpeech>>>) -> Result<VoiceConfig, AuraError> {
+    Ok(tts_engine.inner().lock().await.voice_config().clone())
+}
+
+/// Stop the spoken answer and drop everything queued
+#[tauri::command]
+async fn tts_stop(player: State<'_, PlaybackController>) -> Result<(), AuraError> {
+    log::info!("Tauri command: tts_stop called");
+    player.stop();
+    Ok(())
+}
+
+/// Pause the spoken answer; returns false if nothing is playing
+#[tauri::command]
+async fn tts_pause(player: State<'_, PlaybackController>) -> Result<bool, AuraError> {
+    log::info!("Tauri command: tts_pause called");
+    Ok(player.pause())
+}
+
+/// Resume a paused answer; returns false if nothing is queued
+#[tauri::command]
+async fn tts_resume(player: State<'_, PlaybackController>) -> Result<bool, AuraError> {
+    log::info!("Tauri command: tts_resume called");
+    Ok(player.resume())
+}
+
+/// Skip to the next queued answer; returns false if nothing is playing
+#[tauri::command]
+async fn tts_skip(player: State<'_, PlaybackController>) -> Result<bool, AuraError> {
+    log::info!("Tauri command: tts_skip called");
+    Ok(player.skip())
+}
+
+#[tauri::command]
+async fn tts_get_status(player: State<'_, PlaybackController>) -> Result<PlaybackStatus, AuraError> {
+    Ok(player.status())
+}
+
+/// A catalog voice and whether it is the one speaking
+#[derive(Serialize)]
+struct CatalogVoice {
+    #[serde(flatten)]
+    voice: VoiceInfo,
+    active: bool,
+}
+
+/// List installed Piper voices (bundled and user-installed)
+#[tauri::command]
+async fn tts_list_voices(
+    voice_catalog: State<'_, VoiceCatalogState>,
+    tts_engine: State<'_, Arc<TokioMutex<TextToSpeech>>>,
+) -> Result<Vec<CatalogVoice>, AuraError> {
+    let active_model = tts_engine.inner().lock().await.model_path().to_path_buf();
+    let catalog = voice_catalog.inner().clone();
+    let voices = tokio::task::spawn_blocking(move || catalog.list())
+        .await
+        .map_err(|e| AuraError::Internal(format!("Voice scan task failed: {}", e)))?;
+
+    Ok(voices
+        .into_iter()
+        .map(|voice| CatalogVoice {
+            active: voice.model_path == active_model,
+            voice,
+        })
+        .collect())
+}
+
+/// Install a Piper voice from a local file or a URL
+///
+/// `source` is a `.tar.gz`/`.tgz`/`.tar` archive or a `.onnx` model (its `.onnx.json`
+/// must sit next to it, or at `<url>.json` for downloads). SHA-256 checksums are
+/// optional for local files; for URLs every downloaded file needs one: `sha256` for
+/// `source` and, for a `.onnx` model, `config_sha256` for its config. Download
+/// progress is reported with `voice_install_progress` events.
+#[tauri::command]
+async fn tts_install_voice(
+    app_handle: tauri::AppHandle,
+    source: String,
+    sha256: Option<String>,
+    config_sha256: Option<String>,
+    voice_catalog: State<'_, VoiceCatalogState>,
+) -> Result<VoiceInfo, AuraError> {
+    log::info!("Tauri command: tts_install_voice called (source: {})", source);
+
+    let sha256 = sha256.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    let config_sha256 = config_sha256.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    let catalog = voice_catalog.inner().clone();
+
+    if !(source.starts_with("http://") || source.starts_with("https://")) {
+        let path = std::path::PathBuf::from(source.trim());
+        return tokio::task::spawn_blocking(move || catalog.install(&path, sha256.as_deref(), config_sha256.as_deref()))
+            .await
+            .map_err(|e| AuraError::Internal(format!("Voice install task failed: {}", e)))?
+            .map_err(|e| AuraError::Tts(e));
+    }
+
+    let sha256 = sha256.ok_or_else(|| {
+        AuraError::Config("A SHA-256 checksum is required to install a voice from a URL".to_string())
+    })?;
+    let url = url::Url::parse(&source)
+        .map_err(|e| AuraError::Config(format!("Invalid voice URL: {}", e)))?;
+    let file_name = url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| AuraError::Config(format!("Voice URL has no file name: {}", url)))?
+        .to_string();
+    if file_name.ends_with(".onnx") && config_sha256.is_none() {
+        return Err(AuraError::Config(
+            "A SHA-256 checksum of the voice config (.onnx.json) is required to install a model from a URL".to_string(),
+        ));
+    }
 
-    log::info!("TTS engine locked, calling speak()...");
-    let result = tts.speak(&text)
-        .map_err(|e| AuraError::Tts(e));
+    // Per install, so concurrent installs don't delete each other's downloads
+    let download_dir = voice_catalog::download_dir();
+    std::fs::create_dir_all(&download_dir)
+        .map_err(|e| AuraError::Internal(format!("Failed to create download directory: {}", e)))?;
+    let download_path = download_dir.join(&file_name);
+
+    let result: Result<VoiceInfo, AuraError> = async {
+        download_with_progress(&app_handle, url.as_str(), &download_path, "voice_install_progress").await?;
+        if file_name.ends_with(".onnx") {
+            // Piper publishes each model's config next to it
+            let mut config_url = url.clone();
+            config_url.set_path(&format!("{}.json", url.path()));
+            download_with_progress(
+                &app_handle,
+                config_url.as_str(),
+                &download_path.with_extension("onnx.json"),
+                "voice_install_progress",
+            )
+            .await?;
+        }
 
-    match &result {
-        Ok(_) => log::info!("✓ TTS speak() completed successfully"),
-        Err(e) => log::error!("✗ TTS speak() failed: {}", e),
+        let path = download_path.clone();
+        tokio::task::spawn_blocking(move || catalog.install(&path, Some(&sha256), config_sha256.as_deref()))
+            .await
+            .map_err(|e| AuraError::Internal(format!("Voice install task failed: {}", e)))?
+            .map_err(|e| AuraError::Tts(e))
     }
+    .await;
 
+    let _ = std::fs::remove_dir_all(&download_dir);
     result
 }
 
+/// Switch the TTS engine to a catalog voice and remember it
+///
+/// Takes effect with the next sentence; audio already queued finishes in the old voice.
+#[tauri::command]
+async fn tts_set_voice(
+    voice_id: String,
+    voice_catalog: State<'_, VoiceCatalogState>,
+    tts_engine: State<'_, Arc<TokioMutex<TextToSpeech>>>,
+    db: State<'_, DatabaseState>,
+) -> Result<VoiceInfo, AuraError> {
+    log::info!("Tauri command: tts_set_voice called (voice: {})", voice_id);
+
+    let voice = voice_catalog
+        .find(&voice_id)
+        .ok_or_else(|| AuraError::Config(format!("Voice '{}' is not installed", voice_id)))?;
+
+    tts_engine.inner().lock().await
+        .switch_voice(voice.model_path.clone())
+        .map_err(|e| AuraError::Tts(e))?;
+
+    let db = db.inner().lock().await;
+    let mut settings = db.load_settings()
+        .map_err(|e| AuraError::Database(e))?;
+    settings.voice_model = voice.id.clone();
+    db.save_settings(&settings)
+        .map_err(|e| AuraError::Database(e))?;
+
+    log::info!("✓ TTS voice switched to {}", voice.id);
+    Ok(voice)
+}
+
 #[tauri::command]
-async fn cancel_generation(llm_engine: State<'_, Arc<TokioMutex<LLMEngine>>>) -> Result<(), AuraError> {
+async fn cancel_generation(generation_canceller: State<'_, GenerationCanceller>) -> Result<(), AuraError> {
     log::info!("Tauri command: cancel_generation called");
 
-    let llm = llm_engine.inner().lock().await;
-    llm.cancel_generation().await;
+    if generation_canceller.cancel() {
+        log::info!("✓ Generation task aborted successfully");
+    } else {
+        log::warn!("No active generation task to cancel");
+    }
 
     Ok(())
 }
@@ -281,6 +909,17 @@ async fn cancel_recording(voice_pipeline: State<'_, Arc<StdMutex<NativeVoicePipe
     Ok(())
 }
 
+#[tauri::command]
+async fn list_input_devices() -> Result<Vec<native_voice::InputDeviceInfo>, AuraError> {
+    log::info!("Tauri command: list_input_devices called");
+
+    // Device enumeration can block on some audio backends
+    tokio::task::spawn_blocking(native_voice::list_input_devices)
+        .await
+        .map_err(|e| AuraError::Internal(format!("Task panicked: {}", e)))?
+        .map_err(|e| AuraError::VoicePipeline(e))
+}
+
 // Database Commands
 
 #[tauri::command]
@@ -346,6 +985,80 @@ async fn update_conversation_title(
         .map_err(|e| AuraError::Database(e))
 }
 
+// Persona Commands
+
+#[tauri::command]
+async fn list_personas(db: State<'_, DatabaseState>) -> Result<Vec<Persona>, AuraError> {
+    log::info!("Tauri command: list_personas called");
+
+    let db = db.inner().lock().await;
+    db.load_personas()
+        .map_err(|e| AuraError::Database(e))
+}
+
+/// Validate user-provided persona fields before saving
+fn validate_persona(persona: &Persona) -> Result<(), AuraError> {
+    if persona.name.trim().is_empty() {
+        return Err(AuraError::Config("Persona name cannot be empty".to_string()));
+    }
+    if !(0.0..=2.0).contains(&persona.temperature) {
+        return Err(AuraError::Config("Temperature must be between 0.0 and 2.0".to_string()));
+    }
+    if persona.max_tokens == 0 {
+        return Err(AuraError::Config("Max tokens must be greater than 0".to_string()));
+    }
+    if let Some(top_p) = persona.top_p {
+        if !(0.0..=1.0).contains(&top_p) {
+            return Err(AuraError::Config("Top P must be between 0.0 and 1.0".to_string()));
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn create_persona(persona: Persona, db: State<'_, DatabaseState>) -> Result<i64, AuraError> {
+    log::info!("Tauri command: create_persona called with name: {}", persona.name);
+
+    validate_persona(&persona)?;
+
+    let db = db.inner().lock().await;
+    db.create_persona(&persona)
+        .map_err(|e| AuraError::Database(e))
+}
+
+#[tauri::command]
+async fn update_persona(persona: Persona, db: State<'_, DatabaseState>) -> Result<(), AuraError> {
+    log::info!("Tauri command: update_persona called for persona {}", persona.id);
+
+    validate_persona(&persona)?;
+
+    let db = db.inner().lock().await;
+    db.update_persona(&persona)
+        .map_err(|e| AuraError::Database(e))
+}
+
+#[tauri::command]
+async fn delete_persona(persona_id: i64, db: State<'_, DatabaseState>) -> Result<(), AuraError> {
+    log::info!("Tauri command: delete_persona called for persona {}", persona_id);
+
+    let db = db.inner().lock().await;
+    db.delete_persona(persona_id)
+        .map_err(|e| AuraError::Database(e))
+}
+
+#[tauri::command]
+async fn set_conversation_persona(
+    conversation_id: i64,
+    persona_id: Option<i64>,
+    db: State<'_, DatabaseState>
+) -> Result<(), AuraError> {
+    log::info!("Tauri command: set_conversation_persona called for conversation {} with persona {:?}", conversation_id, persona_id);
+
+    let db = db.inner().lock().await;
+    db.set_conversation_persona(conversation_id, persona_id)
+        .map_err(|e| AuraError::Database(e))
+}
+
 #[tauri::command]
 async fn generate_conversation_title(
     prompt: String,
@@ -358,7 +1071,9 @@ async fn generate_conversation_title(
         prompt.chars().take(150).collect::<String>()
     );
 
-    let llm = llm_engine.inner().lock().await;
+    // Titles always use the default assistant, not the last conversation's persona
+    let mut llm = llm_engine.inner().lock().await;
+    llm.set_generation_params(GenerationParams::default());
     let raw_title = llm.generate_response(&title_prompt).await
         .map_err(|e| AuraError::Llm(e))?;
 
@@ -409,7 +1124,17 @@ async fn save_settings(
     searxng_instance_url: String,
     brave_search_api_key: Option<String>,
     max_search_results: u32,
-    db: State<'_, DatabaseState>
+    context_token_budget: Option<u32>,
+    llm_context_length: Option<u32>,
+    stt_language: Option<String>,
+    stt_partial_interval_ms: Option<u32>,
+    wake_phrases: Option<String>,
+    vad_engine: Option<String>,
+    input_device_name: Option<String>,
+    barge_in_mode: Option<String>,
+    debug_capture_enabled: Option<bool>,
+    db: State<'_, DatabaseState>,
+    debug_capture: State<'_, DebugCaptureState>,
 ) -> Result<(), AuraError> {
     log::info!("Tauri command: save_settings called (provider: {}, server: {}, wake_word: {}, api_base_url: {}, model: {}, vad_sensitivity: {}, vad_timeout_ms: {}, stt_model: {}, voice: {}, online_mode: {}, search_backend: {}, max_results: {})",
                llm_provider, server_address, wake_word_enabled, api_base_url, model_name, vad_sensitivity, vad_timeout_ms, stt_model_name, voice_preference, online_mode_enabled, search_backend, max_search_results);
@@ -439,6 +1164,19 @@ async fn save_settings(
         ha_base_url: String::new(),
         ha_auto_sync: true,
         ha_onboarding_dismissed: false,
+        context_token_budget: 2048,
+        llm_fallback_backends: "[]".to_string(),
+        llm_context_length: 0,
+        stt_language: "auto".to_string(),
+        stt_partial_interval_ms: 500,
+        wake_phrases: r#"[{"phrase":"hey aura","sensitivity":0.75}]"#.to_string(),
+        vad_engine: "energy".to_string(),
+        input_device_name: String::new(),
+        barge_in_mode: "off".to_string(),
+        debug_capture_enabled: false,
+        voice_model: String::new(),
+        ollama_sidecar_host: "127.0.0.1:11434".to_string(),
+        llm_timeout_secs: 120,
     });
 
     let settings = Settings {
@@ -465,26 +1203,127 @@ async fn save_settings(
         ha_base_url: existing_settings.ha_base_url,
         ha_auto_sync: existing_settings.ha_auto_sync,
         ha_onboarding_dismissed: existing_settings.ha_onboarding_dismissed,
+        context_token_budget: context_token_budget.unwrap_or(existing_settings.context_token_budget),
+        llm_fallback_backends: existing_settings.llm_fallback_backends,
+        llm_context_length: llm_context_length.unwrap_or(existing_settings.llm_context_length),
+        stt_language: stt_language.unwrap_or(existing_settings.stt_language),
+        stt_partial_interval_ms: stt_partial_interval_ms.unwrap_or(existing_settings.stt_partial_interval_ms),
+        wake_phrases: wake_phrases.unwrap_or(existing_settings.wake_phrases),
+        vad_engine: vad_engine.unwrap_or(existing_settings.vad_engine),
+        input_device_name: input_device_name.unwrap_or(existing_settings.input_device_name),
+        barge_in_mode: barge_in_mode.unwrap_or(existing_settings.barge_in_mode),
+        debug_capture_enabled: debug_capture_enabled.unwrap_or(existing_settings.debug_capture_enabled),
+        voice_model: existing_settings.voice_model,
+        ollama_sidecar_host: existing_settings.ollama_sidecar_host,
+        llm_timeout_secs: existing_settings.llm_timeout_secs,
     };
 
-    db.save_settings(&settings)
-        .map_err(|e| AuraError::Database(e))
-}
+    db.save_settings(&settings)
+        .map_err(|e| AuraError::Database(e))?;
+
+    debug_capture.set_enabled(settings.debug_capture_enabled);
+    Ok(())
+}
+
+#[tauri::command]
+async fn save_api_key(api_key: String) -> Result<(), AuraError> {
+    log::info!("Tauri command: save_api_key called");
+
+    secrets::save_api_key(&api_key)
+        .map_err(|e| AuraError::Secrets(e))
+}
+
+#[tauri::command]
+async fn load_api_key() -> Result<String, AuraError> {
+    log::info!("Tauri command: load_api_key called");
+
+    secrets::load_api_key()
+        .map_err(|e| AuraError::Secrets(e))
+}
+
+/// Parse the `llm_fallback_backends` setting and attach API keys from the OS keyring
+fn load_fallback_backends(json: &str) -> Vec<LlmBackendConfig> {
+    let mut backends: Vec<LlmBackendConfig> = serde_json::from_str(json).unwrap_or_else(|e| {
+        log::warn!("Ignoring invalid llm_fallback_backends setting: {}", e);
+        Vec::new()
+    });
+
+    for backend in &mut backends {
+        backend.api_key = secrets::load_llm_backend_api_key(&backend.name);
+    }
+
+    backends
+}
+
+/// Replace the LLM fallback chain (tried in order when the configured API is unavailable)
+///
+/// API keys provided here are stored in the OS keyring; backends sent without a key
+/// keep the one already stored for their name. `primary_timeout_secs` sets the
+/// timeout of the configured API (`llm_timeout_secs`); fallbacks carry their own.
+#[tauri::command]
+async fn set_llm_fallback_backends(
+    backends: Vec<LlmBackendConfig>,
+    primary_timeout_secs: Option<u32>,
+    llm_engine: State<'_, Arc<TokioMutex<LLMEngine>>>,
+    db: State<'_, DatabaseState>,
+) -> Result<(), AuraError> {
+    log::info!("Tauri command: set_llm_fallback_backends called ({} backends)", backends.len());
+
+    // Validate everything before touching the keyring, so a rejected list changes nothing
+    let mut names = std::collections::HashSet::new();
+    for backend in &backends {
+        if backend.name.trim().is_empty() || !names.insert(backend.name.as_str()) {
+            return Err(AuraError::Config(format!("Backend names must be unique and non-empty: '{}'", backend.name)));
+        }
+        backend.validate().map_err(|e| AuraError::Config(e))?;
+    }
+    if primary_timeout_secs == Some(0) {
+        return Err(AuraError::Config("Timeout for the primary LLM backend must be greater than 0".to_string()));
+    }
+
+    let mut backends = backends;
+    for backend in &mut backends {
+        match &backend.api_key {
+            Some(api_key) => secrets::save_llm_backend_api_key(&backend.name, api_key)
+                .map_err(|e| AuraError::Secrets(e))?,
+            None => backend.api_key = secrets::load_llm_backend_api_key(&backend.name),
+        }
+    }
+
+    // Serialized without API keys (those live in the keyring)
+    let backends_json = serde_json::to_string(&backends)?;
 
-#[tauri::command]
-async fn save_api_key(api_key: String) -> Result<(), AuraError> {
-    log::info!("Tauri command: save_api_key called");
+    // Apply to the running engine before persisting
+    {
+        let mut llm = llm_engine.inner().lock().await;
+        if let Some(timeout_secs) = primary_timeout_secs {
+            llm.set_primary_timeout(timeout_secs as u64)
+                .map_err(|e| AuraError::Config(e))?;
+        }
+        llm.set_fallback_backends(backends)
+            .map_err(|e| AuraError::Config(e))?;
+    }
 
-    secrets::save_api_key(&api_key)
-        .map_err(|e| AuraError::Secrets(e))
+    let db = db.inner().lock().await;
+    let mut settings = db.load_settings()
+        .map_err(|e| AuraError::Database(e))?;
+    settings.llm_fallback_backends = backends_json;
+    if let Some(timeout_secs) = primary_timeout_secs {
+        settings.llm_timeout_secs = timeout_secs;
+    }
+    db.save_settings(&settings)
+        .map_err(|e| AuraError::Database(e))
 }
 
+/// Health check every LLM backend in the fallback chain
 #[tauri::command]
-async fn load_api_key() -> Result<String, AuraError> {
-    log::info!("Tauri command: load_api_key called");
+async fn check_llm_backends(
+    llm_engine: State<'_, Arc<TokioMutex<LLMEngine>>>,
+) -> Result<Vec<BackendStatus>, AuraError> {
+    log::info!("Tauri command: check_llm_backends called");
 
-    secrets::load_api_key()
-        .map_err(|e| AuraError::Secrets(e))
+    let llm = llm_engine.inner().lock().await;
+    Ok(llm.check_backends().await)
 }
 
 #[tauri::command]
@@ -615,6 +1454,10 @@ async fn reload_voice_pipeline(
     vad_timeout_ms: u32,
     stt_model_name: String,
     voice_pipeline: State<'_, Arc<StdMutex<NativeVoicePipeline>>>,
+    whisper_cache: State<'_, Arc<WhisperCache>>,
+    stt_vocabulary: State<'_, SttVocabularyState>,
+    debug_capture: State<'_, DebugCaptureState>,
+    playback_monitor: State<'_, PlaybackMonitor>,
     database: State<'_, DatabaseState>,
 ) -> Result<(), AuraError> {
     log::info!("Reloading voice pipeline with new settings...");
@@ -624,7 +1467,7 @@ async fn reload_voice_pipeline(
     log::info!("  Wake word enabled: {}", wake_word_enabled);
 
     // Save settings to database first
-    {
+    let saved_settings = {
         let db = database.lock().await;
 
         // Load existing settings (this command doesn't modify voice preference, RAG, Spotify, or Home Assistant settings)
@@ -650,6 +1493,19 @@ async fn reload_voice_pipeline(
             ha_base_url: String::new(),
             ha_auto_sync: true,
             ha_onboarding_dismissed: false,
+            context_token_budget: 2048,
+            llm_fallback_backends: "[]".to_string(),
+            llm_context_length: 0,
+            stt_language: "auto".to_string(),
+            stt_partial_interval_ms: 500,
+            wake_phrases: r#"[{"phrase":"hey aura","sensitivity":0.75}]"#.to_string(),
+            vad_engine: "energy".to_string(),
+            input_device_name: String::new(),
+            barge_in_mode: "off".to_string(),
+            debug_capture_enabled: false,
+            voice_model: String::new(),
+            ollama_sidecar_host: "127.0.0.1:11434".to_string(),
+            llm_timeout_secs: 120,
         });
 
         let settings_to_save = Settings {
@@ -677,11 +1533,26 @@ async fn reload_voice_pipeline(
             ha_base_url: existing_settings.ha_base_url,
             ha_auto_sync: existing_settings.ha_auto_sync,
             ha_onboarding_dismissed: existing_settings.ha_onboarding_dismissed,
+            context_token_budget: existing_settings.context_token_budget,
+            llm_fallback_backends: existing_settings.llm_fallback_backends,
+            llm_context_length: existing_settings.llm_context_length,
+            stt_language: existing_settings.stt_language,
+            stt_partial_interval_ms: existing_settings.stt_partial_interval_ms,
+            wake_phrases: existing_settings.wake_phrases,
+            vad_engine: existing_settings.vad_engine,
+            input_device_name: existing_settings.input_device_name,
+            barge_in_mode: existing_settings.barge_in_mode,
+            debug_capture_enabled: existing_settings.debug_capture_enabled,
+            voice_model: existing_settings.voice_model,
+            ollama_sidecar_host: existing_settings.ollama_sidecar_host,
+            llm_timeout_secs: existing_settings.llm_timeout_secs,
         };
 
         db.save_settings(&settings_to_save)
             .map_err(|e| AuraError::Database(e))?;
-    }
+
+        settings_to_save
+    };
 
     // Determine model path
     let model_path = dirs::data_local_dir()
@@ -689,9 +1560,22 @@ async fn reload_voice_pipeline(
         .unwrap_or_else(|| std::path::PathBuf::from("./models"));
 
     let voice_pipeline_clone = voice_pipeline.inner().clone();
+    let whisper_cache = whisper_cache.inner().clone();
+    let stt_vocabulary = stt_vocabulary.inner().clone();
+    let debug_capture = debug_capture.inner().clone();
+    let playback_monitor = playback_monitor.inner().clone();
 
     // Stop the old pipeline and create a new one using spawn_blocking
     let new_pipeline = tokio::task::spawn_blocking(move || {
+        // Load the (possibly new) Whisper model up front and switch the cache to it;
+        // a missing model is reported when transcribing, not here
+        let whisper_model = model_path.join(&stt_model_name);
+        if whisper_model.exists() {
+            if let Err(e) = whisper_cache.swap(&whisper_model) {
+                log::warn!("⚠ Failed to preload Whisper model: {}", e);
+            }
+        }
+
         // First, stop the old pipeline
         let old_pipeline = voice_pipeline_clone.lock()
             .map_err(|e| AuraError::Internal(format!("Failed to lock voice pipeline: {}", e)))?;
@@ -708,8 +1592,18 @@ async fn reload_voice_pipeline(
             app_handle.clone(),
             model_path.clone(),
             stt_model_name.clone(),
+            saved_settings.stt_language,
+            saved_settings.stt_partial_interval_ms,
+            whisper_cache,
+            stt_vocabulary,
+            debug_capture,
+            saved_settings.input_device_name,
+            wake_word::parse_wake_phrases(&saved_settings.wake_phrases),
             vad_sensitivity,
             vad_timeout_ms,
+            saved_settings.vad_engine,
+            &saved_settings.barge_in_mode,
+            playback_monitor,
         )
         .map_err(|e| AuraError::VoicePipeline(e))?;
 
@@ -746,6 +1640,9 @@ pub struct SetupStatus {
     pub first_run_complete: bool,
     pub whisper_model_exists: bool,
     pub whisper_model_path: String,
+    pub llm_model_name: String,
+    /// Whether the configured model is installed in Ollama (None if Ollama isn't reachable)
+    pub llm_model_installed: Option<bool>,
 }
 
 /// Check the status of all required dependencies
@@ -756,10 +1653,13 @@ async fn check_setup_status(
     log::info!("Checking setup status for first-run wizard");
 
     // Check if first-run wizard has been completed
-    let first_run_complete = {
+    let (first_run_complete, settings) = {
         let db = database.lock().await;
-        db.is_first_run_complete()
-            .map_err(|e| AuraError::Database(e))?
+        let first_run_complete = db.is_first_run_complete()
+            .map_err(|e| AuraError::Database(e))?;
+        let settings = db.load_settings()
+            .map_err(|e| AuraError::Database(e))?;
+        (first_run_complete, settings)
     };
 
     // Check Whisper model existence
@@ -770,14 +1670,24 @@ async fn check_setup_status(
     let whisper_model_path = model_path.join("ggml-tiny.bin");
     let whisper_model_exists = whisper_model_path.exists();
 
+    // Check whether the configured LLM model has been pulled into Ollama
+    let llm_model_installed = match OllamaClient::from_api_base_url(&settings.api_base_url) {
+        Ok(client) => client.has_model(&settings.model_name).await
+            .map_err(|e| log::warn!("Could not check Ollama models: {}", e))
+            .ok(),
+        Err(_) => None,
+    };
+
     let status = SetupStatus {
         first_run_complete,
         whisper_model_exists,
         whisper_model_path: whisper_model_path.to_string_lossy().to_string(),
+        llm_model_name: settings.model_name,
+        llm_model_installed,
     };
 
-    log::info!("Setup status: first_run={}, whisper={}",
-               status.first_run_complete, status.whisper_model_exists);
+    log::info!("Setup status: first_run={}, whisper={}, llm_model={:?}",
+               status.first_run_complete, status.whisper_model_exists, status.llm_model_installed);
 
     Ok(status)
 }
@@ -790,35 +1700,25 @@ struct DownloadProgress {
     percentage: f32,
 }
 
-/// Download the Whisper tiny model from HuggingFace
-#[tauri::command]
-async fn download_whisper_model(
-    app_handle: tauri::AppHandle,
-) -> Result<String, AuraError> {
-    log::info!("Starting Whisper model download");
-
-    // Determine download path
-    let model_path = dirs::data_local_dir()
-        .map(|p| p.join("nivora-aura").join("models"))
-        .unwrap_or_else(|| std::path::PathBuf::from("./models"));
-
-    // Create directory if it doesn't exist
-    std::fs::create_dir_all(&model_path)
-        .map_err(|e| AuraError::Internal(format!("Failed to create models directory: {}", e)))?;
-
-    let dest_path = model_path.join("ggml-tiny.bin");
-
-    // URL for ggml-tiny.bin from HuggingFace
-    let url = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-tiny.bin";
+/// Download a file to `dest_path`, emitting `DownloadProgress` as `event_name` events
+///
+/// Progress events are best effort: failing to emit one doesn't stop the download.
+async fn download_with_progress(
+    app_handle: &tauri::AppHandle,
+    url: &str,
+    dest_path: &std::path::Path,
+    event_name: &str,
+) -> Result<(), AuraError> {
+    log::info!("Downloading {} to {}", url, dest_path.display());
 
-    // Download with progress
     let client = reqwest::Client::new();
     let mut response = client.get(url).send().await
-        .map_err(|e| AuraError::Internal(format!("Failed to start download: {}", e)))?;
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| AuraError::Internal(format!("Failed to download {}: {}", url, e)))?;
 
     let total_size = response.content_length();
 
-    let mut file = tokio::fs::File::create(&dest_path).await
+    let mut file = tokio::fs::File::create(dest_path).await
         .map_err(|e| AuraError::Internal(format!("Failed to create file: {}", e)))?;
 
     let mut downloaded: u64 = 0;
@@ -837,17 +1737,46 @@ async fn download_whisper_model(
             0.0
         };
 
-        // Emit progress event
         let progress = DownloadProgress {
             downloaded_bytes: downloaded,
             total_bytes: total_size,
             percentage,
         };
 
-        app_handle.emit("download_progress", progress)
-            .map_err(|e| AuraError::Internal(format!("Failed to emit progress: {}", e)))?;
+        if let Err(e) = app_handle.emit(event_name, progress) {
+            log::warn!("Failed to emit {} event: {}", event_name, e);
+        }
     }
 
+    tokio::io::AsyncWriteExt::flush(&mut file).await
+        .map_err(|e| AuraError::Internal(format!("Failed to write file: {}", e)))?;
+
+    Ok(())
+}
+
+/// Download the Whisper tiny model from HuggingFace
+#[tauri::command]
+async fn download_whisper_model(
+    app_handle: tauri::AppHandle,
+) -> Result<String, AuraError> {
+    log::info!("Starting Whisper model download");
+
+    // Determine download path
+    let model_path = dirs::data_local_dir()
+        .map(|p| p.join("nivora-aura").join("models"))
+        .unwrap_or_else(|| std::path::PathBuf::from("./models"));
+
+    // Create directory if it doesn't exist
+    std::fs::create_dir_all(&model_path)
+        .map_err(|e| AuraError::Internal(format!("Failed to create models directory: {}", e)))?;
+
+    let dest_path = model_path.join("ggml-tiny.bin");
+
+    // URL for ggml-tiny.bin from HuggingFace
+    let url = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-tiny.bin";
+
+    download_with_progress(&app_handle, url, &dest_path, "download_progress").await?;
+
     log::info!("✓ Whisper model downloaded successfully to: {}", dest_path.display());
 
     Ok(dest_path.to_string_lossy().to_string())
@@ -874,55 +1803,13 @@ async fn mark_setup_complete(
 async fn fetch_available_models(db: State<'_, DatabaseState>) -> Result<Vec<String>, AuraError> {
     log::info!("Tauri command: fetch_available_models called");
 
-    // Load settings to get API base URL
-    let settings = {
-        let db = db.inner().lock().await;
-        db.load_settings()
-            .map_err(|e| AuraError::Database(e))?
-    };
-
-    let api_url = format!("{}/tags", settings.api_base_url);
-    log::info!("Fetching models from: {}", api_url);
-
-    // Create HTTP client with timeout
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(5))
-        .build()
-        .map_err(|e| AuraError::Internal(format!("Failed to create HTTP client: {}", e)))?;
-
-    // Fetch models list
-    let response = client
-        .get(&api_url)
-        .send()
-        .await
-        .map_err(|e| AuraError::Internal(format!("Failed to fetch models: {}", e)))?;
-
-    if !response.status().is_success() {
-        return Err(AuraError::Internal(format!(
-            "Ollama API returned error status: {}",
-            response.status()
-        )));
-    }
-
-    // Parse response
-    #[derive(serde::Deserialize)]
-    struct TagsResponse {
-        models: Vec<ModelInfo>,
-    }
-
-    #[derive(serde::Deserialize)]
-    struct ModelInfo {
-        name: String,
-    }
-
-    let tags_response: TagsResponse = response
-        .json()
-        .await
-        .map_err(|e| AuraError::Internal(format!("Failed to parse models response: {}", e)))?;
+    let client = ollama_client(&db).await?;
 
     // Extract model names
-    let mut model_names: Vec<String> = tags_response
-        .models
+    let mut model_names: Vec<String> = client
+        .list_models()
+        .await
+        .map_err(|e| AuraError::Llm(e))?
         .into_iter()
         .map(|m| m.name)
         .collect();
@@ -941,6 +1828,89 @@ async fn fetch_available_models(db: State<'_, DatabaseState>) -> Result<Vec<Stri
     Ok(model_names)
 }
 
+// =============================================================================
+// OLLAMA MODEL MANAGEMENT COMMANDS
+// =============================================================================
+
+/// Create an Ollama management client for the configured API base URL
+async fn ollama_client(db: &DatabaseState) -> Result<OllamaClient, AuraError> {
+    let settings = {
+        let db = db.lock().await;
+        db.load_settings()
+            .map_err(|e| AuraError::Database(e))?
+    };
+
+    OllamaClient::from_api_base_url(&settings.api_base_url)
+        .map_err(|e| AuraError::Llm(e))
+}
+
+/// List installed Ollama models with size and quantization details
+#[tauri::command]
+async fn ollama_list_models(db: State<'_, DatabaseState>) -> Result<Vec<OllamaModel>, AuraError> {
+    log::info!("Tauri command: ollama_list_models called");
+
+    ollama_client(db.inner()).await?
+        .list_models()
+        .await
+        .map_err(|e| AuraError::Llm(e))
+}
+
+/// Show details (parameters, template, architecture info) of an installed model
+#[tauri::command]
+async fn ollama_show_model(model: String, db: State<'_, DatabaseState>) -> Result<OllamaModelInfo, AuraError> {
+    log::info!("Tauri command: ollama_show_model called for {}", model);
+
+    ollama_client(db.inner()).await?
+        .show_model(&model)
+        .await
+        .map_err(|e| AuraError::Llm(e))
+}
+
+/// Delete an installed model
+#[tauri::command]
+async fn ollama_delete_model(model: String, db: State<'_, DatabaseState>) -> Result<(), AuraError> {
+    log::info!("Tauri command: ollama_delete_model called for {}", model);
+
+    ollama_client(db.inner()).await?
+        .delete_model(&model)
+        .await
+        .map_err(|e| AuraError::Llm(e))
+}
+
+/// List models currently loaded into memory
+#[tauri::command]
+async fn ollama_running_models(db: State<'_, DatabaseState>) -> Result<Vec<RunningModel>, AuraError> {
+    log::info!("Tauri command: ollama_running_models called");
+
+    ollama_client(db.inner()).await?
+        .running_models()
+        .await
+        .map_err(|e| AuraError::Llm(e))
+}
+
+/// Download a model into Ollama, emitting `ollama_pull_progress` events
+///
+/// Used by the first-run wizard to fetch the default model without a terminal.
+#[tauri::command]
+async fn ollama_pull_model(
+    app_handle: tauri::AppHandle,
+    model: String,
+    db: State<'_, DatabaseState>,
+) -> Result<(), AuraError> {
+    log::info!("Tauri command: ollama_pull_model called for {}", model);
+
+    let client = ollama_client(db.inner()).await?;
+
+    client
+        .pull_model(&model, |progress| {
+            if let Err(e) = app_handle.emit("ollama_pull_progress", progress) {
+                log::error!("Failed to emit ollama_pull_progress: {}", e);
+            }
+        })
+        .await
+        .map_err(|e| AuraError::Llm(e))
+}
+
 /// Get GPU acceleration status
 #[tauri::command]
 async fn get_gpu_info(
@@ -961,6 +1931,69 @@ async fn get_gpu_info(
     Ok(gpu_info)
 }
 
+/// Get Ollama sidecar diagnostics (process state, restart count and recent log lines)
+#[tauri::command]
+async fn get_ollama_diagnostics(
+    ollama_sidecar: State<'_, Arc<StdMutex<OllamaSidecar>>>
+) -> Result<ollama_sidecar::SidecarDiagnostics, AuraError> {
+    log::info!("Tauri command: get_ollama_diagnostics called");
+
+    let mut sidecar = ollama_sidecar.lock()
+        .map_err(|e| AuraError::Internal(format!("Failed to lock Ollama sidecar: {}", e)))?;
+
+    Ok(sidecar.diagnostics())
+}
+
+/// Point `api_base_url` (settings and LLM engine) at the host the sidecar runs on
+///
+/// Called after every start and restart of the bundled Ollama. The sidecar may have
+/// moved off a busy port, or back to the default one after a previous run had moved
+/// it, so the URL is rewritten from the host the sidecar last ran on
+/// (`ollama_sidecar_host`). Only URLs pointing at that local host are rewritten; a
+/// user-configured remote or custom server is left alone.
+fn relocate_local_ollama(
+    database: &DatabaseState,
+    llm_engine: &Arc<TokioMutex<LLMEngine>>,
+    new_host: &str,
+) {
+    let db = database.blocking_lock();
+    let mut settings = match db.load_settings() {
+        Ok(settings) => settings,
+        Err(e) => {
+            log::error!("✗ Failed to load settings for Ollama port change: {}", e);
+            return;
+        }
+    };
+
+    if settings.ollama_sidecar_host == new_host {
+        return;
+    }
+
+    let old_host = std::mem::replace(&mut settings.ollama_sidecar_host, new_host.to_string());
+    let new_base_url = ollama_sidecar::relocate_api_base_url(&settings.api_base_url, &old_host, new_host);
+    match &new_base_url {
+        Some(url) => {
+            log::info!("Updating API base URL: {} -> {}", settings.api_base_url, url);
+            settings.api_base_url = url.clone();
+        }
+        None => log::info!("API base URL {} does not use the sidecar, leaving it unchanged", settings.api_base_url),
+    }
+    if let Err(e) = db.save_settings(&settings) {
+        log::error!("✗ Failed to save relocated API base URL: {}", e);
+    }
+    drop(db);
+
+    let Some(new_base_url) = new_base_url else {
+        return;
+    };
+
+    let mut llm = llm_engine.blocking_lock();
+    let model_name = llm.model_info().model_name;
+    if let Err(e) = llm.update_config(new_base_url, model_name, secrets::load_api_key().ok()) {
+        log::error!("✗ Failed to point LLM engine at relocated Ollama: {}", e);
+    }
+}
+
 /// Wait for Ollama server to become ready
 ///
 /// Polls the Ollama API until it responds or times out
@@ -1146,6 +2179,7 @@ async fn spotify_handle_music_command(
     command: String,
     user_id: Option<i64>, // NEW: User context from voice biometrics
     db: State<'_, DatabaseState>,
+    stt_vocabulary: State<'_, SttVocabularyState>,
 ) -> Result<String, AuraError> {
     log::info!("Handling music command: '{}' (user_id: {:?})", command, user_id);
 
@@ -1243,6 +2277,7 @@ async fn spotify_handle_music_command(
             let playlists = client.get_user_playlists(50)
                 .await
                 .map_err(|e| AuraError::Spotify(e.to_string()))?;
+            stt_vocabulary.set_playlists(playlists.iter().map(|p| p.name.clone()).collect());
 
             // Find playlist by name (case-insensitive)
             let playlist = playlists.iter().find(|p| {
@@ -1728,6 +2763,46 @@ pub type HAClientState = Arc<TokioMutex<Option<HomeAssistantClient>>>;
 /// Type alias for Entity Manager state
 pub type EntityManagerState = Arc<EntityManager>;
 
+/// Rebuild the Home Assistant part of the STT vocabulary from the entity cache and
+/// the shortcut names of all users
+async fn refresh_stt_vocabulary(
+    entity_manager: &EntityManager,
+    db: &DatabaseState,
+    vocabulary: &SttVocabulary,
+) {
+    let entities = entity_manager.get_all_entities().await;
+
+    let database = db.lock().await;
+    let shortcut_names = database.query_rows(
+        "SELECT DISTINCT shortcut_name FROM user_ha_shortcuts",
+        &[],
+        |row| row.get(0),
+    ).unwrap_or_else(|e| {
+        log::warn!("Failed to load shortcut names for STT vocabulary: {}", e);
+        Vec::new()
+    });
+    drop(database);
+
+    vocabulary.set_home_terms(&entities, shortcut_names);
+}
+
+/// Refresh the STT vocabulary now and after every entity sync
+fn spawn_stt_vocabulary_refresh(
+    entity_manager: EntityManagerState,
+    db: DatabaseState,
+    vocabulary: SttVocabularyState,
+) {
+    let mut syncs = entity_manager.subscribe_syncs();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            refresh_stt_vocabulary(&entity_manager, &db, &vocabulary).await;
+            if syncs.changed().await.is_err() {
+                break;
+            }
+        }
+    });
+}
+
 /// Response for Home Assistant status query
 #[derive(Serialize)]
 struct HAStatusResponse {
@@ -2350,6 +3425,8 @@ async fn create_user_ha_shortcut(
     ha_entity_id: String,
     entity_type: String,
     db: State<'_, DatabaseState>,
+    entity_manager: State<'_, EntityManagerState>,
+    stt_vocabulary: State<'_, SttVocabularyState>,
 ) -> Result<i64, AuraError> {
     log::info!(
         "Creating HA shortcut: user_id={}, name='{}', entity='{}', type='{}'",
@@ -2371,8 +3448,10 @@ async fn create_user_ha_shortcut(
         "INSERT INTO user_ha_shortcuts (user_id, shortcut_name, ha_entity_id, entity_type, created_at) VALUES (?, ?, ?, ?, ?)",
         &[&user_id, &shortcut_name as &dyn rusqlite::ToSql, &ha_entity_id as &dyn rusqlite::ToSql, &entity_type as &dyn rusqlite::ToSql, &created_at as &dyn rusqlite::ToSql],
     ).map_err(|e| AuraError::Database(format!("Failed to create shortcut: {}", e)))?;
+    drop(database);
 
     log::info!("✓ Created shortcut with id={}", shortcut_id);
+    refresh_stt_vocabulary(&entity_manager, &db, &stt_vocabulary).await;
 
     Ok(shortcut_id)
 }
@@ -2382,6 +3461,8 @@ async fn create_user_ha_shortcut(
 async fn delete_user_ha_shortcut(
     shortcut_id: i64,
     db: State<'_, DatabaseState>,
+    entity_manager: State<'_, EntityManagerState>,
+    stt_vocabulary: State<'_, SttVocabularyState>,
 ) -> Result<(), AuraError> {
     log::info!("Deleting HA shortcut id={}", shortcut_id);
 
@@ -2390,6 +3471,7 @@ async fn delete_user_ha_shortcut(
         "DELETE FROM user_ha_shortcuts WHERE id = ?",
         &[&shortcut_id],
     ).map_err(|e| AuraError::Database(format!("Failed to delete shortcut: {}", e)))?;
+    drop(database);
 
     if rows_affected == 0 {
         return Err(AuraError::Database(format!(
@@ -2399,6 +3481,7 @@ async fn delete_user_ha_shortcut(
     }
 
     log::info!("✓ Deleted shortcut id={}", shortcut_id);
+    refresh_stt_vocabulary(&entity_manager, &db, &stt_vocabulary).await;
     Ok(())
 }
 
@@ -2469,6 +3552,89 @@ async fn update_user_ha_preferences(
     Ok(())
 }
 
+/// Load a user's TTS preferences, as synthesis options (defaults for unknown speakers)
+async fn load_synthesis_options(db: &DatabaseState, user_id: Option<i64>) -> SynthesisOptions {
+    let Some(uid) = user_id else {
+        return SynthesisOptions::default();
+    };
+
+    match query_user_tts_preferences(&*db.lock().await, uid) {
+        Ok(Some(prefs)) => SynthesisOptions {
+            speaker: prefs.speaker,
+            length_scale: prefs.length_scale,
+            noise_scale: prefs.noise_scale,
+            sentence_silence: prefs.sentence_silence,
+        },
+        Ok(None) => SynthesisOptions::default(),
+        Err(e) => {
+            log::warn!("Failed to load TTS preferences for user_id={}: {}", uid, e);
+            SynthesisOptions::default()
+        }
+    }
+}
+
+fn query_user_tts_preferences(database: &Database, user_id: i64) -> Result<Option<UserTtsPreferences>, String> {
+    let mut prefs_vec = database.query_rows(
+        "SELECT user_id, speaker, length_scale, noise_scale, sentence_silence, updated_at FROM user_tts_preferences WHERE user_id = ?",
+        &[&user_id],
+        |row| {
+            Ok(UserTtsPreferences {
+                user_id: row.get(0)?,
+                speaker: row.get(1)?,
+                length_scale: row.get(2)?,
+                noise_scale: row.get(3)?,
+                sentence_silence: row.get(4)?,
+                updated_at: row.get(5)?,
+            })
+        }
+    )?;
+
+    Ok(if prefs_vec.is_empty() { None } else { Some(prefs_vec.remove(0)) })
+}
+
+/// Get TTS preferences (speaker, speech rate, ...) for a specific user
+#[tauri::command]
+async fn get_user_tts_preferences(
+    user_id: i64,
+    db: State<'_, DatabaseState>,
+) -> Result<Option<UserTtsPreferences>, AuraError> {
+    log::info!("Getting TTS preferences for user_id={}", user_id);
+
+    let database = db.lock().await;
+    query_user_tts_preferences(&database, user_id)
+        .map_err(|e| AuraError::Database(format!("Failed to query TTS preferences: {}", e)))
+}
+
+/// Update TTS preferences for a specific user (None = the voice's default)
+#[tauri::command]
+async fn update_user_tts_preferences(
+    user_id: i64,
+    speaker: Option<String>,
+    length_scale: Option<f32>,
+    noise_scale: Option<f32>,
+    sentence_silence: Option<f32>,
+    db: State<'_, DatabaseState>,
+) -> Result<(), AuraError> {
+    log::info!(
+        "Updating TTS preferences for user_id={}: speaker={:?}, length_scale={:?}, noise_scale={:?}, sentence_silence={:?}",
+        user_id, speaker, length_scale, noise_scale, sentence_silence
+    );
+
+    let options = SynthesisOptions { speaker, length_scale, noise_scale, sentence_silence };
+    options.validate().map_err(AuraError::Config)?;
+
+    let database = db.lock().await;
+    let updated_at = chrono::Utc::now().to_rfc3339();
+
+    database.execute_query(
+        "INSERT OR REPLACE INTO user_tts_preferences (user_id, speaker, length_scale, noise_scale, sentence_silence, updated_at) VALUES (?, ?, ?, ?, ?, ?)",
+        &[&user_id, &options.speaker as &dyn rusqlite::ToSql, &options.length_scale as &dyn rusqlite::ToSql, &options.noise_scale as &dyn rusqlite::ToSql, &options.sentence_silence as &dyn rusqlite::ToSql, &updated_at as &dyn rusqlite::ToSql],
+    ).map_err(|e| AuraError::Database(format!("Failed to update TTS preferences: {}", e)))?;
+
+    log::info!("✓ Updated TTS preferences for user_id={}", user_id);
+    Ok(())
+}
+
 /// Lookup a user's shortcut by name (for NLU resolution)
 #[tauri::command]
 async fn lookup_user_shortcut(
@@ -2701,6 +3867,19 @@ pub fn run() {
             ha_base_url: String::new(),
             ha_auto_sync: true,
             ha_onboarding_dismissed: false,
+            context_token_budget: 2048,
+            llm_fallback_backends: "[]".to_string(),
+            llm_context_length: 0,
+            stt_language: "auto".to_string(),
+            stt_partial_interval_ms: 500,
+            wake_phrases: r#"[{"phrase":"hey aura","sensitivity":0.75}]"#.to_string(),
+            vad_engine: "energy".to_string(),
+            input_device_name: String::new(),
+            barge_in_mode: "off".to_string(),
+            debug_capture_enabled: false,
+            voice_model: String::new(),
+            ollama_sidecar_host: "127.0.0.1:11434".to_string(),
+            llm_timeout_secs: 120,
         }
     });
     drop(db_for_llm); // Release the lock
@@ -2708,20 +3887,29 @@ pub fn run() {
     // Load API key from keyring (optional)
     let api_key = secrets::load_api_key().ok();
 
-    let llm_engine = match LLMEngine::new(
+    let (llm_engine, generation_canceller) = match LLMEngine::new(
         settings.api_base_url.clone(),
         settings.model_name.clone(),
         api_key.clone(),
         None
     ) {
-        Ok(llm) => {
+        Ok(mut llm) => {
+            llm.set_context_token_budget(settings.context_token_budget);
+            if let Err(e) = llm.set_primary_timeout(settings.llm_timeout_secs as u64) {
+                log::error!("✗ Invalid LLM timeout setting: {}", e);
+            }
+            if let Err(e) = llm.set_fallback_backends(load_fallback_backends(&settings.llm_fallback_backends)) {
+                log::error!("✗ Failed to configure LLM fallback backends: {}", e);
+            }
             log::info!("✓ LLM engine initialized successfully");
             let info = llm.model_info();
             log::info!("  - API Base URL: {}", info.api_base_url);
             log::info!("  - Model: {}", info.model_name);
             log::info!("  - System prompt: {}", info.system_prompt);
             log::info!("  - API Key: {}", if api_key.is_some() { "provided" } else { "not provided" });
-            Arc::new(TokioMutex::new(llm))
+            // Cancelling must not wait for the engine lock a streamed answer holds
+            let canceller = llm.canceller();
+            (Arc::new(TokioMutex::new(llm)), canceller)
         }
         Err(e) => {
             log::error!("✗ Failed to initialize LLM engine: {}", e);
@@ -2742,18 +3930,59 @@ pub fn run() {
     let entity_manager: EntityManagerState = Arc::new(EntityManager::new());
     let ha_client_state: HAClientState = Arc::new(TokioMutex::new(None));
 
+    // Names Whisper is prompted with (refreshed from Home Assistant and Spotify)
+    let stt_vocabulary: SttVocabularyState = Arc::new(SttVocabulary::new());
+    let stt_vocabulary_for_setup = stt_vocabulary.clone();
+    let entity_manager_for_setup = entity_manager.clone();
+
+    // Opt-in recording capture for diagnosing recognition problems
+    let captures_dir = dirs::data_local_dir()
+        .map(|p| p.join("nivora-aura").join("captures"))
+        .unwrap_or_else(|| std::path::PathBuf::from("./captures"));
+    let debug_capture: DebugCaptureState = Arc::new(DebugCapture::new(
+        captures_dir,
+        debug_capture::MAX_CAPTURES,
+        settings.debug_capture_enabled,
+    ));
+    let debug_capture_for_setup = debug_capture.clone();
+
+    // Tools the LLM may call on the user's behalf
+    let mut tool_registry = ToolRegistry::new();
+    tool_registry.register(Arc::new(HomeAssistantServiceTool::new(ha_client_state.clone())));
+    tool_registry.register(Arc::new(SpotifyPlaybackTool::new(database.clone())));
+    tool_registry.register(Arc::new(WebSearchTool::new(database.clone())));
+    let tool_registry: ToolRegistryState = Arc::new(tool_registry);
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .manage(database.clone())
         .manage(llm_engine)
+        .manage(generation_canceller)
         .manage(entity_manager)
         .manage(ha_client_state)
+        .manage(stt_vocabulary)
+        .manage(debug_capture)
+        .manage(tool_registry)
         .invoke_handler(tauri::generate_handler![
             greet,
             handle_user_prompt,
+            handle_user_prompt_streaming,
             listen_and_transcribe,
+            transcribe_file,
             cancel_recording,
+            list_input_devices,
+            list_debug_captures,
+            replay_debug_capture,
             speak_text,
+            tts_get_voice_config,
+            tts_stop,
+            tts_pause,
+            tts_resume,
+            tts_skip,
+            tts_get_status,
+            tts_list_voices,
+            tts_install_voice,
+            tts_set_voice,
             cancel_generation,
             load_conversations,
             load_messages,
@@ -2761,11 +3990,18 @@ pub fn run() {
             save_message,
             delete_conversation,
             update_conversation_title,
+            list_personas,
+            create_persona,
+            update_persona,
+            delete_persona,
+            set_conversation_persona,
             generate_conversation_title,
             load_settings,
             save_settings,
             save_api_key,
             load_api_key,
+            set_llm_fallback_backends,
+            check_llm_backends,
             update_vad_settings,
             set_voice_state,
             reload_voice_pipeline,
@@ -2773,7 +4009,13 @@ pub fn run() {
             download_whisper_model,
             mark_setup_complete,
             fetch_available_models,
+            ollama_list_models,
+            ollama_show_model,
+            ollama_delete_model,
+            ollama_running_models,
+            ollama_pull_model,
             get_gpu_info,
+            get_ollama_diagnostics,
             // Spotify Music Integration commands
             spotify_start_auth,
             spotify_disconnect,
@@ -2804,6 +4046,8 @@ pub fn run() {
             delete_user_ha_shortcut,
             get_user_ha_preferences,
             update_user_ha_preferences,
+            get_user_tts_preferences,
+            update_user_tts_preferences,
             lookup_user_shortcut,
             // Voice Biometrics commands
             voice_biometrics_status,
@@ -2907,8 +4151,15 @@ pub fn run() {
                     Ok(()) => {
                         log::info!("Ollama server starting...");
 
+                        // The sidecar may run on a different port than last time: follow it
+                        relocate_local_ollama(
+                            &database_for_setup,
+                            &app.state::<Arc<TokioMutex<LLMEngine>>>(),
+                            ollama_sidecar.host(),
+                        );
+
                         // Wait for readiness in background (non-blocking)
-                        let ollama_host_clone = ollama_host.clone();
+                        let ollama_host_clone = ollama_sidecar.host().to_string();
                         tokio::spawn(async move {
                             // Give it 30 seconds to start
                             match wait_for_ollama_ready(&ollama_host_clone, 30).await {
@@ -2931,7 +4182,28 @@ pub fn run() {
             }
 
             // Register Ollama sidecar as managed state for shutdown
-            app.manage(Arc::new(StdMutex::new(ollama_sidecar)));
+            let ollama_sidecar = Arc::new(StdMutex::new(ollama_sidecar));
+            app.manage(ollama_sidecar.clone());
+
+            // Restart the bundled server if it crashes and report outages to the UI
+            if use_bundled_ollama {
+                let supervisor_handle = app.handle().clone();
+                let database_for_supervisor = database_for_setup.clone();
+                let llm_for_supervisor = app.state::<Arc<TokioMutex<LLMEngine>>>().inner().clone();
+                ollama_sidecar::spawn_supervisor(ollama_sidecar, move |connected, host| {
+                    // A restart can move the sidecar to another port
+                    if connected {
+                        relocate_local_ollama(&database_for_supervisor, &llm_for_supervisor, host);
+                    }
+                    let status = native_voice::ServiceStatus {
+                        service: "llm".to_string(),
+                        connected,
+                    };
+                    if let Err(e) = supervisor_handle.emit("service_status", status) {
+                        log::error!("Failed to emit service status: {}", e);
+                    }
+                });
+            }
 
             // Load settings again for voice pipeline configuration
             let vad_settings = {
@@ -2942,7 +4214,14 @@ pub fn run() {
             let vad_sensitivity = vad_settings.as_ref().map(|s| s.vad_sensitivity).unwrap_or(0.02);
             let vad_timeout_ms = vad_settings.as_ref().map(|s| s.vad_timeout_ms).unwrap_or(1280);
             let stt_model_name = vad_settings.as_ref().map(|s| s.stt_model_name.clone()).unwrap_or_else(|| "ggml-tiny.bin".to_string());
+            let stt_language = vad_settings.as_ref().map(|s| s.stt_language.clone()).unwrap_or_else(|| "auto".to_string());
+            let stt_partial_interval_ms = vad_settings.as_ref().map(|s| s.stt_partial_interval_ms).unwrap_or(500);
+            let vad_engine = vad_settings.as_ref().map(|s| s.vad_engine.clone()).unwrap_or_else(|| "energy".to_string());
+            let barge_in_mode = vad_settings.as_ref().map(|s| s.barge_in_mode.clone()).unwrap_or_else(|| "off".to_string());
+            let input_device_name = vad_settings.as_ref().map(|s| s.input_device_name.clone()).unwrap_or_default();
+            let wake_phrases = wake_word::parse_wake_phrases(vad_settings.as_ref().map(|s| s.wake_phrases.as_str()).unwrap_or("[]"));
             let voice_preference = vad_settings.as_ref().map(|s| s.voice_preference.clone()).unwrap_or_else(|| "male".to_string());
+            let voice_model = vad_settings.as_ref().map(|s| s.voice_model.clone()).unwrap_or_default();
 
             // Initialize Subprocess-based Piper TTS engine with bundled resources
             log::info!("Initializing subprocess-based Piper TTS engine...");
@@ -2967,7 +4246,8 @@ pub fn run() {
 
             let bundled_piper_binary = resource_dir.join("piper").join("bin").join(piper_binary_name);
 
-            // Determine voice model based on user preference
+            // Default voice model based on user preference (a catalog voice picked in
+            // settings replaces it below)
             let voice_model_file = if voice_preference == "female" {
                 "en_US-amy-medium.onnx"
             } else {
@@ -3028,16 +4308,41 @@ pub fn run() {
                 }
             };
 
+            // Voices shipped next to the default model, plus voices the user installed
+            let user_voices_dir = dirs::data_local_dir()
+                .map(|p| p.join("nivora-aura").join("voices"))
+                .unwrap_or_else(|| std::path::PathBuf::from("./voices"));
+            let voice_catalog: VoiceCatalogState = Arc::new(VoiceCatalog::new(
+                voice_model_path.parent().map(|dir| dir.to_path_buf()),
+                user_voices_dir,
+            ));
+
+            let voice_model_path = if voice_model.is_empty() {
+                voice_model_path
+            } else {
+                match voice_catalog.find(&voice_model) {
+                    Some(voice) => voice.model_path,
+                    None => {
+                        log::warn!("Voice '{}' is no longer installed, using the default voice", voice_model);
+                        voice_model_path
+                    }
+                }
+            };
+
             log::info!("Piper binary path: {:?}", piper_binary);
             log::info!("Voice model path: {:?} (preference: {})", voice_model_path, voice_preference);
             log::info!("eSpeak-NG data path: {:?}", espeak_data_path);
 
-            let tts_engine = match TextToSpeech::new(piper_binary.clone(), voice_model_path.clone(), espeak_data_path.clone()) {
+            // Playback queue shared by the TTS engine, the tts_* commands and barge-in
+            let playback_monitor = PlaybackMonitor::new();
+            let playback_controller = PlaybackController::new(app_handle.clone(), playback_monitor.clone());
+
+            let tts_engine = match TextToSpeech::new(piper_binary.clone(), voice_model_path.clone(), espeak_data_path.clone(), playback_controller.clone()) {
                 Ok(tts) => {
                     log::info!("✓ Subprocess-based Piper TTS engine initialized successfully");
                     log::info!("  - Piper binary: {:?}", piper_binary);
                     log::info!("  - Voice model: {:?}", voice_model_path);
-                    log::info!("  - Voice: {} ({})", voice_preference, voice_model_file);
+                    log::info!("  - Voice: {}", tts.voice_config().language.as_deref().unwrap_or("unknown language"));
                     log::info!("  - Mode: {}", if use_bundled { "bundled (production)" } else { "system (dev)" });
                     Arc::new(TokioMutex::new(tts))
                 }
@@ -3054,6 +4359,17 @@ pub fn run() {
 
             // Register TTS engine as managed state
             app.manage(tts_engine.clone());
+            app.manage(playback_monitor.clone());
+            app.manage(playback_controller);
+            app.manage(voice_catalog);
+
+            // Barge-in already stopped the playback; also abort a generation still running
+            // (through the canceller: a streamed answer holds the engine lock)
+            let canceller_for_barge_in = app.state::<GenerationCanceller>().inner().clone();
+            app.listen("barge_in", move |_event| {
+                log::info!("Barge-in: cancelling generation");
+                canceller_for_barge_in.cancel();
+            });
 
             // Initialize Voice Biometrics (Speaker Recognition)
             log::info!("Initializing voice biometrics system...");
@@ -3094,30 +4410,81 @@ pub fn run() {
             let voice_biometrics_state: VoiceBiometricsState = Arc::new(voice_biometrics);
             app.manage(voice_biometrics_state.clone());
 
+            // Whisper model cache (shared across pipeline reloads, unloaded when idle)
+            let whisper_cache = native_voice::new_whisper_cache();
+            app.manage(whisper_cache.clone());
+
+            // Keep Whisper's vocabulary in step with Home Assistant and Spotify
+            spawn_stt_vocabulary_refresh(
+                entity_manager_for_setup.clone(),
+                database_for_setup.clone(),
+                stt_vocabulary_for_setup.clone(),
+            );
+            let spotify_client_id = vad_settings.as_ref()
+                .filter(|s| s.spotify_connected && secrets::is_spotify_connected())
+                .map(|s| s.spotify_client_id.clone())
+                .filter(|id| !id.is_empty());
+            if let Some(client_id) = spotify_client_id {
+                let vocabulary = stt_vocabulary_for_setup.clone();
+                tauri::async_runtime::spawn(async move {
+                    let playlists = match SpotifyClient::new(client_id) {
+                        Ok(client) => client.get_user_playlists(50).await.map_err(|e| e.to_string()),
+                        Err(e) => Err(e.to_string()),
+                    };
+                    match playlists {
+                        Ok(playlists) => vocabulary.set_playlists(playlists.into_iter().map(|p| p.name).collect()),
+                        Err(e) => log::warn!("Failed to fetch Spotify playlists for STT vocabulary: {}", e),
+                    }
+                });
+            }
+
             // Initialize Native Voice Pipeline
             log::info!("Initializing native voice pipeline...");
             let voice_pipeline = match NativeVoicePipeline::new(
                 app_handle.clone(),
                 model_path.clone(),
                 stt_model_name.clone(),
+                stt_language.clone(),
+                stt_partial_interval_ms,
+                whisper_cache.clone(),
+                stt_vocabulary_for_setup.clone(),
+                debug_capture_for_setup.clone(),
+                input_device_name.clone(),
+                wake_phrases.clone(),
                 vad_sensitivity,
                 vad_timeout_ms,
+                vad_engine.clone(),
+                &barge_in_mode,
+                playback_monitor,
             ) {
                 Ok(pipeline) => {
                     log::info!("✓ Native voice pipeline initialized");
-                    log::info!("  - Audio device: configured for 16kHz mono");
-                    log::info!("  - Wake word: energy-based VAD");
+                    log::info!("  - Audio device: {} (converted to 16kHz mono)",
+                               if input_device_name.is_empty() { "system default" } else { input_device_name.as_str() });
+                    log::info!("  - Wake phrases: {}", wake_phrases.iter().map(|p| p.phrase.as_str()).collect::<Vec<_>>().join(", "));
                     log::info!("  - STT: whisper-rs (Whisper.cpp)");
                     log::info!("  - STT model: {}", stt_model_name);
+                    log::info!("  - STT language: {}", stt_language);
                     log::info!("  - Model path: {:?}", model_path);
                     log::info!("  - VAD sensitivity: {}", vad_sensitivity);
                     log::info!("  - VAD timeout: {}ms", vad_timeout_ms);
+                    log::info!("  - VAD engine: {}", vad_engine);
+                    log::info!("  - Barge-in: {}", barge_in_mode);
 
                     // Check if models are present
                     if !pipeline.check_readiness() {
                         log::warn!("⚠ STT model not found!");
                         log::warn!("  Please download: {}", stt_model_name);
                         log::warn!("  Place it in: {:?}", model_path);
+                    } else {
+                        // Warm the Whisper cache so the first voice command doesn't wait for the load
+                        let whisper_model = pipeline.get_stt_model_path();
+                        let whisper_cache = whisper_cache.clone();
+                        std::thread::spawn(move || {
+                            if let Err(e) = whisper_cache.get(&whisper_model) {
+                                log::warn!("⚠ Failed to preload Whisper model: {}", e);
+                            }
+                        });
                     }
 
                     Arc::new(StdMutex::new(pipeline))
@@ -1,15 +1,27 @@
+use crate::database::Persona;
+use crate::ollama_api::OllamaClient;
+use crate::prompt_builder::{build_messages, token_budget, PromptContext, TokenBudget};
+use crate::tools::{ToolDefinition, ToolRegistry};
 use reqwest::Client;
-use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-use tokio::sync::Mutex as TokioMutex;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Duration, Instant};
+use tokio::task::AbortHandle;
 
 // LLM Configuration Constants
 const DEFAULT_MAX_TOKENS: u32 = 512;
 const DEFAULT_TEMPERATURE: f32 = 0.7;
 const DEFAULT_TIMEOUT_SECS: u64 = 120; // 2 minutes for LLM generation
+const DEFAULT_CONTEXT_TOKEN_BUDGET: u32 = 2048; // Prompt tokens available for system prompt + history + user prompt
+const MAX_TOOL_ROUNDS: usize = 5; // Maximum request/tool-execution round trips before forcing a final answer
+const BACKEND_RETRY_SECS: u64 = 30; // A backend that failed is tried last for this long
+const HEALTH_CHECK_TIMEOUT_SECS: u64 = 3; // Timeout for backend health checks (GET /models)
+const PRIMARY_BACKEND_NAME: &str = "primary"; // Name of the backend configured in Settings
 
 /// OpenAI-compatible Chat Completion Request
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 struct ChatCompletionRequest {
     model: String,
     messages: Vec<ChatMessage>,
@@ -17,14 +29,121 @@ struct ChatCompletionRequest {
     max_tokens: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stop: Vec<String>,
     stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<ToolDefinition>>,
+}
+
+/// Per-request generation parameters (set from the conversation's persona)
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenerationParams {
+    /// Replaces the engine's default system prompt when set
+    pub system_prompt: Option<String>,
+    pub temperature: f32,
+    pub max_tokens: u32,
+    pub top_p: Option<f32>,
+    pub stop: Vec<String>,
+    /// Overrides the configured model when set
+    pub model_name: Option<String>,
+}
+
+impl Default for GenerationParams {
+    fn default() -> Self {
+        GenerationParams {
+            system_prompt: None,
+            temperature: DEFAULT_TEMPERATURE,
+            max_tokens: DEFAULT_MAX_TOKENS,
+            top_p: None,
+            stop: Vec::new(),
+            model_name: None,
+        }
+    }
+}
+
+impl From<&Persona> for GenerationParams {
+    fn from(persona: &Persona) -> Self {
+        GenerationParams {
+            system_prompt: Some(persona.system_prompt.clone()).filter(|p| !p.trim().is_empty()),
+            temperature: persona.temperature,
+            max_tokens: persona.max_tokens,
+            top_p: persona.top_p,
+            stop: persona.stop.clone(),
+            model_name: persona.model_name.clone().filter(|m| !m.trim().is_empty()),
+        }
+    }
 }
 
 /// Chat message in OpenAI format
 #[derive(Debug, Clone, Serialize, Deserialize)]
-struct ChatMessage {
-    role: String, // "system", "user", or "assistant"
-    content: String,
+pub(crate) struct ChatMessage {
+    pub(crate) role: String, // "system", "user", "assistant", or "tool"
+    /// Assistant messages that only carry tool calls have `"content": null`
+    #[serde(default, deserialize_with = "null_as_empty")]
+    pub(crate) content: String,
+    /// Tool calls requested by the assistant
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tool_calls: Option<Vec<ToolCall>>,
+    /// ID of the tool call this message answers (role "tool" only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tool_call_id: Option<String>,
+}
+
+impl ChatMessage {
+    pub(crate) fn new(role: &str, content: &str) -> Self {
+        ChatMessage {
+            role: role.to_string(),
+            content: content.to_string(),
+            tool_calls: None,
+            tool_call_id: None,
+        }
+    }
+
+    /// Result of a tool call, sent back to the model
+    fn tool_result(tool_call_id: &str, content: &str) -> Self {
+        ChatMessage {
+            role: "tool".to_string(),
+            content: content.to_string(),
+            tool_calls: None,
+            tool_call_id: Some(tool_call_id.to_string()),
+        }
+    }
+}
+
+/// Tool call requested by the model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ToolCall {
+    #[serde(default)]
+    id: String,
+    #[serde(rename = "type", default = "default_tool_call_type")]
+    kind: String,
+    function: FunctionCall,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FunctionCall {
+    name: String,
+    /// JSON-encoded arguments (some servers send an object instead of a string)
+    #[serde(deserialize_with = "string_or_json")]
+    arguments: String,
+}
+
+fn default_tool_call_type() -> String {
+    "function".to_string()
+}
+
+fn null_as_empty<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Ok(Option::<String>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+fn string_or_json<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Ok(match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::String(s) => s,
+        other => other.to_string(),
+    })
 }
 
 /// OpenAI-compatible Chat Completion Response
@@ -38,19 +157,257 @@ struct Choice {
     message: ChatMessage,
 }
 
+/// OpenAI-compatible streaming chunk (`chat.completion.chunk`)
+#[derive(Debug, Deserialize)]
+struct ChatCompletionChunk {
+    choices: Vec<ChunkChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChunkChoice {
+    #[serde(default)]
+    delta: ChunkDelta,
+}
+
+/// Incremental message content; the first chunk usually only carries the role
+#[derive(Debug, Default, Deserialize)]
+struct ChunkDelta {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+/// Incremental parser for server-sent events
+///
+/// Network chunks don't line up with event boundaries, so bytes are buffered
+/// until a full line is available. Only `data:` fields are of interest; comments
+/// (`: keep-alive`) and other fields are ignored.
+#[derive(Debug, Default)]
+struct SseParser {
+    buffer: Vec<u8>,
+}
+
+impl SseParser {
+    /// Feed raw bytes and return the payloads of all complete `data:` lines
+    fn push(&mut self, bytes: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(bytes);
+
+        let mut payloads = Vec::new();
+        while let Some(newline) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=newline).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\r', '\n']);
+
+            if let Some(data) = line.strip_prefix("data:") {
+                payloads.push(data.trim_start().to_string());
+            }
+        }
+
+        payloads
+    }
+}
+
+/// Generated response together with the backend that produced it
+#[derive(Debug, Clone, Serialize)]
+pub struct LlmResponse {
+    pub content: String,
+    /// Name of the backend in the fallback chain that answered
+    pub backend: String,
+}
+
+/// One OpenAI-compatible endpoint in the provider fallback chain
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LlmBackendConfig {
+    /// Display name recorded in responses (e.g., "lm-studio", "remote")
+    pub name: String,
+    /// Base URL of the API (e.g., "http://192.168.1.20:1234/v1")
+    pub api_base_url: String,
+    /// Model to request from this backend (None = the configured or persona model)
+    #[serde(default)]
+    pub model_name: Option<String>,
+    /// API key (loaded from the OS keyring, never persisted in settings)
+    #[serde(default, skip_serializing)]
+    pub api_key: Option<String>,
+    /// Request timeout in seconds
+    #[serde(default = "default_backend_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_backend_timeout_secs() -> u64 {
+    DEFAULT_TIMEOUT_SECS
+}
+
+/// Health of a backend as reported to the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendStatus {
+    pub name: String,
+    pub api_base_url: String,
+    pub healthy: bool,
+    pub latency_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// Runtime state of a backend: an HTTP client with the backend's timeout and failure tracking
+struct LlmBackend {
+    config: LlmBackendConfig,
+    client: Client,
+    /// When the backend last failed to answer (cleared on success)
+    last_failure: StdMutex<Option<Instant>>,
+}
+
+impl LlmBackendConfig {
+    /// Check the base URL format and timeout
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.api_base_url.starts_with("http://") && !self.api_base_url.starts_with("https://") {
+            return Err(format!(
+                "Invalid API Base URL: '{}'. Must start with http:// or https://",
+                self.api_base_url
+            ));
+        }
+
+        if self.timeout_secs == 0 {
+            return Err(format!("Timeout for LLM backend '{}' must be greater than 0", self.name));
+        }
+
+        Ok(())
+    }
+}
+
+impl LlmBackend {
+    fn new(config: LlmBackendConfig) -> Result<Self, String> {
+        config.validate()?;
+
+        let client = Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+        Ok(LlmBackend {
+            config,
+            client,
+            last_failure: StdMutex::new(None),
+        })
+    }
+
+    /// Chat completions endpoint URL
+    fn endpoint(&self) -> String {
+        format!("{}/chat/completions", self.config.api_base_url.trim_end_matches('/'))
+    }
+
+    /// Check if the backend failed within the last `BACKEND_RETRY_SECS`
+    fn recently_failed(&self) -> bool {
+        self.last_failure
+            .lock()
+            .map(|failure| failure.map(|t| t.elapsed() < Duration::from_secs(BACKEND_RETRY_SECS)).unwrap_or(false))
+            .unwrap_or(false)
+    }
+
+    /// Record whether the last request (or health check) succeeded
+    fn record_result(&self, success: bool) {
+        if let Ok(mut failure) = self.last_failure.lock() {
+            *failure = if success { None } else { Some(Instant::now()) };
+        }
+    }
+
+    /// Probe the backend with `GET /models`
+    async fn health_check(&self) -> BackendStatus {
+        let url = format!("{}/models", self.config.api_base_url.trim_end_matches('/'));
+        let started = Instant::now();
+
+        let mut request = self.client.get(&url).timeout(Duration::from_secs(HEALTH_CHECK_TIMEOUT_SECS));
+        if let Some(api_key) = &self.config.api_key {
+            request = request.header("Authorization", format!("Bearer {}", api_key));
+        }
+
+        let error = match request.send().await {
+            Ok(response) if response.status().is_success() => None,
+            Ok(response) => Some(format!("HTTP {}", response.status())),
+            Err(e) => Some(e.to_string()),
+        };
+
+        self.record_result(error.is_none());
+
+        BackendStatus {
+            name: self.config.name.clone(),
+            api_base_url: self.config.api_base_url.clone(),
+            healthy: error.is_none(),
+            latency_ms: error.is_none().then(|| started.elapsed().as_millis() as u64),
+            error,
+        }
+    }
+}
+
+/// Why a request to a backend failed
+enum RequestError {
+    /// Backend unreachable, timed out, overloaded or missing the model: try the next backend
+    Unavailable(String),
+    /// Backend rejected the request itself (e.g., 400 Bad Request): other backends won't help
+    Rejected(String),
+}
+
+/// Aborts the running generation without locking the `LLMEngine`
+///
+/// A streamed answer holds the engine lock until it is complete, so
+/// `cancel_generation` and barge-in go through this handle instead. Cloning shares it.
+#[derive(Clone, Default)]
+pub struct GenerationCanceller {
+    /// Generation number and abort handle of the running generation
+    current: Arc<StdMutex<Option<(u64, AbortHandle)>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl GenerationCanceller {
+    fn register(&self, handle: AbortHandle) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        *self.lock() = Some((id, handle));
+        id
+    }
+
+    /// Forget a finished generation (unless a newer one replaced it)
+    fn unregister(&self, id: u64) {
+        let mut current = self.lock();
+        if current.as_ref().is_some_and(|(current_id, _)| *current_id == id) {
+            *current = None;
+        }
+    }
+
+    /// Abort the running generation; returns false if none was running
+    ///
+    /// The generating call returns "Generation cancelled by user".
+    pub fn cancel(&self) -> bool {
+        match self.lock().take() {
+            Some((_, handle)) => {
+                handle.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<(u64, AbortHandle)>> {
+        self.current.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
 /// Universal LLM Client for OpenAI-compatible APIs
 ///
 /// This module provides a "bring your own backend" approach, allowing
 /// users to connect to any local AI server that exposes an OpenAI-compatible
 /// API endpoint (e.g., Ollama, LM Studio, Jan.ai, LocalAI, etc.)
 pub struct LLMEngine {
-    client: Client,
-    api_base_url: String,
+    /// Fallback chain: the backend from Settings first, then the configured fallbacks in order
+    backends: Vec<Arc<LlmBackend>>,
     model_name: String,
-    api_key: Option<String>,
     system_prompt: String,
+    /// Maximum number of prompt tokens (system prompt + history + user prompt) sent per request
+    context_token_budget: u32,
+    /// Model context window from Settings (None = detect via Ollama `/api/show`)
+    context_length: Option<u32>,
+    /// Context windows detected per model name (None = server didn't report one)
+    detected_context_lengths: HashMap<String, Option<u32>>,
+    /// Sampling parameters, system prompt and model overrides for the next request
+    generation: GenerationParams,
     /// Abort handle for the current generation task (allows immediate cancellation)
-    current_task: Arc<TokioMutex<Option<tokio::task::AbortHandle>>>,
+    current_task: GenerationCanceller,
 }
 
 impl LLMEngine {
@@ -72,18 +429,13 @@ impl LLMEngine {
         log::info!("  Model: {}", model_name);
         log::info!("  API Key: {}", if api_key.is_some() { "provided" } else { "not provided" });
 
-        // Validate base URL format
-        if !api_base_url.starts_with("http://") && !api_base_url.starts_with("https://") {
-            return Err(format!(
-                "Invalid API Base URL: '{}'. Must start with http:// or https://",
-                api_base_url
-            ));
-        }
-
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(DEFAULT_TIMEOUT_SECS))
-            .build()
-            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        let primary = LlmBackend::new(LlmBackendConfig {
+            name: PRIMARY_BACKEND_NAME.to_string(),
+            api_base_url,
+            model_name: None,
+            api_key,
+            timeout_secs: DEFAULT_TIMEOUT_SECS,
+        })?;
 
         let system_prompt = system_prompt.unwrap_or_else(|| {
             "You are Aura, a helpful AI assistant. Provide concise, accurate, and friendly responses."
@@ -93,12 +445,14 @@ impl LLMEngine {
         log::info!("LLM engine initialized successfully");
 
         Ok(LLMEngine {
-            client,
-            api_base_url,
+            backends: vec![Arc::new(primary)],
             model_name,
-            api_key,
             system_prompt,
-            current_task: Arc::new(TokioMutex::new(None)),
+            context_token_budget: DEFAULT_CONTEXT_TOKEN_BUDGET,
+            context_length: None,
+            detected_context_lengths: HashMap::new(),
+            generation: GenerationParams::default(),
+            current_task: GenerationCanceller::default(),
         })
     }
 
@@ -107,81 +461,144 @@ impl LLMEngine {
     /// This method sends a request to the configured OpenAI-compatible API
     /// and returns the generated response.
     ///
-    /// The generation can be immediately cancelled by calling `cancel_generation()`,
+    /// The generation can be immediately cancelled through `canceller()`,
     /// which will abort the HTTP request and return control to the caller.
     pub async fn generate_response(&self, user_prompt: &str) -> Result<String, String> {
-        log::info!("Generating response for prompt: '{}'", user_prompt);
+        self.generate_response_with_history(&PromptContext::default(), user_prompt)
+            .await
+            .map(|response| response.content)
+    }
 
-        // Construct messages array with system prompt and user message
-        let messages = vec![
-            ChatMessage {
-                role: "system".to_string(),
-                content: self.system_prompt.clone(),
-            },
-            ChatMessage {
-                role: "user".to_string(),
-                content: user_prompt.to_string(),
-            },
-        ];
+    /// Generate a response to a user prompt within an ongoing conversation
+    ///
+    /// Prior turns from `context.history` (oldest first, as returned by `Database::load_messages`)
+    /// are replayed to the model so follow-up questions keep their context, the rolling
+    /// summary is added to the system prompt and web search results are placed in front
+    /// of the question. Everything is cut to the prompt budget, oldest turns first
+    /// (see `prompt_builder`).
+    ///
+    /// If a backend is unreachable the next one in the fallback chain is tried; the
+    /// returned `LlmResponse` records which backend answered.
+    pub async fn generate_response_with_history(
+        &self,
+        context: &PromptContext<'_>,
+        user_prompt: &str,
+    ) -> Result<LlmResponse, String> {
+        let request = self.build_request(context, user_prompt, false);
 
-        // Build the request
-        let request = ChatCompletionRequest {
-            model: self.model_name.clone(),
-            messages,
-            max_tokens: Some(DEFAULT_MAX_TOKENS),
-            temperature: Some(DEFAULT_TEMPERATURE),
-            stream: false, // Non-streaming for simplicity
-        };
+        // Clone data needed for the spawned task
+        let backends = self.backends.clone();
 
-        // Construct the full endpoint URL
-        let endpoint = format!("{}/chat/completions", self.api_base_url.trim_end_matches('/'));
+        self.run_abortable(async move {
+            let (response, backend) = send_with_fallback(&backends, &request).await?;
 
-        log::info!("Sending request to: {}", endpoint);
-        log::debug!("Request payload: {:?}", request);
+            // Parse response
+            let completion: ChatCompletionResponse = response
+                .json()
+                .await
+                .map_err(|e| format!("Failed to parse API response: {}. Make sure the server is OpenAI-compatible.", e))?;
+
+            // Extract the assistant's message
+            let assistant_message = completion
+                .choices
+                .first()
+                .ok_or("No response choices returned from API")?
+                .message
+                .content
+                .clone();
+
+            Ok(LlmResponse {
+                content: assistant_message,
+                backend,
+            })
+        })
+        .await
+    }
+
+    /// Generate a response, letting the model call registered tools along the way
+    ///
+    /// The registry's definitions are sent in the request's `tools` array. Whenever
+    /// the model answers with `tool_calls`, each call is executed against its Rust
+    /// handler and the result is sent back as a `tool` message; this repeats until
+    /// the model returns plain text (or `MAX_TOOL_ROUNDS` is reached). Tool failures
+    /// are reported to the model as text so it can explain them to the user.
+    ///
+    /// Servers or models without tool support get the request again without `tools`.
+    pub async fn generate_response_with_tools(
+        &self,
+        context: &PromptContext<'_>,
+        user_prompt: &str,
+        tools: Arc<ToolRegistry>,
+    ) -> Result<LlmResponse, String> {
+        if tools.is_empty() {
+            return self.generate_response_with_history(context, user_prompt).await;
+        }
+
+        let mut request = self.build_request(context, user_prompt, false);
+        request.tools = Some(tools.definitions());
 
         // Clone data needed for the spawned task
-        let client = self.client.clone();
-        let api_key = self.api_key.clone();
-        let endpoint_clone = endpoint.clone();
+        let backends = self.backends.clone();
 
-        // Spawn the HTTP request in an abortable task
-        let task_handle = tokio::spawn(async move {
-            // Build HTTP request
-            let mut http_request = client.post(&endpoint_clone).json(&request);
+        self.run_abortable(async move {
+            for round in 1..=MAX_TOOL_ROUNDS {
+                let (response, backend) = match send_with_fallback(&backends, &request).await {
+                    Ok(answered) => answered,
+                    Err(e) if request.tools.is_some() && is_tools_unsupported_error(&e) => {
+                        log::warn!("Model does not support tool calling, retrying without tools: {}", e);
+                        request.tools = None;
+                        send_with_fallback(&backends, &request).await?
+                    }
+                    Err(e) => return Err(e),
+                };
 
-            // Add API key to headers if provided
-            if let Some(ref api_key) = api_key {
-                http_request = http_request.header("Authorization", format!("Bearer {}", api_key));
-            }
+                let completion: ChatCompletionResponse = response
+                    .json()
+                    .await
+                    .map_err(|e| format!("Failed to parse API response: {}. Make sure the server is OpenAI-compatible.", e))?;
 
-            // Send request
-            let response = http_request
-                .send()
-                .await
-                .map_err(|e| {
-                    format!(
-                        "Failed to connect to LLM API at {}: {}. Make sure your AI server is running.",
-                        endpoint_clone, e
-                    )
-                })?;
-
-            // Check for HTTP errors
-            if !response.status().is_success() {
-                let status = response.status();
-                let error_body = response.text().await.unwrap_or_else(|_| "Unable to read error body".to_string());
-                return Err(format!(
-                    "LLM API returned error {}: {}",
-                    status, error_body
-                ));
+                let message = completion
+                    .choices
+                    .into_iter()
+                    .next()
+                    .ok_or("No response choices returned from API")?
+                    .message;
+
+                let tool_calls = message.tool_calls.clone().unwrap_or_default();
+                if tool_calls.is_empty() {
+                    return Ok(LlmResponse {
+                        content: message.content,
+                        backend,
+                    });
+                }
+
+                log::info!("Model requested {} tool call(s) (round {}/{})", tool_calls.len(), round, MAX_TOOL_ROUNDS);
+                request.messages.push(message);
+
+                for call in tool_calls {
+                    let result = match tools.execute(&call.function.name, &call.function.arguments).await {
+                        Ok(output) => {
+                            log::info!("✓ Tool {} succeeded", call.function.name);
+                            output
+                        }
+                        Err(e) => {
+                            log::warn!("Tool {} failed: {}", call.function.name, e);
+                            format!("Error: {}", e)
+                        }
+                    };
+                    request.messages.push(ChatMessage::tool_result(&call.id, &result));
+                }
             }
 
-            // Parse response
+            // Out of tool rounds: ask for a final answer without offering tools again
+            log::warn!("Reached {} tool rounds, requesting final answer without tools", MAX_TOOL_ROUNDS);
+            request.tools = None;
+            let (response, backend) = send_with_fallback(&backends, &request).await?;
             let completion: ChatCompletionResponse = response
                 .json()
                 .await
                 .map_err(|e| format!("Failed to parse API response: {}. Make sure the server is OpenAI-compatible.", e))?;
 
-            // Extract the assistant's message
             let assistant_message = completion
                 .choices
                 .first()
@@ -190,15 +607,240 @@ impl LLMEngine {
                 .content
                 .clone();
 
-            Ok(assistant_message)
-        });
+            Ok(LlmResponse {
+                content: assistant_message,
+                backend,
+            })
+        })
+        .await
+    }
 
-        // Store the abort handle so cancel_generation can abort this task
-        {
-            let mut current_task = self.current_task.lock().await;
-            *current_task = Some(task_handle.abort_handle());
+    /// Generate a response and stream it token-by-token as it is produced
+    ///
+    /// Sends the request with `stream: true` and parses the server-sent events
+    /// (`data: {chat.completion.chunk}` lines). Every non-empty content delta is
+    /// passed to `on_delta` as soon as it arrives, and the full response is returned
+    /// once the server sends `data: [DONE]` or closes the stream.
+    ///
+    /// Like `generate_response`, the stream is aborted mid-way through `canceller()`,
+    /// which doesn't need the engine (a caller usually holds it for the whole stream).
+    pub async fn generate_response_streaming<F>(
+        &self,
+        context: &PromptContext<'_>,
+        user_prompt: &str,
+        on_delta: F,
+    ) -> Result<LlmResponse, String>
+    where
+        F: Fn(&str) + Send + 'static,
+    {
+        let request = self.build_request(context, user_prompt, true);
+
+        // Clone data needed for the spawned task
+        let backends = self.backends.clone();
+
+        self.run_abortable(async move {
+            // Failover only happens before the first token; a stream is never restarted elsewhere
+            let (mut response, backend) = send_with_fallback(&backends, &request).await?;
+
+            // Some servers ignore `stream: true` and answer with a regular completion
+            let is_event_stream = response
+                .headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.starts_with("text/event-stream"))
+                .unwrap_or(false);
+
+            if !is_event_stream {
+                log::debug!("Server did not return an event stream, falling back to a single response");
+                let completion: ChatCompletionResponse = response
+                    .json()
+                    .await
+                    .map_err(|e| format!("Failed to parse API response: {}. Make sure the server is OpenAI-compatible.", e))?;
+
+                let assistant_message = completion
+                    .choices
+                    .first()
+                    .ok_or("No response choices returned from API")?
+                    .message
+                    .content
+                    .clone();
+
+                on_delta(&assistant_message);
+                return Ok(LlmResponse {
+                    content: assistant_message,
+                    backend,
+                });
+            }
+
+            let mut parser = SseParser::default();
+            let mut full_response = String::new();
+
+            'stream: while let Some(bytes) = response
+                .chunk()
+                .await
+                .map_err(|e| format!("Failed to read streamed response: {}", e))?
+            {
+                for data in parser.push(&bytes) {
+                    if data == "[DONE]" {
+                        break 'stream;
+                    }
+
+                    let chunk: ChatCompletionChunk = match serde_json::from_str(&data) {
+                        Ok(chunk) => chunk,
+                        Err(e) => {
+                            log::warn!("Skipping unparseable stream chunk: {} ({})", data, e);
+                            continue;
+                        }
+                    };
+
+                    for choice in chunk.choices {
+                        if let Some(content) = choice.delta.content {
+                            if !content.is_empty() {
+                                on_delta(&content);
+                                full_response.push_str(&content);
+                            }
+                        }
+                    }
+                }
+            }
+
+            Ok(LlmResponse {
+                content: full_response,
+                backend,
+            })
+        })
+        .await
+    }
+
+    /// Build the chat completion request for a prompt
+    fn build_request(
+        &self,
+        context: &PromptContext<'_>,
+        user_prompt: &str,
+        stream: bool,
+    ) -> ChatCompletionRequest {
+        log::info!(
+            "Generating response for prompt: '{}' ({} prior messages, streaming: {})",
+            user_prompt,
+            context.history.len(),
+            stream
+        );
+
+        let system_prompt = self.generation.system_prompt.as_deref().unwrap_or(&self.system_prompt);
+        let budget = self.token_budget();
+        let messages = build_messages(system_prompt, context, user_prompt, budget.prompt);
+
+        log::debug!(
+            "Replaying {} of {} prior messages (budget: {} tokens, answer: {} tokens)",
+            messages.len() - 2,
+            context.history.len(),
+            budget.prompt,
+            budget.max_output
+        );
+
+        // Build the request
+        let request = ChatCompletionRequest {
+            model: self.request_model_name(),
+            messages,
+            max_tokens: Some(budget.max_output),
+            temperature: Some(self.generation.temperature),
+            top_p: self.generation.top_p,
+            stop: self.generation.stop.clone(),
+            stream,
+            tools: None,
+        };
+
+        log::debug!("Request payload: {:?}", request);
+
+        request
+    }
+
+    /// Prompt and answer tokens per request: the configured budget and `max_tokens`,
+    /// fitted into the model's context window
+    fn token_budget(&self) -> TokenBudget {
+        token_budget(
+            self.context_token_budget,
+            self.effective_context_length(),
+            self.generation.max_tokens,
+        )
+    }
+
+    /// Prompt tokens available per request (see `token_budget`)
+    pub fn prompt_token_budget(&self) -> u32 {
+        self.token_budget().prompt
+    }
+
+    /// Model the next request is sent to (persona override or the configured model)
+    fn request_model_name(&self) -> String {
+        self.generation.model_name.clone().unwrap_or_else(|| self.model_name.clone())
+    }
+
+    /// Context window of the model the next request is sent to, if known
+    fn effective_context_length(&self) -> Option<u32> {
+        self.context_length.or_else(|| {
+            self.detected_context_lengths
+                .get(&self.request_model_name())
+                .copied()
+                .flatten()
+        })
+    }
+
+    /// Set the model context window from Settings (None = detect via Ollama)
+    pub fn set_context_length(&mut self, context_length: Option<u32>) {
+        if context_length != self.context_length {
+            log::info!("Updating LLM context length: {:?} -> {:?}", self.context_length, context_length);
+            self.context_length = context_length;
+        }
+    }
+
+    /// Look up the context window of the current model via Ollama `/api/show`
+    ///
+    /// Queried until there is an answer for the model name, then cached: a server that
+    /// can't be reached yet (Ollama still starting) is asked again on the next request.
+    /// Servers that aren't Ollama leave the context length unknown, in which case only
+    /// `context_token_budget` applies.
+    pub async fn refresh_context_length(&mut self) {
+        if self.context_length.is_some() {
+            return;
+        }
+
+        let model_name = self.request_model_name();
+        if self.detected_context_lengths.contains_key(&model_name) {
+            return;
         }
 
+        let detected = match OllamaClient::from_api_base_url(&self.backends[0].config.api_base_url) {
+            Ok(client) => match client.context_length(&model_name).await {
+                Ok(length) => length,
+                Err(e) => {
+                    log::debug!("Could not query context length for {}: {}", model_name, e);
+                    return;
+                }
+            },
+            Err(e) => {
+                log::debug!("Could not create Ollama client: {}", e);
+                None
+            }
+        };
+
+        match detected {
+            Some(length) => log::info!("Detected context length for {}: {} tokens", model_name, length),
+            None => log::info!("Context length for {} unknown, using the configured token budget", model_name),
+        }
+        self.detected_context_lengths.insert(model_name, detected);
+    }
+
+    /// Run a generation future in an abortable task registered with the canceller
+    async fn run_abortable<Fut>(&self, generation: Fut) -> Result<LlmResponse, String>
+    where
+        Fut: std::future::Future<Output = Result<LlmResponse, String>> + Send + 'static,
+    {
+        // Spawn the HTTP request in an abortable task
+        let task_handle = tokio::spawn(generation);
+
+        // Store the abort handle so cancel_generation can abort this task
+        let generation_id = self.current_task.register(task_handle.abort_handle());
+
         // Wait for the task to complete (or be aborted)
         let result = match task_handle.await {
             Ok(response_result) => {
@@ -218,59 +860,141 @@ impl LLMEngine {
         };
 
         // Clear the abort handle now that the task is done
-        {
-            let mut current_task = self.current_task.lock().await;
-            *current_task = None;
-        }
+        self.current_task.unregister(generation_id);
 
         // Log result and return
         match &result {
-            Ok(message) => log::info!("Response received: {} characters", message.len()),
+            Ok(response) => log::info!("Response received from {}: {} characters", response.backend, response.content.len()),
             Err(e) => log::warn!("Generation failed: {}", e),
         }
 
         result
     }
 
-    /// Cancel the current generation immediately
+    /// A copy of this engine for background requests (e.g. conversation summaries)
     ///
-    /// This aborts the HTTP request task, causing generate_response to return
-    /// with a cancellation error. The UI will immediately return to idle state.
-    pub async fn cancel_generation(&self) {
-        log::info!("Cancellation requested - aborting current generation task");
-
-        let mut current_task = self.current_task.lock().await;
-        if let Some(abort_handle) = current_task.take() {
-            abort_handle.abort();
-            log::info!("✓ Generation task aborted successfully");
-        } else {
-            log::warn!("No active generation task to cancel");
+    /// It uses the same backends, model and budgets but its own generation params and
+    /// canceller, so it runs without holding the shared engine lock and
+    /// `cancel_generation` doesn't reach it.
+    pub fn detached(&self) -> LLMEngine {
+        LLMEngine {
+            backends: self.backends.clone(),
+            model_name: self.model_name.clone(),
+            system_prompt: self.system_prompt.clone(),
+            context_token_budget: self.context_token_budget,
+            context_length: self.context_length,
+            detected_context_lengths: self.detected_context_lengths.clone(),
+            generation: GenerationParams::default(),
+            current_task: GenerationCanceller::default(),
+        }
+    }
+
+    /// Handle to cancel generations without holding the engine lock
+    pub fn canceller(&self) -> GenerationCanceller {
+        self.current_task.clone()
+    }
+
+    /// Set the maximum number of prompt tokens sent per request
+    pub fn set_context_token_budget(&mut self, budget: u32) {
+        if budget != self.context_token_budget {
+            log::info!("Updating LLM context token budget: {} -> {}", self.context_token_budget, budget);
+            self.context_token_budget = budget;
+        }
+    }
+
+    /// Set the generation parameters used by subsequent requests
+    ///
+    /// Pass `GenerationParams::default()` to restore the built-in defaults
+    /// (conversations without a persona).
+    pub fn set_generation_params(&mut self, params: GenerationParams) {
+        if params != self.generation {
+            log::info!(
+                "Updating LLM generation params: temperature={}, max_tokens={}, top_p={:?}, model={:?}",
+                params.temperature,
+                params.max_tokens,
+                params.top_p,
+                params.model_name
+            );
+            self.generation = params;
         }
     }
 
     /// Get information about the configured LLM
     pub fn model_info(&self) -> ModelInfo {
         ModelInfo {
-            api_base_url: self.api_base_url.clone(),
+            api_base_url: self.backends[0].config.api_base_url.clone(),
             model_name: self.model_name.clone(),
             system_prompt: self.system_prompt.clone(),
         }
     }
 
-    /// Update the model configuration
+    /// Update the model configuration (the primary backend; fallbacks are kept)
     pub fn update_config(
         &mut self,
         api_base_url: String,
         model_name: String,
         api_key: Option<String>,
-    ) {
+    ) -> Result<(), String> {
         log::info!("Updating LLM configuration");
         log::info!("  New API Base URL: {}", api_base_url);
         log::info!("  New Model: {}", model_name);
 
-        self.api_base_url = api_base_url;
+        self.backends[0] = Arc::new(LlmBackend::new(LlmBackendConfig {
+            name: PRIMARY_BACKEND_NAME.to_string(),
+            api_base_url,
+            model_name: None,
+            api_key,
+            timeout_secs: self.backends[0].config.timeout_secs,
+        })?);
         self.model_name = model_name;
-        self.api_key = api_key;
+
+        Ok(())
+    }
+
+    /// Set how long a request to the primary backend may take before failing over
+    pub fn set_primary_timeout(&mut self, timeout_secs: u64) -> Result<(), String> {
+        let primary = &self.backends[0].config;
+        if primary.timeout_secs == timeout_secs {
+            return Ok(());
+        }
+
+        log::info!("Updating primary LLM backend timeout: {}s -> {}s", primary.timeout_secs, timeout_secs);
+        self.backends[0] = Arc::new(LlmBackend::new(LlmBackendConfig {
+            timeout_secs,
+            ..primary.clone()
+        })?);
+
+        Ok(())
+    }
+
+    /// Replace the fallback backends tried (in order) when the primary backend is unavailable
+    pub fn set_fallback_backends(&mut self, configs: Vec<LlmBackendConfig>) -> Result<(), String> {
+        let fallbacks = configs
+            .into_iter()
+            .map(|config| LlmBackend::new(config).map(Arc::new))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        log::info!("LLM fallback chain: {} fallback backend(s)", fallbacks.len());
+        for backend in &fallbacks {
+            log::info!(
+                "  - {} at {} (timeout: {}s)",
+                backend.config.name,
+                backend.config.api_base_url,
+                backend.config.timeout_secs
+            );
+        }
+
+        self.backends.truncate(1);
+        self.backends.extend(fallbacks);
+
+        Ok(())
+    }
+
+    /// Health check every backend in the fallback chain
+    ///
+    /// Results also update failover ordering: unhealthy backends are tried last.
+    pub async fn check_backends(&self) -> Vec<BackendStatus> {
+        futures_util::future::join_all(self.backends.iter().map(|backend| backend.health_check())).await
     }
 }
 
@@ -281,9 +1005,132 @@ pub struct ModelInfo {
     pub system_prompt: String,
 }
 
+/// Send a chat completion request and turn connection/HTTP failures into readable errors
+async fn send_request(
+    client: &Client,
+    endpoint: &str,
+    api_key: Option<&str>,
+    request: &ChatCompletionRequest,
+) -> Result<reqwest::Response, RequestError> {
+    // Build HTTP request
+    let mut http_request = client.post(endpoint).json(request);
+
+    // Add API key to headers if provided
+    if let Some(api_key) = api_key {
+        http_request = http_request.header("Authorization", format!("Bearer {}", api_key));
+    }
+
+    // Send request
+    let response = http_request
+        .send()
+        .await
+        .map_err(|e| {
+            RequestError::Unavailable(format!(
+                "Failed to connect to LLM API at {}: {}. Make sure your AI server is running.",
+                endpoint, e
+            ))
+        })?;
+
+    // Check for HTTP errors
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_body = response.text().await.unwrap_or_else(|_| "Unable to read error body".to_string());
+        let message = format!(
+            "LLM API returned error {}: {}",
+            status, error_body
+        );
+
+        // Server-side failures, rate limits and unknown models may succeed elsewhere
+        let unavailable = status.is_server_error()
+            || status == reqwest::StatusCode::NOT_FOUND
+            || status == reqwest::StatusCode::REQUEST_TIMEOUT
+            || status == reqwest::StatusCode::TOO_MANY_REQUESTS;
+
+        return Err(if unavailable {
+            RequestError::Unavailable(message)
+        } else {
+            RequestError::Rejected(message)
+        });
+    }
+
+    Ok(response)
+}
+
+/// Send a request through the fallback chain and return the response with the answering backend's name
+///
+/// Backends are tried in configured order, except that backends which failed within the
+/// last `BACKEND_RETRY_SECS` are moved to the end. Only unavailability triggers failover;
+/// a rejected request is returned as-is.
+async fn send_with_fallback(
+    backends: &[Arc<LlmBackend>],
+    request: &ChatCompletionRequest,
+) -> Result<(reqwest::Response, String), String> {
+    let mut ordered: Vec<&Arc<LlmBackend>> = backends.iter().collect();
+    ordered.sort_by_key(|backend| backend.recently_failed());
+
+    let mut failures: Vec<(String, String)> = Vec::new();
+
+    for backend in ordered {
+        let name = &backend.config.name;
+
+        // Backends may serve a different model than the one configured in Settings
+        let backend_request;
+        let request = match &backend.config.model_name {
+            Some(model) if *model != request.model => {
+                backend_request = ChatCompletionRequest {
+                    model: model.clone(),
+                    ..request.clone()
+                };
+                &backend_request
+            }
+            _ => request,
+        };
+
+        let endpoint = backend.endpoint();
+        log::info!("Sending request to: {} (backend: {})", endpoint, name);
+
+        match send_request(&backend.client, &endpoint, backend.config.api_key.as_deref(), request).await {
+            Ok(response) => {
+                backend.record_result(true);
+                if !failures.is_empty() {
+                    log::info!("✓ Failed over to LLM backend '{}'", name);
+                }
+                return Ok((response, name.clone()));
+            }
+            Err(RequestError::Rejected(e)) => return Err(e),
+            Err(RequestError::Unavailable(e)) => {
+                log::warn!("LLM backend '{}' unavailable: {}", name, e);
+                backend.record_result(false);
+                failures.push((name.clone(), e));
+            }
+        }
+    }
+
+    // Keep the original error message when there is nothing to fail over to
+    match failures.len() {
+        0 => Err("No LLM backends configured".to_string()),
+        1 => Err(failures.remove(0).1),
+        _ => Err(format!(
+            "All LLM backends failed: {}",
+            failures
+                .iter()
+                .map(|(name, e)| format!("[{}] {}", name, e))
+                .collect::<Vec<_>>()
+                .join("; ")
+        )),
+    }
+}
+
+/// Check if an API error means the model/server rejected the `tools` parameter
+fn is_tools_unsupported_error(error: &str) -> bool {
+    let error = error.to_lowercase();
+    error.contains("does not support tools") || (error.contains("error 400") && error.contains("tool"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::database::Message;
 
     #[test]
     fn test_llm_engine_creation() {
@@ -306,4 +1153,347 @@ mod tests {
         );
         assert!(engine.is_err());
     }
+
+    fn message(id: i64, role: &str, content: &str) -> Message {
+        Message {
+            id,
+            conversation_id: 1,
+            role: role.to_string(),
+            content: content.to_string(),
+            timestamp: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_build_request_fits_model_context_length() {
+        let mut engine = LLMEngine::new(
+            "http://localhost:11434/v1".to_string(),
+            "llama3".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        let history = vec![
+            message(1, "user", &"x".repeat(2000)), // ~504 tokens
+            message(2, "assistant", "short answer"),
+        ];
+
+        let context = PromptContext {
+            history: &history,
+            ..Default::default()
+        };
+
+        // The 2048-token default budget has room for the long turn
+        assert_eq!(engine.build_request(&context, "next", false).messages.len(), 4);
+
+        // A 1024-token window minus 512 reserved answer tokens does not
+        engine.set_context_length(Some(1024));
+        assert_eq!(engine.build_request(&context, "next", false).messages.len(), 2);
+        assert_eq!(engine.build_request(&context, "next", false).max_tokens, Some(512));
+
+        // Too small for a full answer: the answer is cut, not the window overrun
+        engine.set_context_length(Some(300));
+        let request = engine.build_request(&context, "next", false);
+        assert_eq!(request.max_tokens, Some(300 - engine.prompt_token_budget()));
+    }
+
+    #[test]
+    fn test_sse_parser_handles_split_chunks() {
+        let mut parser = SseParser::default();
+
+        assert!(parser.push(b"data: {\"a\":").is_empty());
+        assert_eq!(parser.push(b"1}\r\n\n: keep-alive\n\ndata: [DONE]\n"), vec!["{\"a\":1}", "[DONE]"]);
+        assert!(parser.push(b"\n").is_empty());
+    }
+
+    #[test]
+    fn test_parse_stream_chunk_delta() {
+        let role_only: ChatCompletionChunk = serde_json::from_str(
+            r#"{"object":"chat.completion.chunk","choices":[{"index":0,"delta":{"role":"assistant"}}]}"#,
+        )
+        .unwrap();
+        assert!(role_only.choices[0].delta.content.is_none());
+
+        let content: ChatCompletionChunk = serde_json::from_str(
+            r#"{"object":"chat.completion.chunk","choices":[{"index":0,"delta":{"content":"Hel"},"finish_reason":null}]}"#,
+        )
+        .unwrap();
+        assert_eq!(content.choices[0].delta.content.as_deref(), Some("Hel"));
+    }
+
+    #[test]
+    fn test_build_request_applies_persona_params() {
+        let mut engine = LLMEngine::new(
+            "http://localhost:1234/v1".to_string(),
+            "llama3".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        let request = engine.build_request(&PromptContext::default(), "Hi", false);
+        assert_eq!(request.model, "llama3");
+        assert_eq!(request.max_tokens, Some(DEFAULT_MAX_TOKENS));
+        let body = serde_json::to_value(&request).unwrap();
+        assert!(body.get("top_p").is_none());
+        assert!(body.get("stop").is_none());
+
+        let persona = Persona {
+            id: 1,
+            name: "Coder".to_string(),
+            system_prompt: "You write Rust.".to_string(),
+            temperature: 0.1,
+            max_tokens: 4096,
+            top_p: Some(0.95),
+            stop: vec!["###".to_string()],
+            model_name: Some("qwen2.5-coder".to_string()),
+            created_at: String::new(),
+        };
+        engine.set_generation_params(GenerationParams::from(&persona));
+
+        let request = engine.build_request(&PromptContext::default(), "Hi", false);
+        assert_eq!(request.model, "qwen2.5-coder");
+        assert_eq!(request.max_tokens, Some(4096));
+        assert_eq!(request.messages[0].content, "You write Rust.");
+        let body = serde_json::to_value(&request).unwrap();
+        assert_eq!(body["stop"][0], "###");
+
+        engine.set_generation_params(GenerationParams::default());
+        let request = engine.build_request(&PromptContext::default(), "Hi", false);
+        assert_eq!(request.model, "llama3");
+        assert!(request.messages[0].content.starts_with("You are Aura"));
+    }
+
+    /// Serve one canned chat completion on a local port and return its base URL
+    async fn mock_backend(content: &'static str) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buffer = vec![0u8; 16 * 1024];
+            let _ = socket.read(&mut buffer).await;
+
+            let body = serde_json::json!({
+                "choices": [{ "message": { "role": "assistant", "content": content } }]
+            })
+            .to_string();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        format!("http://{}/v1", addr)
+    }
+
+    /// Base URL of a local port nothing is listening on
+    async fn dead_backend() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        format!("http://{}/v1", addr)
+    }
+
+    #[tokio::test]
+    async fn test_context_length_is_retried_until_ollama_answers() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            // Still loading, then the model info
+            let responses = [
+                ("503 Service Unavailable", "{}".to_string()),
+                ("200 OK", serde_json::json!({ "model_info": { "llama.context_length": 8192 } }).to_string()),
+            ];
+            for (status, body) in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buffer = vec![0u8; 16 * 1024];
+                let _ = socket.read(&mut buffer).await;
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        let mut engine = LLMEngine::new(format!("http://{}/v1", addr), "llama3".to_string(), None, None).unwrap();
+        engine.refresh_context_length().await;
+        assert_eq!(engine.effective_context_length(), None);
+        assert!(engine.detected_context_lengths.is_empty());
+
+        engine.refresh_context_length().await;
+        assert_eq!(engine.effective_context_length(), Some(8192));
+    }
+
+    #[tokio::test]
+    async fn test_fallback_backend_answers_when_primary_is_down() {
+        let mut engine = LLMEngine::new(dead_backend().await, "llama3".to_string(), None, None).unwrap();
+        engine
+            .set_fallback_backends(vec![LlmBackendConfig {
+                name: "lm-studio".to_string(),
+                api_base_url: mock_backend("Hello from the fallback").await,
+                model_name: Some("phi3".to_string()),
+                api_key: None,
+                timeout_secs: 5,
+            }])
+            .unwrap();
+
+        let response = engine.generate_response_with_history(&PromptContext::default(), "Hi").await.unwrap();
+        assert_eq!(response.content, "Hello from the fallback");
+        assert_eq!(response.backend, "lm-studio");
+        assert!(engine.backends[0].recently_failed());
+    }
+
+    /// Serve a stream that sends one delta and then stays open; returns its base URL
+    async fn stalled_stream_backend() -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buffer = vec![0u8; 16 * 1024];
+            let _ = socket.read(&mut buffer).await;
+
+            let chunk = serde_json::json!({ "choices": [{ "index": 0, "delta": { "content": "Hello" } }] });
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nConnection: close\r\n\r\ndata: {}\n\n",
+                chunk
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            tokio::time::sleep(Duration::from_secs(60)).await;
+        });
+
+        format!("http://{}/v1", addr)
+    }
+
+    #[tokio::test]
+    async fn test_cancel_while_streaming() {
+        let engine = LLMEngine::new(stalled_stream_backend().await, "llama3".to_string(), None, None).unwrap();
+        let canceller = engine.canceller();
+        let engine = Arc::new(tokio::sync::Mutex::new(engine));
+
+        let (delta_tx, mut delta_rx) = tokio::sync::mpsc::unbounded_channel();
+        let streaming = {
+            let engine = engine.clone();
+            tokio::spawn(async move {
+                let llm = engine.lock().await;
+                llm.generate_response_streaming(&PromptContext::default(), "Hi", move |delta| {
+                    let _ = delta_tx.send(delta.to_string());
+                })
+                .await
+            })
+        };
+
+        assert_eq!(delta_rx.recv().await.as_deref(), Some("Hello"));
+        // The stream still holds the engine; cancelling must not need it
+        assert!(engine.try_lock().is_err());
+        assert!(canceller.cancel());
+
+        let result = tokio::time::timeout(Duration::from_secs(5), streaming).await.unwrap().unwrap();
+        assert_eq!(result.unwrap_err(), "Generation cancelled by user");
+        assert!(!canceller.cancel());
+    }
+
+    #[tokio::test]
+    async fn test_detached_engine_is_not_cancelled_with_the_shared_one() {
+        let engine = LLMEngine::new(stalled_stream_backend().await, "llama3".to_string(), None, None).unwrap();
+        let detached = engine.detached();
+
+        let (delta_tx, mut delta_rx) = tokio::sync::mpsc::unbounded_channel();
+        let background = tokio::spawn(async move {
+            detached
+                .generate_response_streaming(&PromptContext::default(), "Summarize", move |delta| {
+                    let _ = delta_tx.send(delta.to_string());
+                })
+                .await
+        });
+
+        assert_eq!(delta_rx.recv().await.as_deref(), Some("Hello"));
+        assert!(!engine.canceller().cancel());
+        assert!(!background.is_finished());
+        background.abort();
+    }
+
+    #[tokio::test]
+    async fn test_single_backend_keeps_original_error() {
+        let engine = LLMEngine::new(dead_backend().await, "llama3".to_string(), None, None).unwrap();
+
+        let error = engine.generate_response("Hi").await.unwrap_err();
+        assert!(error.contains("Make sure your AI server is running"));
+    }
+
+    #[test]
+    fn test_primary_timeout_is_configurable() {
+        let mut engine = LLMEngine::new("http://localhost:11434/v1".to_string(), "llama3".to_string(), None, None).unwrap();
+        assert_eq!(engine.backends[0].config.timeout_secs, DEFAULT_TIMEOUT_SECS);
+
+        engine.set_primary_timeout(15).unwrap();
+        assert_eq!(engine.backends[0].config.timeout_secs, 15);
+        assert_eq!(engine.backends[0].config.name, PRIMARY_BACKEND_NAME);
+
+        assert!(engine.set_primary_timeout(0).is_err());
+        assert_eq!(engine.backends[0].config.timeout_secs, 15);
+    }
+
+    #[test]
+    fn test_backend_config_never_serializes_api_key() {
+        let config: LlmBackendConfig = serde_json::from_str(
+            r#"{"name":"remote","api_base_url":"https://api.example.com/v1","api_key":"secret"}"#,
+        )
+        .unwrap();
+        assert_eq!(config.timeout_secs, DEFAULT_TIMEOUT_SECS);
+        assert_eq!(config.api_key.as_deref(), Some("secret"));
+
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(!json.contains("secret"));
+    }
+
+    #[test]
+    fn test_parse_tool_call_response() {
+        let response: ChatCompletionResponse = serde_json::from_str(
+            r#"{"choices":[{"message":{"role":"assistant","content":null,"tool_calls":[
+                {"id":"call_1","type":"function","function":{"name":"web_search","arguments":"{\"query\":\"weather\"}"}},
+                {"id":"call_2","function":{"name":"spotify_playback","arguments":{"action":"pause"}}}
+            ]}}]}"#,
+        )
+        .unwrap();
+
+        let message = &response.choices[0].message;
+        assert_eq!(message.content, "");
+
+        let calls = message.tool_calls.as_ref().unwrap();
+        assert_eq!(calls[0].function.name, "web_search");
+        assert_eq!(calls[0].function.arguments, r#"{"query":"weather"}"#);
+        assert_eq!(calls[1].kind, "function");
+        assert_eq!(calls[1].function.arguments, r#"{"action":"pause"}"#);
+    }
+
+    #[test]
+    fn test_tool_result_message_serialization() {
+        let message = serde_json::to_value(ChatMessage::tool_result("call_1", "done")).unwrap();
+        assert_eq!(message["role"], "tool");
+        assert_eq!(message["tool_call_id"], "call_1");
+        assert!(message.get("tool_calls").is_none());
+
+        let plain = serde_json::to_value(ChatMessage::new("user", "hi")).unwrap();
+        assert!(plain.get("tool_call_id").is_none());
+    }
+
+    #[test]
+    fn test_tools_unsupported_error_detection() {
+        assert!(is_tools_unsupported_error(
+            r#"LLM API returned error 400 Bad Request: {"error":{"message":"registry.ollama.ai/library/gemma:2b does not support tools"}}"#
+        ));
+        assert!(!is_tools_unsupported_error("LLM API returned error 500: out of memory"));
+    }
 }
@@ -0,0 +1,213 @@
+//! Model Cache
+//!
+//! Keeps a large on-disk model (e.g., the Whisper STT model) loaded between uses:
+//! - Loaded once per model path and shared as `Arc<T>` behind a lock
+//! - `swap` loads a replacement first and then switches atomically, so in-flight
+//!   users keep the old model until they finish
+//! - Evicted after an idle timeout to free memory (reloaded on next use)
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+/// How often the idle evictor checks the cache
+const EVICTION_CHECK_SECS: u64 = 30;
+
+/// A loaded model and when it was last handed out
+struct CachedModel<T> {
+    path: PathBuf,
+    model: Arc<T>,
+    last_used: Instant,
+}
+
+/// Loads a model from disk
+type Loader<T> = Box<dyn Fn(&Path) -> Result<T, String> + Send + Sync>;
+
+/// Cache holding at most one loaded model
+pub struct ModelCache<T> {
+    /// Name used in log messages (e.g., "Whisper")
+    name: &'static str,
+    loader: Loader<T>,
+    idle_timeout: Duration,
+    slot: Mutex<Option<CachedModel<T>>>,
+}
+
+impl<T: Send + Sync + 'static> ModelCache<T> {
+    /// Create an empty cache
+    ///
+    /// # Arguments
+    /// * `name` - Model kind for log messages
+    /// * `loader` - Loads the model from disk
+    /// * `idle_timeout` - Unused models are evicted after this long
+    pub fn new<F>(name: &'static str, loader: F, idle_timeout: Duration) -> Self
+    where
+        F: Fn(&Path) -> Result<T, String> + Send + Sync + 'static,
+    {
+        Self {
+            name,
+            loader: Box::new(loader),
+            idle_timeout,
+            slot: Mutex::new(None),
+        }
+    }
+
+    /// Get the model at `path`, loading it if it isn't cached yet
+    ///
+    /// The lock is held while loading so concurrent callers don't load the same
+    /// model twice.
+    pub fn get(&self, path: &Path) -> Result<Arc<T>, String> {
+        let mut slot = self.slot.lock()
+            .map_err(|e| format!("Failed to lock {} model cache: {}", self.name, e))?;
+
+        if let Some(cached) = slot.as_mut().filter(|cached| cached.path == path) {
+            cached.last_used = Instant::now();
+            return Ok(cached.model.clone());
+        }
+
+        log::info!("Loading {} model: {:?}", self.name, path);
+        let start = Instant::now();
+        let model = Arc::new((self.loader)(path)?);
+        log::info!("✓ {} model loaded in {:.2}s", self.name, start.elapsed().as_secs_f32());
+
+        *slot = Some(CachedModel {
+            path: path.to_path_buf(),
+            model: model.clone(),
+            last_used: Instant::now(),
+        });
+
+        Ok(model)
+    }
+
+    /// Load the model at `path` and switch to it atomically
+    ///
+    /// The new model is loaded without holding the lock; callers already using the
+    /// previous model keep it until they drop their `Arc`. Does nothing if `path` is
+    /// already cached.
+    pub fn swap(&self, path: &Path) -> Result<(), String> {
+        if self.loaded_path().as_deref() == Some(path) {
+            return Ok(());
+        }
+
+        log::info!("Loading {} model for swap: {:?}", self.name, path);
+        let model = Arc::new((self.loader)(path)?);
+
+        let mut slot = self.slot.lock()
+            .map_err(|e| format!("Failed to lock {} model cache: {}", self.name, e))?;
+        *slot = Some(CachedModel {
+            path: path.to_path_buf(),
+            model,
+            last_used: Instant::now(),
+        });
+
+        log::info!("✓ Switched {} model to {:?}", self.name, path);
+        Ok(())
+    }
+
+    /// Path of the currently cached model, if any
+    pub fn loaded_path(&self) -> Option<PathBuf> {
+        self.slot
+            .lock()
+            .ok()
+            .and_then(|slot| slot.as_ref().map(|cached| cached.path.clone()))
+    }
+
+    /// Drop the cached model if it hasn't been used within the idle timeout
+    ///
+    /// Returns true if a model was evicted.
+    pub fn evict_if_idle(&self) -> bool {
+        let mut slot = match self.slot.lock() {
+            Ok(slot) => slot,
+            Err(_) => return false,
+        };
+
+        let idle = slot
+            .as_ref()
+            .map(|cached| cached.last_used.elapsed() >= self.idle_timeout)
+            .unwrap_or(false);
+
+        if idle {
+            if let Some(cached) = slot.take() {
+                log::info!(
+                    "Evicting idle {} model {:?} (unused for {}s)",
+                    self.name,
+                    cached.path,
+                    cached.last_used.elapsed().as_secs()
+                );
+            }
+        }
+
+        idle
+    }
+}
+
+/// Periodically evict the cached model once it has been idle for too long
+///
+/// The background thread stops when the cache itself is dropped.
+pub fn spawn_idle_evictor<T: Send + Sync + 'static>(cache: &Arc<ModelCache<T>>) {
+    let cache: Weak<ModelCache<T>> = Arc::downgrade(cache);
+
+    std::thread::spawn(move || loop {
+        std::thread::sleep(Duration::from_secs(EVICTION_CHECK_SECS));
+
+        match cache.upgrade() {
+            Some(cache) => {
+                cache.evict_if_idle();
+            }
+            None => break,
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Loader returning the model's path, counting its loads in `loads`
+    fn load_name(loads: Arc<AtomicUsize>) -> impl Fn(&Path) -> Result<String, String> + Send + Sync + 'static {
+        move |path| {
+            loads.fetch_add(1, Ordering::SeqCst);
+            if path.ends_with("missing.bin") {
+                return Err("model not found".to_string());
+            }
+            Ok(path.to_string_lossy().to_string())
+        }
+    }
+
+    #[test]
+    fn test_cache_loads_once_and_swaps() {
+        let loads = Arc::new(AtomicUsize::new(0));
+        let cache = ModelCache::new("test", load_name(loads.clone()), Duration::from_secs(60));
+
+        let first = cache.get(Path::new("/models/base.bin")).unwrap();
+        let second = cache.get(Path::new("/models/base.bin")).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+
+        // Swapping keeps the old model alive for existing holders
+        cache.swap(Path::new("/models/small.bin")).unwrap();
+        assert_eq!(first.as_str(), "/models/base.bin");
+        assert_eq!(cache.get(Path::new("/models/small.bin")).unwrap().as_str(), "/models/small.bin");
+        assert_eq!(cache.loaded_path(), Some(PathBuf::from("/models/small.bin")));
+
+        // A failed swap keeps the current model
+        assert!(cache.swap(Path::new("/models/missing.bin")).is_err());
+        assert_eq!(cache.loaded_path(), Some(PathBuf::from("/models/small.bin")));
+    }
+
+    #[test]
+    fn test_idle_model_is_evicted() {
+        let loads = Arc::new(AtomicUsize::new(0));
+        let cache = ModelCache::new("test", load_name(loads.clone()), Duration::from_millis(0));
+        cache.get(Path::new("/models/tiny.bin")).unwrap();
+
+        assert!(cache.evict_if_idle());
+        assert!(cache.loaded_path().is_none());
+        assert!(!cache.evict_if_idle());
+
+        let busy = ModelCache::new("test", load_name(loads.clone()), Duration::from_secs(60));
+        busy.get(Path::new("/models/tiny.bin")).unwrap();
+        assert!(!busy.evict_if_idle());
+        assert_eq!(loads.load(Ordering::SeqCst), 2);
+    }
+}
@@ -3,34 +3,353 @@
 //! Unified voice module using:
 //! - whisper-rs for speech-to-text transcription
 //! - cpal for audio input
-//! - Energy-based VAD for wake word simulation
+//! - Keyword spotting for the wake word (see `wake_word`, energy-based fallback)
 //!
 //! Architecture:
-//! 1. Single audio stream (16kHz mono) from microphone via cpal
-//! 2. Continuous energy-based voice activity detection
+//! 1. Single audio stream from the selected microphone via cpal, converted to 16kHz
+//!    mono (see `audio_convert`)
+//! 2. Continuous wake word detection on a separate thread
 //! 3. On-demand STT transcription (activated by voice activity or Push-to-Talk)
-//! 4. Built-in VAD using RMS energy for end-of-speech detection
+//! 4. Pluggable VAD for end-of-speech detection (RMS energy or Silero, see `vad`), also
+//!    on its own thread
+//! 5. Whisper model kept loaded in a shared `WhisperCache` between utterances, prompted
+//!    with known entity and playlist names (see `stt_vocabulary`)
+//! 6. TTS playback drives the Speaking state; optional barge-in: speech during
+//!    playback interrupts it (see `barge_in`)
+//! 7. Optional debug capture of every recording to disk (see `debug_capture`)
 
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use cpal::StreamConfig;
+use cpal::{FromSample, Sample, SampleFormat, SizedSample, StreamConfig};
+use rodio::Source;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tauri::{AppHandle, Emitter};
 use log::{info, error, warn, debug};
 use whisper_rs::{WhisperContext, WhisperContextParameters, FullParams, SamplingStrategy};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use crate::audio_convert::{self, AudioConverter};
+use crate::barge_in::{self, BargeInDetector, BargeInMode, PlaybackMonitor};
+use crate::debug_capture::{DebugCaptureState, VadParameters};
+use crate::model_cache::{self, ModelCache};
+use crate::stt_vocabulary::SttVocabularyState;
+use crate::partial_transcript::PartialTranscriptTracker;
+use crate::vad::{self, VoiceActivityDetector, SPEECH_THRESHOLD};
+use crate::wake_word::{self, WakePhrase, WakeWordDetector};
 
 // Audio configuration constants
-const SAMPLE_RATE: u32 = 16000;  // Required by whisper-rs
-const CHANNELS: u16 = 1;         // Mono audio
+const SAMPLE_RATE: u32 = 16000;  // Required by whisper-rs (capture is converted to this)
 const CHUNK_SIZE: usize = 512;   // Process in small chunks for responsiveness
 
 // VAD (Voice Activity Detection) constants
-const VOICE_FRAMES_REQUIRED: usize = 10;     // Require consistent voice energy for wake word
 const MAX_RECORDING_SECONDS: usize = 30;     // Maximum 30 seconds per transcription
 const SKIP_FRAMES_AFTER_WAKE_WORD: usize = 15; // Skip ~500ms of audio after wake word to prevent capturing it
 const MIN_RECORDING_FRAMES: usize = 30;      // Minimum 30 frames (~1 second) before allowing silence detection
+const SPEECH_QUEUE_CHUNKS: usize = 64;       // Recorded chunks buffered for the end-of-speech thread (~2s)
+
+// Wake word detection
+const WAKE_QUEUE_CHUNKS: usize = 64;         // Audio chunks buffered for the wake word thread (~2s)
+const WAKE_DEBOUNCE_SECS: u64 = 3;           // Minimum time between wake word events
+const WAKE_STREAM_GAP_MS: u64 = 500;         // Longer gaps between chunks reset the detector
+
+// Live partial transcripts
+const MIN_PARTIAL_SAMPLES: usize = SAMPLE_RATE as usize / 2; // Wait for 500ms of speech before the first partial pass
+
+// Whisper language
+const AUTO_LANGUAGE: &str = "auto";          // stt_language value that enables language detection
+
+// Whisper model cache
+const WHISPER_IDLE_TIMEOUT_SECS: u64 = 600;  // Unload the Whisper model after 10 minutes without transcriptions
+
+/// Loaded Whisper model shared across utterances and pipeline reloads
+pub type WhisperCache = ModelCache<WhisperContext>;
+
+/// Create the Whisper model cache and start its idle evictor
+pub fn new_whisper_cache() -> Arc<WhisperCache> {
+    let cache = Arc::new(WhisperCache::new(
+        "Whisper",
+        load_whisper_context,
+        Duration::from_secs(WHISPER_IDLE_TIMEOUT_SECS),
+    ));
+    model_cache::spawn_idle_evictor(&cache);
+    cache
+}
+
+/// Load a Whisper model from disk
+fn load_whisper_context(model_path: &Path) -> Result<WhisperContext, String> {
+    let path = model_path
+        .to_str()
+        .ok_or_else(|| format!("Invalid Whisper model path: {:?}", model_path))?;
+
+    WhisperContext::new_with_params(path, WhisperContextParameters::default())
+        .map_err(|e| format!("Failed to load Whisper model: {}", e))
+}
+
+/// Language to request from Whisper for a model and the `stt_language` setting
+///
+/// Returns None for auto-detection. English-only models (`*.en.bin`) can't detect
+/// or transcribe other languages, so they always get "en". Unrecognized codes fall
+/// back to auto-detection.
+fn whisper_language(stt_language: &str, stt_model_name: &str) -> Option<String> {
+    if stt_model_name.contains(".en.") {
+        return Some("en".to_string());
+    }
+
+    let language = stt_language.trim().to_lowercase();
+    if language.is_empty() || language == AUTO_LANGUAGE {
+        return None;
+    }
+
+    let is_iso_code = (2..=3).contains(&language.len()) && language.chars().all(|c| c.is_ascii_lowercase());
+    if !is_iso_code {
+        warn!("Invalid STT language '{}', falling back to auto-detection", stt_language);
+        return None;
+    }
+
+    Some(language)
+}
+
+/// Text returned by Whisper for one utterance (payload of the `final_transcription` event)
+#[derive(Debug, Clone, Serialize)]
+pub struct Transcript {
+    /// The transcribed text
+    pub text: String,
+    /// Spoken language (ISO 639-1 code), forced or detected
+    pub language: Option<String>,
+}
+
+/// A timestamped part of a transcription
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptSegment {
+    pub start_seconds: f32,
+    pub end_seconds: f32,
+    pub text: String,
+    /// Who spoke this segment (file transcription with voice biometrics only)
+    pub speaker: Option<SpeakerInfo>,
+}
+
+/// Result of the `transcribe_file` command
+#[derive(Debug, Clone, Serialize)]
+pub struct FileTranscription {
+    /// Full text (all segments)
+    pub text: String,
+    /// Spoken language (ISO 639-1 code), forced or detected
+    pub language: Option<String>,
+    pub duration_seconds: f32,
+    pub segments: Vec<TranscriptSegment>,
+}
+
+/// Runs Whisper with the pipeline's model and language settings
+///
+/// Holds only the shared model cache, so it can transcribe without keeping the
+/// voice pipeline locked (e.g., long audio files).
+#[derive(Clone)]
+pub struct WhisperTranscriber {
+    whisper_cache: Arc<WhisperCache>,
+    model_path: PathBuf,
+    language: Option<String>,
+    /// Names to bias the decoding towards (empty = no prompt)
+    initial_prompt: String,
+}
+
+impl WhisperTranscriber {
+    /// Transcribe 16kHz mono audio into timestamped segments
+    ///
+    /// Returns the non-empty segments and the forced or detected language.
+    /// `partial` runs a quick single-segment pass for live captions.
+    pub fn transcribe(&self, samples: &[f32], partial: bool) -> Result<(Vec<TranscriptSegment>, Option<String>), String> {
+        let log_level = if partial { log::Level::Debug } else { log::Level::Info };
+        log::log!(log_level, "Initializing Whisper for transcription...");
+
+        // Cached Whisper model (loaded from disk only on first use or after eviction)
+        let ctx = self.whisper_cache.get(&self.model_path)?;
+
+        // Configure transcription parameters
+        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+
+        // Forced language, or let Whisper detect it
+        params.set_language(Some(self.language.as_deref().unwrap_or(AUTO_LANGUAGE)));
+        params.set_print_special(false);
+        params.set_print_progress(false);
+        params.set_print_realtime(false);
+        params.set_print_timestamps(false);
+        if !self.initial_prompt.is_empty() {
+            params.set_initial_prompt(&self.initial_prompt);
+        }
+        if partial {
+            params.set_single_segment(true);
+            params.set_no_context(true);
+        }
+
+        // Create Whisper state
+        let mut state = ctx.create_state()
+            .map_err(|e| format!("Failed to create Whisper state: {}", e))?;
+
+        // Run transcription
+        log::log!(log_level, "Running Whisper transcription ({:.1}s of audio)...", samples.len() as f32 / SAMPLE_RATE as f32);
+        state.full(params, samples)
+            .map_err(|e| format!("Whisper transcription failed: {}", e))?;
+
+        // Extract transcribed segments (timestamps are in 10ms units)
+        let num_segments = state.full_n_segments();
+
+        let mut segments = Vec::new();
+        for i in 0..num_segments {
+            if let Some(segment) = state.get_segment(i) {
+                let text = segment.to_str()
+                    .map_err(|e| format!("Failed to get segment {} text: {}", i, e))?
+                    .trim();
+                if text.is_empty() {
+                    continue;
+                }
+
+                segments.push(TranscriptSegment {
+                    start_seconds: segment.start_timestamp() as f32 / 100.0,
+                    end_seconds: segment.end_timestamp() as f32 / 100.0,
+                    text: text.to_string(),
+                    speaker: None,
+                });
+            }
+        }
+
+        let language = self.language.clone().or_else(|| {
+            whisper_rs::get_lang_str(state.full_lang_id_from_state()).map(|lang| lang.to_string())
+        });
+
+        Ok((segments, language))
+    }
+
+    /// Transcribe 16kHz mono audio into a single text
+    ///
+    /// Errors if Whisper heard no speech. Partial passes only log at debug level.
+    fn transcript(&self, samples: &[f32], partial: bool) -> Result<Transcript, String> {
+        let log_level = if partial { log::Level::Debug } else { log::Level::Info };
+        let (segments, language) = self.transcribe(samples, partial)?;
+
+        let transcription = segments.iter().map(|s| s.text.as_str()).collect::<Vec<_>>().join(" ");
+        if transcription.is_empty() {
+            log::log!(log_level, "Whisper returned empty transcription - no speech detected");
+            return Err("No speech detected in audio".to_string());
+        }
+
+        log::log!(log_level, "Transcription complete ({}): '{}'", language.as_deref().unwrap_or("unknown"), transcription);
+        Ok(Transcript {
+            text: transcription,
+            language,
+        })
+    }
+}
+
+/// Live captions while the user speaks: partial Whisper passes on a worker thread
+///
+/// One pass runs at a time, and ticks that come while it is still running are
+/// skipped, so the recording loop never waits for Whisper.
+struct LiveCaptions {
+    transcriber: WhisperTranscriber,
+    app_handle: AppHandle,
+    tracker: Arc<Mutex<PartialTranscriptTracker>>,
+    busy: Arc<AtomicBool>,
+    /// Set when the recording ends (on drop); a pass still running drops its caption
+    ended: Arc<AtomicBool>,
+    last_samples: usize,
+}
+
+impl LiveCaptions {
+    fn new(transcriber: WhisperTranscriber, app_handle: AppHandle) -> Self {
+        Self {
+            transcriber,
+            app_handle,
+            tracker: Arc::new(Mutex::new(PartialTranscriptTracker::new())),
+            busy: Arc::new(AtomicBool::new(false)),
+            ended: Arc::new(AtomicBool::new(false)),
+            last_samples: 0,
+        }
+    }
+
+    /// Start a partial pass over the recording so far
+    ///
+    /// Skipped while the previous pass is running or until enough new audio has
+    /// arrived. Failures are only logged: the final transcription still runs at end of
+    /// speech.
+    fn tick(&mut self, recording_buffer: &Mutex<Vec<f32>>) {
+        if self.busy.load(Ordering::SeqCst) {
+            debug!("Previous partial transcription still running, skipping this tick");
+            return;
+        }
+
+        let samples: Vec<f32> = {
+            let buffer = recording_buffer.lock().unwrap();
+            if buffer.len() < MIN_PARTIAL_SAMPLES || buffer.len() == self.last_samples {
+                return;
+            }
+            buffer.clone()
+        };
+        self.last_samples = samples.len();
+
+        self.busy.store(true, Ordering::SeqCst);
+        let transcriber = self.transcriber.clone();
+        let app_handle = self.app_handle.clone();
+        let tracker = self.tracker.clone();
+        let busy = self.busy.clone();
+        let ended = self.ended.clone();
+
+        let spawned = std::thread::Builder::new()
+            .name("partial-transcription".to_string())
+            .spawn(move || {
+                match transcriber.transcript(&samples, true) {
+                    Ok(transcript) => {
+                        // Checked under the tracker lock, which `drop` takes too
+                        let mut tracker = tracker.lock().unwrap();
+                        if !ended.load(Ordering::SeqCst) {
+                            if let Some(caption) = tracker.update(&transcript.text) {
+                                debug!("Partial transcription: '{}' + '{}'", caption.stable, caption.tentative);
+                                if let Err(e) = app_handle.emit("partial_transcription", caption) {
+                                    warn!("Failed to emit partial_transcription event: {}", e);
+                                }
+                            }
+                        }
+                    }
+                    Err(e) => debug!("Partial transcription skipped: {}", e),
+                }
+                busy.store(false, Ordering::SeqCst);
+            });
+
+        if let Err(e) = spawned {
+            warn!("Failed to start partial transcription thread: {}", e);
+            self.busy.store(false, Ordering::SeqCst);
+        }
+    }
+}
+
+impl Drop for LiveCaptions {
+    fn drop(&mut self) {
+        // No caption may follow the final transcription
+        let _tracker = self.tracker.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        self.ended.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Decode an audio file (WAV, FLAC or MP3) into 16kHz mono samples
+pub fn load_audio_file(path: &Path) -> Result<Vec<f32>, String> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| format!("Failed to open audio file {:?}: {}", path, e))?;
+    let decoder = rodio::Decoder::new(std::io::BufReader::new(file))
+        .map_err(|e| format!("Failed to decode audio file {:?}: {}", path, e))?;
+
+    let channels = decoder.channels();
+    let sample_rate = decoder.sample_rate();
+    let interleaved: Vec<f32> = decoder.collect();
+    if interleaved.is_empty() {
+        return Err(format!("Audio file {:?} contains no audio", path));
+    }
+
+    info!("Decoded {:?}: {} Hz, {} channel(s), {:.1}s",
+          path, sample_rate, channels, interleaved.len() as f32 / channels.max(1) as f32 / sample_rate as f32);
+
+    let mono = audio_convert::downmix(&interleaved, channels);
+    Ok(audio_convert::resample(&mono, sample_rate, SAMPLE_RATE))
+}
 
 /// Voice pipeline state machine
 ///
@@ -40,7 +359,7 @@ const MIN_RECORDING_FRAMES: usize = 30;      // Minimum 30 frames (~1 second) be
 pub enum VoiceState {
     /// Pipeline is inactive (not listening for anything)
     Idle,
-    /// Actively listening for wake word via energy-based VAD
+    /// Actively listening for the wake word
     ListeningForWakeWord,
     /// Recording and transcribing user speech
     Transcribing,
@@ -57,12 +376,14 @@ pub struct TranscriptionResult {
     pub duration_seconds: f32,
     /// Number of audio samples processed
     pub sample_count: usize,
+    /// Spoken language (ISO 639-1 code), if known
+    pub language: Option<String>,
     /// Speaker identification results (if available)
     pub speaker_info: Option<SpeakerInfo>,
 }
 
 /// Speaker identification information
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SpeakerInfo {
     /// Identified user ID (if recognized)
     pub user_id: Option<i64>,
@@ -79,11 +400,17 @@ pub struct NativeVoicePipeline {
     app_handle: AppHandle,
     model_path: PathBuf,
     stt_model_name: String,                  // STT model filename (e.g., "ggml-base.en.bin")
+    stt_language: String,                    // Whisper language ("auto" or ISO 639-1 code)
+    stt_partial_interval_ms: u32,            // Re-transcribe interval for partial_transcription events (0 = off)
+    whisper_cache: Arc<WhisperCache>,        // Loaded Whisper model (outlives pipeline reloads)
+    vocabulary: SttVocabularyState,          // Entity/playlist names used as Whisper's initial prompt
+    debug_capture: DebugCaptureState,        // Saves finished recordings when diagnostics are enabled
 
     // State machine (thread-safe, accessible from both audio thread and command handlers)
     state: Arc<Mutex<VoiceState>>,
 
     // Audio stream control
+    input_device_name: String,               // Microphone to capture from (empty = system default)
     wake_word_active: Arc<AtomicBool>,       // Set to false to stop the entire audio loop
 
     // Recording buffers and signals
@@ -93,17 +420,94 @@ pub struct NativeVoicePipeline {
 
     // Wake word detection
     voice_detected: Arc<AtomicBool>,         // Track if voice activity detected for wake word
+    wake_phrases: Vec<WakePhrase>,           // Phrases for the keyword spotting detector
 
     // VAD Configuration (shared with audio thread via Arc<Mutex>)
     vad_sensitivity: Arc<Mutex<f32>>,        // Voice energy threshold (0.0-1.0), controls microphone sensitivity
     vad_timeout_ms: Arc<Mutex<u32>>,         // Silence timeout in milliseconds before ending recording
+    vad_engine: String,                      // End-of-speech detector ("energy" or "silero")
+
+    // Barge-in (interrupting TTS playback)
+    barge_in_mode: BargeInMode,
+    playback: PlaybackMonitor,               // Current TTS playback (level for echo gating, stop)
+    barge_in_recording: Arc<AtomicBool>,     // Recording started by barge-in, waiting for start_transcription
+}
+
+/// A recorded chunk for the end-of-speech thread
+struct SpeechFrame {
+    samples: Vec<f32>,
+    /// Position in the recording (from 1)
+    number: usize,
+    /// Drop VAD state first (new recording, or one started by barge-in)
+    reset: bool,
+}
+
+/// Recording state shared between the audio callback and the end-of-speech thread
+struct RecordingProgress {
+    has_detected_speech: Arc<AtomicBool>,
+    silence_frame_count: Arc<AtomicUsize>,
+    recording_frame_count: Arc<AtomicUsize>,
+    recording_complete: Arc<AtomicBool>,
+    vad_timeout_ms: Arc<Mutex<u32>>,
+}
+
+/// What the audio and wake word threads need to interrupt TTS playback
+#[derive(Clone)]
+struct BargeIn {
+    mode: BargeInMode,
+    playback: PlaybackMonitor,
+    vad_sensitivity: Arc<Mutex<f32>>,
+    app_handle: AppHandle,
+    state: Arc<Mutex<VoiceState>>,
+    recording_buffer: Arc<Mutex<Vec<f32>>>,
+    recording_complete: Arc<AtomicBool>,
+    skip_frames_counter: Arc<AtomicUsize>,
+    recording_pending: Arc<AtomicBool>,
+}
+
+impl BargeIn {
+    /// Stop the playback and start recording the user's new request
+    ///
+    /// `pre_roll` is audio that already belongs to the request. Returns false if the
+    /// assistant stopped speaking in the meantime.
+    fn interrupt(&self, pre_roll: &[f32], trigger: &str) -> bool {
+        {
+            let Ok(mut state) = self.state.lock() else {
+                return false;
+            };
+            if *state != VoiceState::Speaking {
+                return false;
+            }
+
+            // Prepare the recording before the audio callback sees the new state
+            if let Ok(mut buffer) = self.recording_buffer.lock() {
+                buffer.clear();
+                buffer.extend_from_slice(pre_roll);
+            }
+            self.recording_complete.store(false, Ordering::Relaxed);
+            self.skip_frames_counter.store(0, Ordering::Relaxed);
+            self.recording_pending.store(true, Ordering::Relaxed);
+
+            *state = VoiceState::Transcribing;
+        }
+
+        info!("Barge-in ({}): Speaking -> Transcribing", trigger);
+        if !self.playback.stop() {
+            debug!("Barge-in: no TTS playback to stop");
+        }
+
+        if let Err(e) = self.app_handle.emit("barge_in", trigger) {
+            error!("Failed to emit barge_in: {}", e);
+        }
+        true
+    }
 }
 
 /// Service status for frontend
 #[derive(serde::Serialize, Clone)]
-struct ServiceStatus {
-    service: String,
-    connected: bool,
+pub(crate) struct ServiceStatus {
+    pub service: String,
+    pub connected: bool,
 }
 
 impl NativeVoicePipeline {
@@ -112,24 +516,45 @@ impl NativeVoicePipeline {
         app_handle: AppHandle,
         model_path: PathBuf,
         stt_model_name: String,
+        stt_language: String,
+        stt_partial_interval_ms: u32,
+        whisper_cache: Arc<WhisperCache>,
+        vocabulary: SttVocabularyState,
+        debug_capture: DebugCaptureState,
+        input_device_name: String,
+        wake_phrases: Vec<WakePhrase>,
         vad_sensitivity: f32,
         vad_timeout_ms: u32,
+        vad_engine: String,
+        barge_in_mode: &str,
+        playback: PlaybackMonitor,
     ) -> Result<Self, String> {
-        info!("Initializing native voice pipeline: stt_model={}, vad_sensitivity={}, vad_timeout_ms={}",
-              stt_model_name, vad_sensitivity, vad_timeout_ms);
+        info!("Initializing native voice pipeline: stt_model={}, stt_language={}, stt_partial_interval_ms={}, input_device={:?}, vad_sensitivity={}, vad_timeout_ms={}, vad_engine={}, barge_in_mode={}",
+              stt_model_name, stt_language, stt_partial_interval_ms, input_device_name, vad_sensitivity, vad_timeout_ms, vad_engine, barge_in_mode);
 
         Ok(Self {
             app_handle,
             model_path,
             stt_model_name,
+            stt_language,
+            stt_partial_interval_ms,
+            whisper_cache,
+            vocabulary,
+            debug_capture,
             state: Arc::new(Mutex::new(VoiceState::Idle)),
+            input_device_name,
             wake_word_active: Arc::new(AtomicBool::new(false)),
             recording_buffer: Arc::new(Mutex::new(Vec::with_capacity(SAMPLE_RATE as usize * MAX_RECORDING_SECONDS))),
             recording_complete: Arc::new(AtomicBool::new(false)),
             skip_frames_counter: Arc::new(AtomicUsize::new(0)),
             voice_detected: Arc::new(AtomicBool::new(false)),
+            wake_phrases,
             vad_sensitivity: Arc::new(Mutex::new(vad_sensitivity)),
             vad_timeout_ms: Arc::new(Mutex::new(vad_timeout_ms)),
+            vad_engine,
+            barge_in_mode: BargeInMode::parse(barge_in_mode),
+            playback,
+            barge_in_recording: Arc::new(AtomicBool::new(false)),
         })
     }
 
@@ -137,8 +562,8 @@ impl NativeVoicePipeline {
     ///
     /// This spawns a background thread that:
     /// 1. Captures audio from the microphone via cpal
-    /// 2. Detects voice activity using energy-based VAD
-    /// 3. Emits wake_word_detected event when voice is detected
+    /// 2. Listens for the wake word (keyword spotting, or energy-based VAD as fallback)
+    /// 3. Emits wake_word_detected event with the detected phrase
     pub fn start(&self) -> Result<(), String> {
         info!("Starting native voice pipeline");
 
@@ -152,36 +577,76 @@ impl NativeVoicePipeline {
         {
             let mut state = self.state.lock()
                 .map_err(|e| format!("Failed to lock state: {}", e))?;
-            *state = VoiceState::ListeningForWakeWord;
-            info!("Voice state: Idle -> ListeningForWakeWord");
+            *state = self.resting_state();
+            info!("Voice state: Idle -> {:?}", *state);
         }
 
+        // TTS playback moves the pipeline in and out of Speaking (no wake word
+        // detection while the assistant talks; barge-in handles interruptions)
+        let playback_state = self.state.clone();
+        self.playback.set_listener(move |playing| {
+            let Ok(mut state) = playback_state.lock() else {
+                return;
+            };
+            match (*state, playing) {
+                (VoiceState::ListeningForWakeWord, true) => {
+                    *state = VoiceState::Speaking;
+                    info!("Voice state: ListeningForWakeWord -> Speaking (playback started)");
+                }
+                (VoiceState::Speaking, false) => {
+                    *state = VoiceState::ListeningForWakeWord;
+                    info!("Voice state: Speaking -> ListeningForWakeWord (playback finished)");
+                }
+                _ => {}
+            }
+        });
+
         // Mark wake word as active
         self.wake_word_active.store(true, Ordering::Relaxed);
 
         // Clone references for the audio thread
         let app_handle = self.app_handle.clone();
+        let input_device_name = self.input_device_name.clone();
         let state = self.state.clone();
         let wake_word_active = self.wake_word_active.clone();
         let recording_buffer = self.recording_buffer.clone();
         let recording_complete = self.recording_complete.clone();
         let skip_frames_counter = self.skip_frames_counter.clone();
         let voice_detected = self.voice_detected.clone();
-        let vad_sensitivity = self.vad_sensitivity.clone();
         let vad_timeout_ms = self.vad_timeout_ms.clone();
+        let barge_in = BargeIn {
+            mode: self.barge_in_mode,
+            playback: self.playback.clone(),
+            vad_sensitivity: self.vad_sensitivity.clone(),
+            app_handle: self.app_handle.clone(),
+            state: self.state.clone(),
+            recording_buffer: self.recording_buffer.clone(),
+            recording_complete: self.recording_complete.clone(),
+            skip_frames_counter: self.skip_frames_counter.clone(),
+            recording_pending: self.barge_in_recording.clone(),
+        };
+
+        // Load the wake word detector (falls back to energy-based detection)
+        let wake_detector = wake_word::create_detector(&self.model_path, &self.wake_phrases, self.vad_sensitivity.clone());
+
+        // Load the end-of-speech detector (falls back to energy-based VAD)
+        let speech_detector = vad::create_vad(&self.vad_engine, &self.model_path, self.vad_sensitivity.clone());
 
         // Spawn background audio processing thread
         std::thread::spawn(move || {
             if let Err(e) = Self::run_audio_loop(
                 app_handle.clone(),
+                &input_device_name,
                 state,
                 wake_word_active,
                 recording_buffer,
                 recording_complete,
                 skip_frames_counter,
                 voice_detected,
-                vad_sensitivity,
                 vad_timeout_ms,
+                wake_detector,
+                speech_detector,
+                barge_in,
             ) {
                 error!("Audio loop error: {}", e);
             }
@@ -197,195 +662,199 @@ impl NativeVoicePipeline {
     /// Main audio processing loop (runs in background thread)
     fn run_audio_loop(
         app_handle: AppHandle,
+        input_device_name: &str,
         state: Arc<Mutex<VoiceState>>,
         wake_word_active: Arc<AtomicBool>,
         recording_buffer: Arc<Mutex<Vec<f32>>>,
         recording_complete: Arc<AtomicBool>,
         skip_frames_counter: Arc<AtomicUsize>,
         voice_detected: Arc<AtomicBool>,
-        vad_sensitivity: Arc<Mutex<f32>>,
         vad_timeout_ms: Arc<Mutex<u32>>,
+        wake_detector: Box<dyn WakeWordDetector>,
+        speech_detector: Box<dyn VoiceActivityDetector>,
+        barge_in: BargeIn,
     ) -> Result<(), String> {
         // Initialize audio device
         let host = cpal::default_host();
-        let device = host
-            .default_input_device()
-            .ok_or("No input device available")?;
-
-        info!("Audio device: {}", device.name().unwrap_or_default());
-
-        // Configure audio stream (16kHz mono)
-        let config = StreamConfig {
-            channels: CHANNELS,
-            sample_rate: cpal::SampleRate(SAMPLE_RATE),
-            buffer_size: cpal::BufferSize::Fixed(CHUNK_SIZE as u32),
-        };
-
-        // Wake word detection state (only used when NOT recording)
-        // Use atomics for interior mutability in Fn callback
-        let voice_frame_count = Arc::new(std::sync::atomic::AtomicUsize::new(0));
-        let last_wake_emission = Arc::new(Mutex::new(std::time::Instant::now()));
+        let device = select_input_device(&host, input_device_name)?;
+
+        // Capture in the device's native format; the converter turns it into 16kHz
+        // mono chunks for the detectors and Whisper
+        let supported_config = device
+            .default_input_config()
+            .map_err(|e| format!("Failed to get input config: {}", e))?;
+        let sample_format = supported_config.sample_format();
+        let config: StreamConfig = supported_config.config();
+        let converter = AudioConverter::new(config.sample_rate.0, config.channels, SAMPLE_RATE, CHUNK_SIZE);
+
+        info!("Audio device: {} ({} Hz, {} channel(s), {})",
+              device.name().unwrap_or_default(), config.sample_rate.0, config.channels, sample_format);
+
+        // Wake word detection runs on its own thread so model inference never blocks
+        // the audio callback; chunks are dropped if the detector falls behind
+        let wake_detector_name = wake_detector.name();
+        let speech_detector_name = speech_detector.name();
+        let (wake_tx, wake_rx) = std::sync::mpsc::sync_channel::<Vec<f32>>(WAKE_QUEUE_CHUNKS);
+        {
+            let app_handle = app_handle.clone();
+            let state = state.clone();
+            let voice_detected = voice_detected.clone();
+            let barge_in = barge_in.clone();
+            std::thread::spawn(move || {
+                Self::run_wake_word_loop(app_handle, state, voice_detected, wake_detector, barge_in, wake_rx);
+            });
+        }
 
         // Recording state (only used when IS recording)
         let silence_frame_count = Arc::new(std::sync::atomic::AtomicUsize::new(0));
         let has_detected_speech = Arc::new(AtomicBool::new(false));
         let recording_frame_count = Arc::new(std::sync::atomic::AtomicUsize::new(0));
 
+        // End-of-speech detection runs on its own thread too (Silero is an ONNX model);
+        // chunks are dropped if it falls behind
+        let (speech_tx, speech_rx) = std::sync::mpsc::sync_channel::<SpeechFrame>(SPEECH_QUEUE_CHUNKS);
+        {
+            let recording = RecordingProgress {
+                has_detected_speech: has_detected_speech.clone(),
+                silence_frame_count: silence_frame_count.clone(),
+                recording_frame_count: recording_frame_count.clone(),
+                recording_complete: recording_complete.clone(),
+                vad_timeout_ms,
+            };
+            std::thread::spawn(move || {
+                Self::run_speech_loop(speech_detector, recording, speech_rx);
+            });
+        }
+
         // Clone for audio callback
-        let voice_frame_count_clone = voice_frame_count.clone();
-        let last_wake_emission_clone = last_wake_emission.clone();
         let silence_frame_count_clone = silence_frame_count.clone();
         let has_detected_speech_clone = has_detected_speech.clone();
         let recording_frame_count_clone = recording_frame_count.clone();
         let state_clone = state.clone();
         let recording_buffer_clone = recording_buffer.clone();
-        let recording_complete_clone = recording_complete.clone();
         let skip_frames_clone = skip_frames_counter.clone();
         let wake_word_clone = wake_word_active.clone();
-        let voice_clone = voice_detected.clone();
-        let app_clone = app_handle.clone();
-        let vad_sensitivity_clone = vad_sensitivity.clone();
-        let vad_timeout_ms_clone = vad_timeout_ms.clone();
-
-        // Build audio input stream
-        let stream = device
-            .build_input_stream(
-                &config,
-                move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                    // Check if we should still be processing
-                    if !wake_word_clone.load(Ordering::Relaxed) {
-                        return;
-                    }
-
-                    // Get current state
-                    let current_state = state_clone.lock().map(|s| *s).unwrap_or(VoiceState::Idle);
-
-                    // Load current VAD settings
-                    let sensitivity = vad_sensitivity_clone.lock().map(|s| *s).unwrap_or(0.02);
-                    let timeout_ms = vad_timeout_ms_clone.lock().map(|t| *t).unwrap_or(1280);
-
-                    // Calculate silence_frames from timeout_ms
-                    // Each chunk is 512 samples at 16kHz = 32ms
-                    let silence_frames = (timeout_ms as f32 / 32.0).round() as usize;
+        // The end-of-speech detector forgets the previous recording with the next frame
+        // that reaches it
+        let mut reset_speech_detector = false;
+
+        // Barge-in state (only used while Speaking)
+        let mut barge_in_detector = BargeInDetector::new();
+        let mut barge_in_pre_roll: std::collections::VecDeque<Vec<f32>> =
+            std::collections::VecDeque::with_capacity(barge_in::TRIGGER_FRAMES);
+
+        // Process each converted 16kHz chunk
+        let on_chunk = move |data: &[f32]| {
+            // Check if we should still be processing
+            if !wake_word_clone.load(Ordering::Relaxed) {
+                return;
+            }
 
-                    // Wake word threshold is 2.5x the sensitivity for more reliable detection
-                    let wake_threshold = sensitivity * 2.5;
+            // Get current state
+            let current_state = state_clone.lock().map(|s| *s).unwrap_or(VoiceState::Idle);
 
-                    // Calculate energy level of this chunk
-                    let energy = calculate_rms_energy(data);
+            // Barge-in audio from an earlier playback isn't part of the next one
+            if current_state != VoiceState::Speaking && !barge_in_pre_roll.is_empty() {
+                barge_in_pre_roll.clear();
+                barge_in_detector.reset();
+            }
 
-                    // STATE MACHINE: Process audio based on current state
-                    match current_state {
-                        VoiceState::Speaking => {
-                            // ===== SPEAKING MODE =====
+            // STATE MACHINE: Process audio based on current state
+            match current_state {
+                VoiceState::Speaking => {
+                    // ===== SPEAKING MODE =====
+                    match barge_in.mode {
+                        BargeInMode::Off => {
                             // Completely ignore audio input to prevent feedback loop
                             // The assistant's TTS output won't trigger the wake word detector
                             return;
                         }
-                        VoiceState::Transcribing => {
-                            // ===== TRANSCRIBING MODE =====
-
-                            // Skip initial frames after wake word to prevent capturing the wake word itself
-                            let skip_count = skip_frames_clone.load(Ordering::Relaxed);
-                            if skip_count > 0 {
-                                skip_frames_clone.fetch_sub(1, Ordering::Relaxed);
-                                return; // Discard this frame
+                        BargeInMode::WakeWord => {
+                            // Only a wake phrase interrupts; the wake word thread decides
+                            let _ = wake_tx.try_send(data.to_vec());
+                        }
+                        BargeInMode::Voice => {
+                            // Keep the frames that trigger the barge-in, they're the start of the request
+                            if barge_in_pre_roll.len() == barge_in::TRIGGER_FRAMES {
+                                barge_in_pre_roll.pop_front();
+                            }
+                            barge_in_pre_roll.push_back(data.to_vec());
+
+                            let threshold = barge_in.vad_sensitivity.lock().map(|s| *s).unwrap_or(0.02);
+                            if barge_in_detector.process(data, threshold, barge_in.playback.echo_level()) {
+                                let pre_roll: Vec<f32> = barge_in_pre_roll.drain(..).flatten().collect();
+                                if barge_in.interrupt(&pre_roll, "voice") {
+                                    // The user is already speaking: continue as a recording in progress
+                                    reset_speech_detector = true;
+                                    has_detected_speech_clone.store(true, Ordering::Relaxed);
+                                    silence_frame_count_clone.store(0, Ordering::Relaxed);
+                                    recording_frame_count_clone.store(barge_in::TRIGGER_FRAMES, Ordering::Relaxed);
+                                }
                             }
+                        }
+                    }
+                }
+                VoiceState::Transcribing => {
+                    // ===== TRANSCRIBING MODE =====
+
+                    // Skip initial frames after wake word to prevent capturing the wake word itself
+                    let skip_count = skip_frames_clone.load(Ordering::Relaxed);
+                    if skip_count > 0 {
+                        skip_frames_clone.fetch_sub(1, Ordering::Relaxed);
+                        return; // Discard this frame
+                    }
 
-                            // Increment recording frame counter
-                            let frame_count = recording_frame_count_clone.fetch_add(1, Ordering::Relaxed) + 1;
+                    // Increment recording frame counter
+                    let frame_count = recording_frame_count_clone.fetch_add(1, Ordering::Relaxed) + 1;
 
-                            // Add all samples to recording buffer
-                            if let Ok(mut buffer) = recording_buffer_clone.lock() {
-                                buffer.extend_from_slice(data);
-                            }
+                    // Add all samples to recording buffer
+                    if let Ok(mut buffer) = recording_buffer_clone.lock() {
+                        buffer.extend_from_slice(data);
+                    }
 
-                            // VAD: Detect speech start and end
-                            if energy > sensitivity {
-                                // Voice detected
-                                let was_speech = has_detected_speech_clone.swap(true, Ordering::Relaxed);
-                                silence_frame_count_clone.store(0, Ordering::Relaxed);
+                    // New recording: drop VAD state from the previous one
+                    if frame_count == 1 {
+                        reset_speech_detector = true;
+                    }
 
-                                // Log first speech detection
-                                if !was_speech {
-                                    debug!("Speech detected! (energy: {:.4} > threshold: {:.4})", energy, sensitivity);
-                                }
-                            } else {
-                                // Silence detected
-                                if has_detected_speech_clone.load(Ordering::Relaxed) {
-                                    // Only allow silence detection after minimum recording duration
-                                    if frame_count >= MIN_RECORDING_FRAMES {
-                                        let silence_count = silence_frame_count_clone.fetch_add(1, Ordering::Relaxed) + 1;
-
-                                        // Check if we've had enough silence to end recording
-                                        if silence_count >= silence_frames {
-                                            debug!("Silence detected after speech - ending recording (frames: {}, silence_frames: {})",
-                                                   frame_count, silence_count);
-                                            recording_complete_clone.store(true, Ordering::Relaxed);
-
-                                            // Reset recording state for next time
-                                            has_detected_speech_clone.store(false, Ordering::Relaxed);
-                                            silence_frame_count_clone.store(0, Ordering::Relaxed);
-                                            recording_frame_count_clone.store(0, Ordering::Relaxed);
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        VoiceState::ListeningForWakeWord => {
-                            // ===== WAKE WORD DETECTION MODE =====
-                            // Energy-based wake word detection
-                            if energy > wake_threshold {
-                                let count = voice_frame_count_clone.fetch_add(1, Ordering::Relaxed) + 1;
-
-                                // Require consistent voice activity to avoid false positives
-                                if count >= VOICE_FRAMES_REQUIRED {
-                                    // Only emit wake word event every 3 seconds to avoid spam
-                                    let now = std::time::Instant::now();
-                                    if let Ok(mut last_emission) = last_wake_emission_clone.lock() {
-                                        if now.duration_since(*last_emission).as_secs() >= 3 {
-                                            debug!("Voice activity detected (energy: {:.4}, threshold: {:.4})", energy, wake_threshold);
-
-                                            // Emit wake word detected event
-                                            if let Err(e) = app_clone.emit("wake_word_detected", ()) {
-                                                error!("Failed to emit wake_word_detected: {}", e);
-                                            }
-
-                                            voice_clone.store(true, Ordering::Relaxed);
-                                            *last_emission = now;
-                                            voice_frame_count_clone.store(0, Ordering::Relaxed);
-                                        }
-                                    }
-                                }
-                            } else if energy < sensitivity {
-                                // Reset counter if we detect silence
-                                voice_frame_count_clone.fetch_update(
-                                    Ordering::Relaxed,
-                                    Ordering::Relaxed,
-                                    |x| Some(x.saturating_sub(1))
-                                ).ok();
-                            }
-                        }
-                        VoiceState::Idle => {
-                            // ===== IDLE MODE =====
-                            // Do nothing, pipeline is not active
-                            return;
-                        }
+                    // VAD: the end-of-speech thread detects speech start and end
+                    let frame = SpeechFrame {
+                        samples: data.to_vec(),
+                        number: frame_count,
+                        reset: reset_speech_detector,
+                    };
+                    if speech_tx.try_send(frame).is_ok() {
+                        reset_speech_detector = false;
                     }
-                },
-                move |err| {
-                    error!("Audio stream error: {}", err);
-                },
-                None,
-            )
-            .map_err(|e| format!("Failed to build input stream: {}", e))?;
+                }
+                VoiceState::ListeningForWakeWord => {
+                    // ===== WAKE WORD DETECTION MODE =====
+                    // Hand the audio to the wake word thread
+                    let _ = wake_tx.try_send(data.to_vec());
+                }
+                VoiceState::Idle => {
+                    // ===== IDLE MODE =====
+                    // Do nothing, pipeline is not active
+                    return;
+                }
+            }
+        };
+
+        // Build audio input stream
+        let stream = match sample_format {
+            SampleFormat::F32 => build_capture_stream::<f32>(&device, &config, converter, on_chunk),
+            SampleFormat::I16 => build_capture_stream::<i16>(&device, &config, converter, on_chunk),
+            SampleFormat::U16 => build_capture_stream::<u16>(&device, &config, converter, on_chunk),
+            SampleFormat::I32 => build_capture_stream::<i32>(&device, &config, converter, on_chunk),
+            other => Err(format!("Unsupported input sample format: {}", other)),
+        }?;
 
         // Start the audio stream
         stream
             .play()
             .map_err(|e| format!("Failed to play stream: {}", e))?;
 
-        info!("✓ Audio stream started (wake word: energy-based VAD)");
+        info!("✓ Audio stream started (wake word: {}, VAD: {})", wake_detector_name, speech_detector_name);
 
         // Keep the stream alive
         loop {
@@ -403,6 +872,122 @@ impl NativeVoicePipeline {
         Ok(())
     }
 
+    /// Feed recorded audio to the VAD and set `recording_complete` at end of speech
+    ///
+    /// Runs until the audio stream (the sending side of `frames`) is dropped.
+    fn run_speech_loop(
+        mut speech_detector: Box<dyn VoiceActivityDetector>,
+        recording: RecordingProgress,
+        frames: std::sync::mpsc::Receiver<SpeechFrame>,
+    ) {
+        while let Ok(frame) = frames.recv() {
+            if frame.reset {
+                speech_detector.reset();
+            }
+
+            // Load current VAD settings
+            let timeout_ms = recording.vad_timeout_ms.lock().map(|t| *t).unwrap_or(1280);
+
+            // Calculate silence_frames from timeout_ms
+            // Each chunk is 512 samples at 16kHz = 32ms
+            let silence_frames = (timeout_ms as f32 / 32.0).round() as usize;
+
+            // VAD: Detect speech start and end
+            let decision = speech_detector.analyze_frame(&frame.samples);
+            if decision.is_speech {
+                // Voice detected
+                let was_speech = recording.has_detected_speech.swap(true, Ordering::Relaxed);
+                recording.silence_frame_count.store(0, Ordering::Relaxed);
+
+                // Log first speech detection
+                if !was_speech {
+                    match decision.probability {
+                        Some(probability) => debug!("Speech detected! ({} VAD: {:.2} > threshold: {:.2})",
+                                                    speech_detector.name(), probability, SPEECH_THRESHOLD),
+                        None => debug!("Speech detected! ({} VAD)", speech_detector.name()),
+                    }
+                }
+            } else {
+                // Silence detected
+                if recording.has_detected_speech.load(Ordering::Relaxed) {
+                    // Only allow silence detection after minimum recording duration
+                    if frame.number >= MIN_RECORDING_FRAMES {
+                        let silence_count = recording.silence_frame_count.fetch_add(1, Ordering::Relaxed) + 1;
+
+                        // Check if we've had enough silence to end recording
+                        if silence_count >= silence_frames {
+                            debug!("Silence detected after speech - ending recording (frames: {}, silence_frames: {})",
+                                   frame.number, silence_count);
+                            recording.recording_complete.store(true, Ordering::Relaxed);
+
+                            // Reset recording state for next time
+                            recording.has_detected_speech.store(false, Ordering::Relaxed);
+                            recording.silence_frame_count.store(0, Ordering::Relaxed);
+                            recording.recording_frame_count.store(0, Ordering::Relaxed);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Feed audio to the wake word detector and emit wake_word_detected on a match
+    ///
+    /// Runs until the audio stream (the sending side of `chunks`) is dropped.
+    fn run_wake_word_loop(
+        app_handle: AppHandle,
+        state: Arc<Mutex<VoiceState>>,
+        voice_detected: Arc<AtomicBool>,
+        mut detector: Box<dyn WakeWordDetector>,
+        barge_in: BargeIn,
+        chunks: std::sync::mpsc::Receiver<Vec<f32>>,
+    ) {
+        info!("Wake word detector started ({})", detector.name());
+
+        let mut last_chunk = std::time::Instant::now();
+        let mut last_detection: Option<std::time::Instant> = None;
+
+        while let Ok(chunk) = chunks.recv() {
+            // Audio from before a recording or TTS playback isn't continuous with this chunk
+            if last_chunk.elapsed() > Duration::from_millis(WAKE_STREAM_GAP_MS) {
+                detector.reset();
+            }
+            last_chunk = std::time::Instant::now();
+
+            let Some(phrase) = detector.process(&chunk) else {
+                continue;
+            };
+            detector.reset();
+
+            // Only emit wake word event every few seconds to avoid spam
+            if last_detection.is_some_and(|t| t.elapsed() < Duration::from_secs(WAKE_DEBOUNCE_SECS)) {
+                continue;
+            }
+
+            // The pipeline may have moved on while this chunk was queued
+            match state.lock().map(|s| *s).unwrap_or(VoiceState::Idle) {
+                VoiceState::ListeningForWakeWord => {}
+                VoiceState::Speaking if barge_in.mode == BargeInMode::WakeWord => {
+                    if barge_in.interrupt(&[], &phrase) {
+                        last_detection = Some(std::time::Instant::now());
+                    }
+                    continue;
+                }
+                _ => continue,
+            }
+
+            info!("Wake word detected: '{}' ({})", phrase, detector.name());
+            last_detection = Some(std::time::Instant::now());
+            voice_detected.store(true, Ordering::Relaxed);
+
+            if let Err(e) = app_handle.emit("wake_word_detected", phrase) {
+                error!("Failed to emit wake_word_detected: {}", e);
+            }
+        }
+
+        info!("Wake word detector stopped");
+    }
+
     /// Manually trigger transcription (Push-to-Talk or after wake word)
     ///
     /// This method:
@@ -417,8 +1002,8 @@ impl NativeVoicePipeline {
     /// This function is idempotent and thread-safe - it can be safely called
     /// multiple times in a row without state corruption.
     ///
-    /// Returns: (transcription_text, audio_samples)
-    pub fn start_transcription(&self) -> Result<(String, Vec<f32>), String> {
+    /// Returns: (transcript, audio_samples)
+    pub fn start_transcription(&self) -> Result<(Transcript, Vec<f32>), String> {
         info!("Transcription triggered");
 
         if !self.wake_word_active.load(Ordering::Relaxed) {
@@ -435,32 +1020,40 @@ impl NativeVoicePipeline {
         }
 
         // STATE TRANSITION: Move to Transcribing state (with guard against concurrent calls)
-        {
+        let barge_in_recording = {
             let mut state = self.state.lock()
                 .map_err(|e| format!("Failed to lock state: {}", e))?;
 
-            // Guard: Don't allow transcription if already transcribing
+            // Guard: Don't allow transcription if already transcribing, unless it's
+            // the recording a barge-in started for this call to pick up
             if *state == VoiceState::Transcribing {
-                return Err("Already transcribing - please wait for current recording to complete".to_string());
+                if !self.barge_in_recording.swap(false, Ordering::Relaxed) {
+                    return Err("Already transcribing - please wait for current recording to complete".to_string());
+                }
+                info!("Continuing recording started by barge-in");
+                true
+            } else {
+                let prev_state = *state;
+                *state = VoiceState::Transcribing;
+                info!("Voice state: {:?} -> Transcribing", prev_state);
+                false
             }
+        };
 
-            let prev_state = *state;
-            *state = VoiceState::Transcribing;
-            info!("Voice state: {:?} -> Transcribing", prev_state);
-        }
+        if !barge_in_recording {
+            // CRITICAL: Prepare for new recording by resetting ALL state
+            // This ensures idempotency - each transcription starts with a clean slate
+            info!("Resetting recording state for new transcription...");
+            {
+                let mut buffer = self.recording_buffer.lock().unwrap();
+                buffer.clear();
+            }
+            self.recording_complete.store(false, Ordering::Relaxed);
+            self.voice_detected.store(false, Ordering::Relaxed);
 
-        // CRITICAL: Prepare for new recording by resetting ALL state
-        // This ensures idempotency - each transcription starts with a clean slate
-        info!("Resetting recording state for new transcription...");
-        {
-            let mut buffer = self.recording_buffer.lock().unwrap();
-            buffer.clear();
+            // Set skip counter to discard initial frames and prevent wake word capture
+            self.skip_frames_counter.store(SKIP_FRAMES_AFTER_WAKE_WORD, Ordering::Relaxed);
         }
-        self.recording_complete.store(false, Ordering::Relaxed);
-        self.voice_detected.store(false, Ordering::Relaxed);
-
-        // Set skip counter to discard initial frames and prevent wake word capture
-        self.skip_frames_counter.store(SKIP_FRAMES_AFTER_WAKE_WORD, Ordering::Relaxed);
         info!("Recording started - speak now...");
         debug!(
             "Recording config: skip_frames={}, min_frames={}, vad_sensitivity={:.4}, vad_timeout_ms={}",
@@ -474,6 +1067,12 @@ impl NativeVoicePipeline {
         let start_time = std::time::Instant::now();
         let timeout = std::time::Duration::from_secs(MAX_RECORDING_SECONDS as u64);
 
+        // Live captions: re-transcribe the growing recording while the user speaks
+        let partial_interval = Duration::from_millis(self.stt_partial_interval_ms as u64);
+        let mut captions = (self.stt_partial_interval_ms > 0)
+            .then(|| LiveCaptions::new(self.whisper_transcriber(&whisper_model), self.app_handle.clone()));
+        let mut last_partial = std::time::Instant::now();
+
         loop {
             // Check if recording completed (silence detected by audio callback)
             if self.recording_complete.load(Ordering::Relaxed) {
@@ -487,16 +1086,25 @@ impl NativeVoicePipeline {
                 break;
             }
 
+            if let Some(captions) = captions.as_mut() {
+                if last_partial.elapsed() >= partial_interval {
+                    last_partial = std::time::Instant::now();
+                    captions.tick(&self.recording_buffer);
+                }
+            }
+
             // Small sleep to avoid busy loop
             std::thread::sleep(std::time::Duration::from_millis(50));
         }
+        // A partial pass still running is abandoned
+        drop(captions);
 
-        // STATE TRANSITION: Return to ListeningForWakeWord
+        // STATE TRANSITION: Return to ListeningForWakeWord (or Speaking)
         {
             let mut state = self.state.lock()
                 .map_err(|e| format!("Failed to lock state: {}", e))?;
-            *state = VoiceState::ListeningForWakeWord;
-            info!("Voice state: Transcribing -> ListeningForWakeWord");
+            *state = self.resting_state();
+            info!("Voice state: Transcribing -> {:?}", *state);
         }
 
         // Extract recorded audio
@@ -549,6 +1157,27 @@ impl NativeVoicePipeline {
         // Transcribe using whisper-rs (with automatic cleanup via defer-like pattern)
         let transcription_result = self.transcribe_with_whisper(&recording_samples, &whisper_model);
 
+        if let Ok(transcript) = &transcription_result {
+            if let Err(e) = self.app_handle.emit("final_transcription", transcript) {
+                warn!("Failed to emit final_transcription event: {}", e);
+            }
+        }
+
+        if self.debug_capture.is_enabled() {
+            let result = transcription_result
+                .as_ref()
+                .map(|t| (t.text.as_str(), t.language.as_deref()))
+                .map_err(|e| e.as_str());
+            let vad = VadParameters {
+                sensitivity: *vad_sens,
+                timeout_ms: *self.vad_timeout_ms.lock().unwrap(),
+                engine: self.vad_engine.clone(),
+            };
+            if let Err(e) = self.debug_capture.save(&recording_samples, result, vad) {
+                warn!("Failed to save debug capture: {}", e);
+            }
+        }
+
         // CRITICAL: Full cleanup after transcription (whether it succeeded or failed)
         // This ensures the pipeline is ready for the next transcription
         info!("Performing final cleanup after transcription...");
@@ -565,7 +1194,7 @@ impl NativeVoicePipeline {
         // Return the transcription result and audio samples (for speaker identification)
         // Clone the samples before cleanup so they can be used for speaker ID
         match transcription_result {
-            Ok(text) => Ok((text, recording_samples)),
+            Ok(transcript) => Ok((transcript, recording_samples)),
             Err(e) => Err(e),
         }
     }
@@ -610,56 +1239,37 @@ impl NativeVoicePipeline {
     }
 
     /// Transcribe audio samples using Whisper
-    fn transcribe_with_whisper(&self, samples: &[f32], model_path: &PathBuf) -> Result<String, String> {
-        info!("Initializing Whisper for transcription...");
-
-        // Load Whisper model
-        let ctx = WhisperContext::new_with_params(
-            model_path.to_str().unwrap(),
-            WhisperContextParameters::default()
-        ).map_err(|e| format!("Failed to load Whisper model: {}", e))?;
-
-        // Configure transcription parameters
-        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
-
-        // Set language to English
-        params.set_language(Some("en"));
-        params.set_print_special(false);
-        params.set_print_progress(false);
-        params.set_print_realtime(false);
-        params.set_print_timestamps(false);
-
-        // Create Whisper state
-        let mut state = ctx.create_state()
-            .map_err(|e| format!("Failed to create Whisper state: {}", e))?;
-
-        // Run transcription
-        info!("Running Whisper transcription...");
-        state.full(params, samples)
-            .map_err(|e| format!("Whisper transcription failed: {}", e))?;
-
-        // Extract transcribed text
-        let num_segments = state.full_n_segments();
+    fn transcribe_with_whisper(&self, samples: &[f32], model_path: &Path) -> Result<Transcript, String> {
+        self.whisper_transcriber(model_path).transcript(samples, false)
+    }
 
-        let mut transcription = String::new();
-        for i in 0..num_segments {
-            if let Some(segment) = state.get_segment(i) {
-                let text = segment.to_str()
-                    .map_err(|e| format!("Failed to get segment {} text: {}", i, e))?;
-                transcription.push_str(text);
-                transcription.push(' ');
-            }
+    /// Whisper with this pipeline's language and vocabulary settings
+    fn whisper_transcriber(&self, model_path: &Path) -> WhisperTranscriber {
+        WhisperTranscriber {
+            whisper_cache: self.whisper_cache.clone(),
+            model_path: model_path.to_path_buf(),
+            language: whisper_language(&self.stt_language, &self.stt_model_name),
+            initial_prompt: self.vocabulary.prompt(),
         }
+    }
 
-        let transcription = transcription.trim().to_string();
+    /// Directory holding the Whisper, VAD and wake word models
+    pub fn model_path(&self) -> &Path {
+        &self.model_path
+    }
 
-        if transcription.is_empty() {
-            info!("Whisper returned empty transcription - no speech detected");
-            return Err("No speech detected in audio".to_string());
+    /// Whisper model and language settings for transcribing audio outside the live
+    /// pipeline (see `WhisperTranscriber`)
+    pub fn transcriber(&self) -> Result<WhisperTranscriber, String> {
+        let model_path = self.get_stt_model_path();
+        if !model_path.exists() {
+            return Err(format!(
+                "Whisper model not found: {:?}. Please download {}",
+                model_path, self.stt_model_name
+            ));
         }
 
-        info!("Transcription complete: '{}'", transcription);
-        Ok(transcription)
+        Ok(self.whisper_transcriber(&model_path))
     }
 
     /// Update VAD settings in real-time
@@ -699,11 +1309,18 @@ impl NativeVoicePipeline {
     /// Set the voice pipeline state
     ///
     /// This is used to control the state machine from external commands.
-    /// Key use case: Setting to Speaking when TTS starts to prevent feedback loop.
+    /// Speaking follows TTS playback on its own (see `start`).
     pub fn set_state(&self, new_state: VoiceState) -> Result<(), String> {
         let mut state = self.state.lock()
             .map_err(|e| format!("Failed to lock state: {}", e))?;
 
+        // A barge-in recording belongs to the next start_transcription call; the
+        // frontend finishing its interrupted turn mustn't end it
+        if self.barge_in_recording.load(Ordering::Relaxed) && new_state != VoiceState::Transcribing {
+            info!("Ignoring voice state change to {:?} during barge-in recording", new_state);
+            return Ok(());
+        }
+
         let prev_state = *state;
         *state = new_state;
         info!("Voice state: {:?} -> {:?}", prev_state, new_state);
@@ -729,6 +1346,7 @@ impl NativeVoicePipeline {
         self.recording_complete.store(false, Ordering::Relaxed);
         self.voice_detected.store(false, Ordering::Relaxed);
         self.skip_frames_counter.store(0, Ordering::Relaxed);
+        self.barge_in_recording.store(false, Ordering::Relaxed);
 
         // Clear recording buffer
         {
@@ -737,19 +1355,28 @@ impl NativeVoicePipeline {
             buffer.clear();
         }
 
-        // STATE TRANSITION: Force reset to ListeningForWakeWord
+        // STATE TRANSITION: Force reset to ListeningForWakeWord (or Speaking)
         {
             let mut state = self.state.lock()
                 .map_err(|e| format!("Failed to lock state: {}", e))?;
             let prev_state = *state;
-            *state = VoiceState::ListeningForWakeWord;
-            info!("Voice state: {:?} -> ListeningForWakeWord (forced reset)", prev_state);
+            *state = self.resting_state();
+            info!("Voice state: {:?} -> {:?} (forced reset)", prev_state, *state);
         }
 
         info!("✓ Voice pipeline reset complete - ready for next operation");
         Ok(())
     }
 
+    /// State between requests: Speaking while TTS audio is still playing
+    fn resting_state(&self) -> VoiceState {
+        if self.playback.is_active() {
+            VoiceState::Speaking
+        } else {
+            VoiceState::ListeningForWakeWord
+        }
+    }
+
     /// Stop the voice pipeline
     pub fn stop(&self) {
         info!("Stopping native voice pipeline");
@@ -767,7 +1394,7 @@ impl NativeVoicePipeline {
     /// Check if the voice pipeline is ready
     pub fn check_readiness(&self) -> bool {
         // Check if configured whisper model exists
-        // Wake word uses energy-based VAD (no model needed)
+        // Wake word falls back to energy-based VAD if the keyword model is missing
         let whisper_model_path = self.model_path.join(&self.stt_model_name);
         let whisper_exists = whisper_model_path.exists();
 
@@ -802,6 +1429,115 @@ impl Drop for NativeVoicePipeline {
     }
 }
 
+/// Capture device details for the microphone picker
+#[derive(Debug, Clone, Serialize)]
+pub struct InputDeviceInfo {
+    pub name: String,
+    pub is_default: bool,
+    /// Format the pipeline will capture in (None if the device doesn't report one)
+    pub default_config: Option<InputConfigInfo>,
+    pub supported_configs: Vec<InputConfigInfo>,
+}
+
+/// A capture format supported by an input device
+#[derive(Debug, Clone, Serialize)]
+pub struct InputConfigInfo {
+    pub channels: u16,
+    pub min_sample_rate: u32,
+    pub max_sample_rate: u32,
+    pub sample_format: String,
+}
+
+/// List the available microphones and their capture formats
+pub fn list_input_devices() -> Result<Vec<InputDeviceInfo>, String> {
+    let host = cpal::default_host();
+    let default_name = host.default_input_device().and_then(|d| d.name().ok());
+
+    let devices = host
+        .input_devices()
+        .map_err(|e| format!("Failed to enumerate input devices: {}", e))?;
+
+    Ok(devices
+        .filter_map(|device| {
+            let name = device.name().ok()?;
+            let default_config = device.default_input_config().ok().map(|config| InputConfigInfo {
+                channels: config.channels(),
+                min_sample_rate: config.sample_rate().0,
+                max_sample_rate: config.sample_rate().0,
+                sample_format: config.sample_format().to_string(),
+            });
+            let supported_configs = device
+                .supported_input_configs()
+                .map(|configs| {
+                    configs
+                        .map(|range| InputConfigInfo {
+                            channels: range.channels(),
+                            min_sample_rate: range.min_sample_rate().0,
+                            max_sample_rate: range.max_sample_rate().0,
+                            sample_format: range.sample_format().to_string(),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            Some(InputDeviceInfo {
+                is_default: default_name.as_deref() == Some(name.as_str()),
+                name,
+                default_config,
+                supported_configs,
+            })
+        })
+        .collect())
+}
+
+/// Find the configured microphone, falling back to the system default
+fn select_input_device(host: &cpal::Host, name: &str) -> Result<cpal::Device, String> {
+    if !name.is_empty() {
+        let found = host
+            .input_devices()
+            .map_err(|e| format!("Failed to enumerate input devices: {}", e))?
+            .find(|device| device.name().map(|n| n == name).unwrap_or(false));
+
+        match found {
+            Some(device) => return Ok(device),
+            None => warn!("Input device '{}' not found, using the default microphone", name),
+        }
+    }
+
+    host.default_input_device()
+        .ok_or_else(|| "No input device available".to_string())
+}
+
+/// Build an input stream for sample type `T`, converting captured audio into
+/// 16kHz mono chunks for `on_chunk`
+fn build_capture_stream<T>(
+    device: &cpal::Device,
+    config: &StreamConfig,
+    mut converter: AudioConverter,
+    mut on_chunk: impl FnMut(&[f32]) + Send + 'static,
+) -> Result<cpal::Stream, String>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    let mut samples = Vec::new();
+
+    device
+        .build_input_stream(
+            config,
+            move |data: &[T], _: &cpal::InputCallbackInfo| {
+                samples.clear();
+                samples.extend(data.iter().map(|&s| f32::from_sample(s)));
+                converter.process(&samples, &mut on_chunk);
+            },
+            move |err| {
+                error!("Audio stream error: {}", err);
+            },
+            None,
+        )
+        .map_err(|e| format!("Failed to build input stream: {}", e))
+}
+
 /// Calculate RMS energy of audio samples
 fn calculate_rms_energy(samples: &[f32]) -> f32 {
     if samples.is_empty() {
@@ -811,3 +1547,48 @@ fn calculate_rms_energy(samples: &[f32]) -> f32 {
     let sum_squares: f32 = samples.iter().map(|&s| s * s).sum();
     (sum_squares / samples.len() as f32).sqrt()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_audio_file_converts_to_16khz_mono() {
+        let path = std::env::temp_dir().join(format!("aura_load_audio_{}.wav", std::process::id()));
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 44100,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for i in 0..44100 {
+            // The tone is inverted on the right channel and cancels out on downmix
+            let tone = ((i as f32 * 0.05).sin() * 8000.0) as i16;
+            writer.write_sample(1000 + tone).unwrap();
+            writer.write_sample(1000 - tone).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let samples = load_audio_file(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(samples.len(), 16000);
+        let expected = 1000.0 / 32768.0;
+        assert!(samples[100..15900].iter().all(|s| (s - expected).abs() < 0.01));
+    }
+
+    #[test]
+    fn test_whisper_language() {
+        // Auto-detection on multilingual models
+        assert_eq!(whisper_language("auto", "ggml-base.bin"), None);
+        assert_eq!(whisper_language("", "ggml-base.bin"), None);
+        // Configured language codes are normalized
+        assert_eq!(whisper_language(" DE ", "ggml-small.bin"), Some("de".to_string()));
+        // English-only models always transcribe English
+        assert_eq!(whisper_language("auto", "ggml-base.en.bin"), Some("en".to_string()));
+        assert_eq!(whisper_language("fr", "ggml-tiny.en.bin"), Some("en".to_string()));
+        // Invalid codes fall back to auto-detection
+        assert_eq!(whisper_language("german", "ggml-base.bin"), None);
+    }
+}
@@ -0,0 +1,551 @@
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::Duration;
+
+/// Timeout for quick management calls (tags, show, delete, ps)
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+
+/// Timeout for establishing a connection (pulls themselves may run for a long time)
+const CONNECT_TIMEOUT_SECS: u64 = 5;
+
+/// Model installed in Ollama (`/api/tags`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OllamaModel {
+    pub name: String,
+    #[serde(default)]
+    pub size: u64,
+    #[serde(default)]
+    pub digest: String,
+    #[serde(default)]
+    pub modified_at: String,
+    #[serde(default)]
+    pub details: Option<ModelDetails>,
+}
+
+/// Model metadata reported by `/api/tags` and `/api/show`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelDetails {
+    #[serde(default)]
+    pub format: String,
+    #[serde(default)]
+    pub family: String,
+    #[serde(default)]
+    pub parameter_size: String,
+    #[serde(default)]
+    pub quantization_level: String,
+}
+
+/// Detailed model information (`/api/show`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OllamaModelInfo {
+    #[serde(default)]
+    pub modelfile: String,
+    #[serde(default)]
+    pub parameters: String,
+    #[serde(default)]
+    pub template: String,
+    #[serde(default)]
+    pub details: ModelDetails,
+    /// Architecture-specific keys (e.g., "gemma.context_length")
+    #[serde(default)]
+    pub model_info: serde_json::Map<String, serde_json::Value>,
+}
+
+impl OllamaModelInfo {
+    /// Context window the model runs with, in tokens
+    ///
+    /// A `num_ctx` parameter from the Modelfile wins over the architecture's
+    /// trained context length (`<arch>.context_length` in `model_info`).
+    pub fn context_length(&self) -> Option<u32> {
+        let num_ctx = self.parameters.lines().find_map(|line| {
+            let mut parts = line.split_whitespace();
+            match (parts.next(), parts.next()) {
+                (Some("num_ctx"), Some(value)) => value.parse().ok(),
+                _ => None,
+            }
+        });
+
+        num_ctx.or_else(|| {
+            self.model_info
+                .iter()
+                .find(|(key, _)| key.ends_with(".context_length"))
+                .and_then(|(_, value)| value.as_u64())
+                .map(|length| length.min(u32::MAX as u64) as u32)
+        })
+    }
+}
+
+/// Model currently loaded into memory (`/api/ps`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunningModel {
+    pub name: String,
+    #[serde(default)]
+    pub size: u64,
+    #[serde(default)]
+    pub size_vram: u64,
+    #[serde(default)]
+    pub expires_at: String,
+}
+
+/// Progress of a model pull, emitted to the frontend as `ollama_pull_progress`
+#[derive(Debug, Clone, Serialize)]
+pub struct PullProgress {
+    pub model: String,
+    /// Ollama status line (e.g., "pulling manifest", "downloading", "success")
+    pub status: String,
+    pub digest: Option<String>,
+    pub total: Option<u64>,
+    pub completed: Option<u64>,
+    /// Percentage of the current layer (only while downloading)
+    pub percentage: Option<f32>,
+}
+
+/// One line of the NDJSON stream returned by `/api/pull`
+#[derive(Debug, Deserialize)]
+struct PullStatusLine {
+    #[serde(default)]
+    status: String,
+    #[serde(default)]
+    digest: Option<String>,
+    #[serde(default)]
+    total: Option<u64>,
+    #[serde(default)]
+    completed: Option<u64>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagsResponse {
+    #[serde(default)]
+    models: Vec<OllamaModel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PsResponse {
+    #[serde(default)]
+    models: Vec<RunningModel>,
+}
+
+/// Client for Ollama's native management API
+///
+/// The OpenAI-compatible shim (`/v1`) only covers chat completions and a basic model
+/// list; pulling, inspecting and deleting models needs the native `/api` endpoints.
+pub struct OllamaClient {
+    client: Client,
+    base_url: String,
+}
+
+impl OllamaClient {
+    /// Create a client for an Ollama server
+    ///
+    /// # Arguments
+    /// * `base_url` - Server root URL (e.g., "http://127.0.0.1:11434")
+    pub fn new(base_url: &str) -> Result<Self, String> {
+        let client = Client::builder()
+            .connect_timeout(Duration::from_secs(CONNECT_TIMEOUT_SECS))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+        Ok(OllamaClient {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    /// Create a client from the OpenAI-compatible API base URL stored in Settings
+    ///
+    /// "http://localhost:11434/v1" becomes "http://localhost:11434".
+    pub fn from_api_base_url(api_base_url: &str) -> Result<Self, String> {
+        let trimmed = api_base_url.trim_end_matches('/');
+        Self::new(trimmed.strip_suffix("/v1").unwrap_or(trimmed))
+    }
+
+    /// List installed models (`GET /api/tags`)
+    pub async fn list_models(&self) -> Result<Vec<OllamaModel>, String> {
+        let response = self
+            .client
+            .get(format!("{}/api/tags", self.base_url))
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to connect to Ollama at {}: {}", self.base_url, e))?;
+
+        let tags: TagsResponse = check_status(response)
+            .await?
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse Ollama model list: {}", e))?;
+
+        Ok(tags.models)
+    }
+
+    /// Show details of an installed model (`POST /api/show`)
+    pub async fn show_model(&self, model: &str) -> Result<OllamaModelInfo, String> {
+        let response = self
+            .client
+            .post(format!("{}/api/show", self.base_url))
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .json(&json!({ "model": model }))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to connect to Ollama at {}: {}", self.base_url, e))?;
+
+        check_status(response)
+            .await?
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse Ollama model info: {}", e))
+    }
+
+    /// Context window of a model (`POST /api/show`)
+    ///
+    /// `Ok(None)` is a definite answer: the model doesn't report one, or the server
+    /// isn't Ollama (an error status or a response that isn't model info). `Err` means
+    /// the server couldn't answer right now (unreachable, or a server error while it
+    /// starts up), so asking again later may succeed.
+    pub async fn context_length(&self, model: &str) -> Result<Option<u32>, String> {
+        let response = self
+            .client
+            .post(format!("{}/api/show", self.base_url))
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .json(&json!({ "model": model }))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to connect to Ollama at {}: {}", self.base_url, e))?;
+
+        if response.status().is_server_error() {
+            return Err(format!("Ollama API returned error {}", response.status()));
+        }
+
+        match check_status(response).await {
+            Ok(response) => Ok(response
+                .json::<OllamaModelInfo>()
+                .await
+                .ok()
+                .and_then(|info| info.context_length())),
+            Err(e) => {
+                log::debug!("No model info for {}: {}", model, e);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Delete an installed model (`DELETE /api/delete`)
+    pub async fn delete_model(&self, model: &str) -> Result<(), String> {
+        log::info!("Deleting Ollama model: {}", model);
+
+        let response = self
+            .client
+            .delete(format!("{}/api/delete", self.base_url))
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .json(&json!({ "model": model }))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to connect to Ollama at {}: {}", self.base_url, e))?;
+
+        check_status(response).await?;
+
+        log::info!("✓ Deleted Ollama model: {}", model);
+        Ok(())
+    }
+
+    /// List models currently loaded into memory (`GET /api/ps`)
+    pub async fn running_models(&self) -> Result<Vec<RunningModel>, String> {
+        let response = self
+            .client
+            .get(format!("{}/api/ps", self.base_url))
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to connect to Ollama at {}: {}", self.base_url, e))?;
+
+        let ps: PsResponse = check_status(response)
+            .await?
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse Ollama running models: {}", e))?;
+
+        Ok(ps.models)
+    }
+
+    /// Check whether a model is installed
+    ///
+    /// Names without a tag match the implicit ":latest" tag, like the Ollama CLI.
+    pub async fn has_model(&self, model: &str) -> Result<bool, String> {
+        let wanted = normalize_model_name(model);
+        Ok(self
+            .list_models()
+            .await?
+            .iter()
+            .any(|m| normalize_model_name(&m.name) == wanted))
+    }
+
+    /// Download a model (`POST /api/pull`), reporting progress as it streams in
+    ///
+    /// Ollama answers with one JSON status object per line; each is passed to
+    /// `on_progress`. Returns once the server reports "success".
+    pub async fn pull_model<F>(&self, model: &str, mut on_progress: F) -> Result<(), String>
+    where
+        F: FnMut(PullProgress),
+    {
+        log::info!("Pulling Ollama model: {}", model);
+
+        let response = self
+            .client
+            .post(format!("{}/api/pull", self.base_url))
+            .json(&json!({ "model": model, "stream": true }))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to connect to Ollama at {}: {}", self.base_url, e))?;
+
+        let mut response = check_status(response).await?;
+        let mut buffer: Vec<u8> = Vec::new();
+        let mut succeeded = false;
+
+        while let Some(bytes) = response
+            .chunk()
+            .await
+            .map_err(|e| format!("Failed to read pull progress: {}", e))?
+        {
+            buffer.extend_from_slice(&bytes);
+
+            // Handle every complete line; keep a trailing partial line for the next chunk
+            while let Some(newline) = buffer.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=newline).collect();
+                if let Some(progress) = parse_pull_line(model, &line)? {
+                    succeeded |= progress.status == "success";
+                    on_progress(progress);
+                }
+            }
+        }
+
+        // The final line may arrive without a trailing newline
+        if let Some(progress) = parse_pull_line(model, &buffer)? {
+            succeeded |= progress.status == "success";
+            on_progress(progress);
+        }
+
+        if !succeeded {
+            return Err(format!("Pull of {} ended before Ollama reported success", model));
+        }
+
+        log::info!("✓ Pulled Ollama model: {}", model);
+        Ok(())
+    }
+}
+
+/// Turn non-success responses into readable errors (Ollama returns `{"error": "..."}`)
+async fn check_status(response: reqwest::Response) -> Result<reqwest::Response, String> {
+    if response.status().is_success() {
+        return Ok(response);
+    }
+
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    let message = serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|v| v.get("error").and_then(|e| e.as_str()).map(String::from))
+        .unwrap_or(body);
+
+    Err(format!("Ollama API returned error {}: {}", status, message))
+}
+
+/// Parse one NDJSON line from `/api/pull` (blank lines yield `None`)
+fn parse_pull_line(model: &str, line: &[u8]) -> Result<Option<PullProgress>, String> {
+    let line = String::from_utf8_lossy(line);
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(None);
+    }
+
+    let status: PullStatusLine = serde_json::from_str(line)
+        .map_err(|e| format!("Failed to parse pull progress '{}': {}", line, e))?;
+
+    if let Some(error) = status.error {
+        return Err(format!("Failed to pull {}: {}", model, error));
+    }
+
+    let percentage = match (status.completed, status.total) {
+        (Some(completed), Some(total)) if total > 0 => Some(completed as f32 / total as f32 * 100.0),
+        _ => None,
+    };
+
+    Ok(Some(PullProgress {
+        model: model.to_string(),
+        status: status.status,
+        digest: status.digest,
+        total: status.total,
+        completed: status.completed,
+        percentage,
+    }))
+}
+
+/// Add the implicit ":latest" tag to untagged model names
+fn normalize_model_name(name: &str) -> String {
+    if name.contains(':') {
+        name.to_string()
+    } else {
+        format!("{}:latest", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Serve one canned HTTP response on a local port; returns the base URL and the raw request
+    async fn mock_server(status: &'static str, body: String) -> (String, tokio::task::JoinHandle<String>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let handle = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buffer = vec![0u8; 16 * 1024];
+            let n = socket.read(&mut buffer).await.unwrap();
+
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+
+            String::from_utf8_lossy(&buffer[..n]).to_string()
+        });
+
+        (format!("http://{}", addr), handle)
+    }
+
+    #[tokio::test]
+    async fn test_context_length_tells_unavailable_from_unknown() {
+        let (url, _) = mock_server("503 Service Unavailable", "{}".to_string()).await;
+        assert!(OllamaClient::new(&url).unwrap().context_length("llama3").await.is_err());
+
+        // Not Ollama: a definite "unknown"
+        let (url, _) = mock_server("404 Not Found", "{\"error\":\"not found\"}".to_string()).await;
+        assert_eq!(OllamaClient::new(&url).unwrap().context_length("llama3").await, Ok(None));
+
+        let body = r#"{"parameters":"num_ctx 4096","model_info":{"llama.context_length":131072}}"#;
+        let (url, _) = mock_server("200 OK", body.to_string()).await;
+        assert_eq!(OllamaClient::new(&url).unwrap().context_length("llama3").await, Ok(Some(4096)));
+    }
+
+    #[test]
+    fn test_from_api_base_url_strips_openai_suffix() {
+        assert_eq!(OllamaClient::from_api_base_url("http://localhost:11434/v1").unwrap().base_url, "http://localhost:11434");
+        assert_eq!(OllamaClient::from_api_base_url("http://localhost:11434/v1/").unwrap().base_url, "http://localhost:11434");
+        assert_eq!(OllamaClient::from_api_base_url("http://localhost:11434").unwrap().base_url, "http://localhost:11434");
+    }
+
+    #[tokio::test]
+    async fn test_list_models() {
+        let body = r#"{"models":[{"name":"gemma:2b","size":1678456656,"digest":"b50d6c999e59","modified_at":"2024-05-01T10:00:00Z","details":{"family":"gemma","parameter_size":"3B","quantization_level":"Q4_0"}}]}"#;
+        let (url, request) = mock_server("200 OK", body.to_string()).await;
+
+        let client = OllamaClient::new(&url).unwrap();
+        let models = client.list_models().await.unwrap();
+
+        assert!(request.await.unwrap().starts_with("GET /api/tags"));
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].name, "gemma:2b");
+        assert_eq!(models[0].details.as_ref().unwrap().parameter_size, "3B");
+    }
+
+    #[tokio::test]
+    async fn test_show_model_reads_model_info() {
+        let body = r#"{"parameters":"stop \"<end_of_turn>\"","template":"{{ .Prompt }}","details":{"family":"gemma"},"model_info":{"gemma.context_length":8192}}"#;
+        let (url, request) = mock_server("200 OK", body.to_string()).await;
+
+        let client = OllamaClient::new(&url).unwrap();
+        let info = client.show_model("gemma:2b").await.unwrap();
+
+        let request = request.await.unwrap();
+        assert!(request.starts_with("POST /api/show"));
+        assert!(request.contains(r#""model":"gemma:2b""#));
+        assert_eq!(info.details.family, "gemma");
+        assert_eq!(info.model_info["gemma.context_length"], 8192);
+        assert_eq!(info.context_length(), Some(8192));
+    }
+
+    #[test]
+    fn test_context_length_prefers_num_ctx() {
+        let info: OllamaModelInfo = serde_json::from_str(
+            r#"{"parameters":"num_ctx                        4096\nstop \"<end_of_turn>\"","model_info":{"llama.context_length":131072}}"#,
+        )
+        .unwrap();
+        assert_eq!(info.context_length(), Some(4096));
+
+        let unknown: OllamaModelInfo = serde_json::from_str("{}").unwrap();
+        assert_eq!(unknown.context_length(), None);
+    }
+
+    #[tokio::test]
+    async fn test_delete_missing_model_reports_ollama_error() {
+        let (url, request) = mock_server("404 Not Found", r#"{"error":"model 'nope' not found"}"#.to_string()).await;
+
+        let client = OllamaClient::new(&url).unwrap();
+        let error = client.delete_model("nope").await.unwrap_err();
+
+        assert!(request.await.unwrap().starts_with("DELETE /api/delete"));
+        assert!(error.contains("model 'nope' not found"));
+    }
+
+    #[tokio::test]
+    async fn test_running_models() {
+        let body = r#"{"models":[{"name":"gemma:2b","size":3000000000,"size_vram":2500000000,"expires_at":"2024-06-04T14:38:31Z"}]}"#;
+        let (url, _request) = mock_server("200 OK", body.to_string()).await;
+
+        let client = OllamaClient::new(&url).unwrap();
+        let running = client.running_models().await.unwrap();
+
+        assert_eq!(running[0].size_vram, 2500000000);
+    }
+
+    #[tokio::test]
+    async fn test_pull_model_streams_progress() {
+        let body = [
+            r#"{"status":"pulling manifest"}"#,
+            r#"{"status":"downloading","digest":"sha256:abc","total":200,"completed":50}"#,
+            r#"{"status":"downloading","digest":"sha256:abc","total":200,"completed":200}"#,
+            r#"{"status":"verifying sha256 digest"}"#,
+            r#"{"status":"success"}"#,
+        ]
+        .join("\n");
+        let (url, request) = mock_server("200 OK", body).await;
+
+        let client = OllamaClient::new(&url).unwrap();
+        let mut updates = Vec::new();
+        client.pull_model("gemma:2b", |p| updates.push(p)).await.unwrap();
+
+        assert!(request.await.unwrap().starts_with("POST /api/pull"));
+        assert_eq!(updates.len(), 5);
+        assert_eq!(updates[1].percentage, Some(25.0));
+        assert_eq!(updates[2].percentage, Some(100.0));
+        assert_eq!(updates[4].status, "success");
+    }
+
+    #[tokio::test]
+    async fn test_pull_model_surfaces_stream_error() {
+        let body = [
+            r#"{"status":"pulling manifest"}"#,
+            r#"{"error":"pull model manifest: file does not exist"}"#,
+        ]
+        .join("\n");
+        let (url, _request) = mock_server("200 OK", body).await;
+
+        let client = OllamaClient::new(&url).unwrap();
+        let error = client.pull_model("nope", |_| {}).await.unwrap_err();
+
+        assert!(error.contains("file does not exist"));
+    }
+
+    #[test]
+    fn test_normalize_model_name() {
+        assert_eq!(normalize_model_name("llama3"), "llama3:latest");
+        assert_eq!(normalize_model_name("gemma:2b"), "gemma:2b");
+    }
+}
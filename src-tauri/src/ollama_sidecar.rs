@@ -1,6 +1,114 @@
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
 use std::path::PathBuf;
 use std::process::{Child, Command, Stdio};
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Number of recent Ollama log lines kept for diagnostics
+const LOG_BUFFER_LINES: usize = 500;
+
+/// How often the supervisor checks whether the Ollama process is still alive
+const SUPERVISOR_POLL_SECS: u64 = 5;
+
+/// Restart backoff: first delay, doubled after each failed attempt up to the maximum
+const RESTART_BACKOFF_INITIAL_SECS: u64 = 1;
+const RESTART_BACKOFF_MAX_SECS: u64 = 60;
+
+/// A process that stays up this long is considered healthy again (resets the backoff)
+const STABLE_RUN_SECS: u64 = 60;
+
+/// Timeout for connecting to / reading from the Ollama port when probing it
+const PORT_PROBE_TIMEOUT_MS: u64 = 500;
+
+/// What is currently listening on an Ollama host address
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PortStatus {
+    /// Nothing is listening
+    Free,
+    /// An Ollama server answers `/api/version`
+    Ollama,
+    /// Some other program holds the port
+    Other,
+}
+
+/// Probe `host` ("ip:port") to find out whether an Ollama server already listens there
+pub fn probe_host(host: &str) -> PortStatus {
+    let timeout = Duration::from_millis(PORT_PROBE_TIMEOUT_MS);
+
+    let addr = match host.to_socket_addrs().ok().and_then(|mut addrs| addrs.next()) {
+        Some(addr) => addr,
+        None => return PortStatus::Free,
+    };
+
+    let mut stream = match TcpStream::connect_timeout(&addr, timeout) {
+        Ok(stream) => stream,
+        Err(_) => return PortStatus::Free,
+    };
+
+    let _ = stream.set_read_timeout(Some(timeout));
+    let _ = stream.set_write_timeout(Some(timeout));
+
+    let request = format!(
+        "GET /api/version HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+        host
+    );
+    if stream.write_all(request.as_bytes()).is_err() {
+        return PortStatus::Other;
+    }
+
+    // The response is tiny; read until the server closes or the timeout hits
+    let mut response = Vec::new();
+    let _ = stream.take(64 * 1024).read_to_end(&mut response);
+    let response = String::from_utf8_lossy(&response);
+
+    let status_ok = response
+        .lines()
+        .next()
+        .map(|line| line.starts_with("HTTP/1.") && line.contains(" 200"))
+        .unwrap_or(false);
+
+    if status_ok && response.contains("\"version\"") {
+        PortStatus::Ollama
+    } else {
+        PortStatus::Other
+    }
+}
+
+/// Find a free TCP port on the host's IP and return the new "ip:port" address
+fn find_free_host(host: &str) -> Result<String, String> {
+    let ip = host.rsplit_once(':').map(|(ip, _)| ip).unwrap_or("127.0.0.1");
+
+    let listener = TcpListener::bind((ip, 0))
+        .map_err(|e| format!("Failed to find a free port for Ollama: {}", e))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to read free port: {}", e))?
+        .port();
+
+    Ok(format!("{}:{}", ip, port))
+}
+
+/// Point a local Ollama API base URL (e.g., "http://localhost:11434/v1") at `new_host`
+///
+/// Returns None when the URL refers to a different server (remote host or another
+/// port), in which case the user's setting must be left alone.
+pub fn relocate_api_base_url(api_base_url: &str, old_host: &str, new_host: &str) -> Option<String> {
+    let mut url = reqwest::Url::parse(api_base_url).ok()?;
+
+    let old_port: u16 = old_host.rsplit_once(':')?.1.parse().ok()?;
+    let is_local = matches!(url.host_str(), Some("localhost") | Some("127.0.0.1"));
+    if !is_local || url.port_or_known_default() != Some(old_port) {
+        return None;
+    }
+
+    let (new_ip, new_port) = new_host.rsplit_once(':')?;
+    url.set_host(Some(new_ip)).ok()?;
+    url.set_port(Some(new_port.parse().ok()?)).ok()?;
+
+    Some(url.to_string())
+}
 
 /// GPU acceleration backend type
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -187,6 +295,65 @@ fn detect_amd_gpu() -> Option<String> {
     None
 }
 
+/// Ring buffer of recent log lines from the Ollama process (stdout and stderr)
+#[derive(Clone, Default)]
+pub struct LogBuffer {
+    lines: Arc<Mutex<VecDeque<String>>>,
+}
+
+impl LogBuffer {
+    /// Append a line, dropping the oldest once `LOG_BUFFER_LINES` is reached
+    pub fn push(&self, line: String) {
+        if let Ok(mut lines) = self.lines.lock() {
+            if lines.len() >= LOG_BUFFER_LINES {
+                lines.pop_front();
+            }
+            lines.push_back(line);
+        }
+    }
+
+    /// Snapshot of the buffered lines, oldest first
+    pub fn lines(&self) -> Vec<String> {
+        self.lines
+            .lock()
+            .map(|lines| lines.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Forward every line from a child pipe into the buffer on a background thread
+    fn capture<R: Read + Send + 'static>(&self, stream: R, source: &'static str) {
+        let buffer = self.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stream).lines() {
+                match line {
+                    Ok(line) => {
+                        log::debug!("[ollama {}] {}", source, line);
+                        buffer.push(line);
+                    }
+                    Err(_) => break,
+                }
+            }
+        });
+    }
+}
+
+/// Snapshot of the sidecar's state for the diagnostics command
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SidecarDiagnostics {
+    pub running: bool,
+    pub pid: Option<u32>,
+    pub host: String,
+    pub binary_path: String,
+    pub gpu_info: GpuInfo,
+    /// Seconds since the current process was started
+    pub uptime_secs: Option<u64>,
+    /// Number of automatic restarts since launch
+    pub restart_count: u32,
+    /// Exit status of the last process that died (e.g., "exit status: 1", "signal: 9")
+    pub last_exit_status: Option<String>,
+    pub recent_logs: Vec<String>,
+}
+
 /// Ollama sidecar process manager
 ///
 /// Manages the lifecycle of a bundled Ollama server as a sidecar process.
@@ -198,6 +365,15 @@ pub struct OllamaSidecar {
     models_path: PathBuf,
     host: String,
     gpu_info: GpuInfo,
+    /// Recent stdout/stderr lines of the Ollama process
+    logs: LogBuffer,
+    /// Whether the supervisor should restart the process if it dies (false after `stop`)
+    supervised: bool,
+    started_at: Option<Instant>,
+    restart_count: u32,
+    last_exit_status: Option<String>,
+    /// An Ollama server we found already running on `host` (not ours to stop)
+    adopted: bool,
 }
 
 impl OllamaSidecar {
@@ -242,6 +418,12 @@ impl OllamaSidecar {
             models_path,
             host,
             gpu_info,
+            logs: LogBuffer::default(),
+            supervised: false,
+            started_at: None,
+            restart_count: 0,
+            last_exit_status: None,
+            adopted: false,
         })
     }
 
@@ -250,19 +432,53 @@ impl OllamaSidecar {
         &self.gpu_info
     }
 
+    /// Address the Ollama server listens on (may differ from the requested one after
+    /// a port conflict)
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// Whether an already-running Ollama server was adopted instead of spawning one
+    pub fn is_adopted(&self) -> bool {
+        self.adopted
+    }
+
     /// Start the Ollama server process
     ///
     /// This spawns the Ollama server as a background process with the appropriate
     /// environment variables configured.
     ///
+    /// The configured host is probed first: an Ollama server already answering there
+    /// is adopted (no child is spawned and it is never killed; if it goes away, the
+    /// supervisor starts a sidecar in its place), while a port held by another program
+    /// makes the sidecar move to a free port (see `host()`).
+    ///
     /// # Returns
     /// Ok(()) if the process started successfully, Err with details if failed
     pub fn start(&mut self) -> Result<(), String> {
-        if self.process.is_some() {
+        if self.process.is_some() || self.adopted {
             log::warn!("Ollama sidecar already running");
             return Ok(());
         }
 
+        match probe_host(&self.host) {
+            PortStatus::Free => {}
+            PortStatus::Ollama => {
+                log::info!("✓ Ollama is already running on {}, adopting it", self.host);
+                self.logs.push(format!("[aura] Adopted existing Ollama server on {}", self.host));
+                self.adopted = true;
+                self.supervised = true;
+                return Ok(());
+            }
+            PortStatus::Other => {
+                let new_host = find_free_host(&self.host)?;
+                log::warn!("Port for {} is in use by another program, moving Ollama to {}",
+                           self.host, new_host);
+                self.logs.push(format!("[aura] {} in use, using {}", self.host, new_host));
+                self.host = new_host;
+            }
+        }
+
         log::info!("Starting Ollama sidecar process...");
         log::info!("  Acceleration: {} ({})",
                    self.gpu_info.backend,
@@ -271,19 +487,28 @@ impl OllamaSidecar {
         // Spawn Ollama server process
         // Note: Ollama automatically detects and uses available GPU acceleration
         // based on bundled libraries (CUDA, HIP, Metal)
-        let child = Command::new(&self.binary_path)
+        let mut child = Command::new(&self.binary_path)
             .arg("serve")
             .env("OLLAMA_MODELS", &self.models_path)
             .env("OLLAMA_HOST", &self.host)
             .env("OLLAMA_KEEP_ALIVE", "5m")
             .env("OLLAMA_NUM_PARALLEL", "1") // Limit to 1 request at a time for resource efficiency
-            .stdout(Stdio::null()) // Suppress stdout
-            .stderr(Stdio::null()) // Suppress stderr (change to inherit() for debugging)
+            .stdout(Stdio::piped()) // Captured into the log ring buffer for diagnostics
+            .stderr(Stdio::piped())
             .spawn()
             .map_err(|e| format!("Failed to spawn Ollama process: {}", e))?;
 
+        if let Some(stdout) = child.stdout.take() {
+            self.logs.capture(stdout, "stdout");
+        }
+        if let Some(stderr) = child.stderr.take() {
+            self.logs.capture(stderr, "stderr");
+        }
+
         let pid = child.id();
         self.process = Some(child);
+        self.supervised = true;
+        self.started_at = Some(Instant::now());
 
         log::info!("✓ Ollama sidecar started successfully (PID: {})", pid);
         log::info!("  Waiting for server to become ready...");
@@ -344,12 +569,29 @@ impl OllamaSidecar {
     }
 
     /// Check if the Ollama server process is running
+    ///
+    /// An exited process is reaped and its exit status kept for diagnostics; an
+    /// adopted server that stopped answering is released, so `restart` spawns our own.
     pub fn is_running(&mut self) -> bool {
+        if self.adopted {
+            if probe_host(&self.host) == PortStatus::Ollama {
+                return true;
+            }
+            log::warn!("Adopted Ollama server on {} is gone", self.host);
+            self.logs.push(format!("[aura] Adopted Ollama server on {} stopped answering", self.host));
+            self.adopted = false;
+            return false;
+        }
+
         if let Some(ref mut child) = self.process {
             match child.try_wait() {
                 Ok(None) => true, // Still running
                 Ok(Some(status)) => {
                     log::warn!("Ollama process exited with status: {}", status);
+                    self.last_exit_status = Some(status.to_string());
+                    self.logs.push(format!("[aura] Ollama process exited ({})", status));
+                    self.process = None;
+                    self.started_at = None;
                     false
                 }
                 Err(e) => {
@@ -362,10 +604,61 @@ impl OllamaSidecar {
         }
     }
 
+    /// Restart the Ollama process (used by the supervisor after a crash)
+    pub fn restart(&mut self) -> Result<(), String> {
+        log::info!("Restarting Ollama sidecar (restart #{})", self.restart_count + 1);
+
+        if self.process.is_some() {
+            self.stop()?;
+        }
+
+        self.restart_count += 1;
+        self.logs.push(format!("[aura] Restarting Ollama (restart #{})", self.restart_count));
+        self.start()
+    }
+
+    /// Whether the supervisor should keep this process alive
+    pub fn is_supervised(&self) -> bool {
+        self.supervised
+    }
+
+    /// Seconds the current process has been running
+    pub fn uptime(&self) -> Option<Duration> {
+        self.started_at.map(|t| t.elapsed())
+    }
+
+    /// Collect process state and recent log lines for troubleshooting
+    pub fn diagnostics(&mut self) -> SidecarDiagnostics {
+        let running = self.is_running();
+
+        SidecarDiagnostics {
+            running,
+            pid: self.process.as_ref().map(|child| child.id()),
+            host: self.host.clone(),
+            binary_path: self.binary_path.to_string_lossy().to_string(),
+            gpu_info: self.gpu_info.clone(),
+            uptime_secs: self.uptime().map(|d| d.as_secs()),
+            restart_count: self.restart_count,
+            last_exit_status: self.last_exit_status.clone(),
+            recent_logs: self.logs.lines(),
+        }
+    }
+
     /// Gracefully stop the Ollama server
     ///
     /// This sends a termination signal to the Ollama process and waits for it to exit.
     pub fn stop(&mut self) -> Result<(), String> {
+        // A deliberate stop must not be undone by the supervisor
+        self.supervised = false;
+        self.started_at = None;
+
+        if self.adopted {
+            // Not our process: leave the user's Ollama server running
+            log::info!("Releasing adopted Ollama server on {} (left running)", self.host);
+            self.adopted = false;
+            return Ok(());
+        }
+
         if let Some(mut child) = self.process.take() {
             log::info!("Stopping Ollama sidecar process...");
 
@@ -418,3 +711,253 @@ impl Drop for OllamaSidecar {
         }
     }
 }
+
+/// Keep the Ollama sidecar alive
+///
+/// Spawns a background thread that polls `is_running` every `SUPERVISOR_POLL_SECS`.
+/// When the process has died it is restarted with exponential backoff (reset once a
+/// process stays up for `STABLE_RUN_SECS`). `on_status` is called with the sidecar's
+/// host whenever the process goes down or comes back up (a restart may have moved it
+/// to another port). A sidecar stopped via `stop()` is left alone.
+pub fn spawn_supervisor<F>(sidecar: Arc<Mutex<OllamaSidecar>>, on_status: F)
+where
+    F: Fn(bool, &str) + Send + 'static,
+{
+    std::thread::spawn(move || {
+        log::info!("Ollama supervisor started");
+
+        let mut was_running = true;
+        let mut backoff = Duration::from_secs(RESTART_BACKOFF_INITIAL_SECS);
+
+        loop {
+            std::thread::sleep(Duration::from_secs(SUPERVISOR_POLL_SECS));
+
+            let (running, supervised, uptime, host) = match sidecar.lock() {
+                Ok(mut sidecar) => (
+                    sidecar.is_running(),
+                    sidecar.is_supervised(),
+                    sidecar.uptime(),
+                    sidecar.host().to_string(),
+                ),
+                Err(_) => {
+                    log::error!("Ollama sidecar lock poisoned, stopping supervisor");
+                    return;
+                }
+            };
+
+            if running {
+                if !was_running {
+                    log::info!("✓ Ollama sidecar is running again");
+                    on_status(true, &host);
+                    was_running = true;
+                }
+                if uptime.map(|u| u >= Duration::from_secs(STABLE_RUN_SECS)).unwrap_or(false) {
+                    backoff = Duration::from_secs(RESTART_BACKOFF_INITIAL_SECS);
+                }
+                continue;
+            }
+
+            if !supervised {
+                continue;
+            }
+
+            if was_running {
+                log::error!("✗ Ollama sidecar is not running");
+                on_status(false, &host);
+                was_running = false;
+            }
+
+            log::info!("Restarting Ollama sidecar in {}s", backoff.as_secs());
+            std::thread::sleep(backoff);
+
+            let result = match sidecar.lock() {
+                Ok(mut sidecar) => {
+                    // Skip if stopped deliberately or already back up during the backoff
+                    if sidecar.is_supervised() && !sidecar.is_running() {
+                        sidecar.restart()
+                    } else {
+                        Ok(())
+                    }
+                }
+                Err(_) => {
+                    log::error!("Ollama sidecar lock poisoned, stopping supervisor");
+                    return;
+                }
+            };
+
+            if let Err(e) = result {
+                log::error!("✗ Failed to restart Ollama sidecar: {}", e);
+            }
+
+            backoff = (backoff * 2).min(Duration::from_secs(RESTART_BACKOFF_MAX_SECS));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// Serve `response` to every connection on a local port and return its address
+    fn serve_forever(response: &'static str) -> String {
+        serve_until_stopped(response).0
+    }
+
+    /// Like `serve_forever`, until the returned function is called (which closes the port)
+    fn serve_until_stopped(response: &'static str) -> (String, impl FnOnce()) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let host = listener.local_addr().unwrap().to_string();
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let stop_flag = stopped.clone();
+        let server = std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = match stream {
+                    Ok(stream) => stream,
+                    Err(_) => break,
+                };
+                if stop_flag.load(Ordering::SeqCst) {
+                    break;
+                }
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf);
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        let stop_host = host.clone();
+        let stop = move || {
+            stopped.store(true, Ordering::SeqCst);
+            // Wake the accept loop so it sees the flag
+            let _ = TcpStream::connect(&stop_host);
+            server.join().unwrap();
+        };
+        (host, stop)
+    }
+
+    /// Sidecar whose "binary" is a plain (non-executable) file, so spawning always fails
+    fn test_sidecar(dir: &std::path::Path, host: &str) -> OllamaSidecar {
+        let binary = dir.join("ollama");
+        std::fs::write(&binary, b"").unwrap();
+        OllamaSidecar::new(binary, dir.join("models"), host.to_string()).unwrap()
+    }
+
+    const OLLAMA_VERSION_RESPONSE: &str =
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 20\r\n\r\n{\"version\":\"0.5.7\"}";
+
+    #[test]
+    fn test_probe_host_detects_ollama() {
+        let host = serve_forever(OLLAMA_VERSION_RESPONSE);
+        assert_eq!(probe_host(&host), PortStatus::Ollama);
+    }
+
+    #[test]
+    fn test_probe_host_detects_other_program() {
+        let host = serve_forever("HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n");
+        assert_eq!(probe_host(&host), PortStatus::Other);
+    }
+
+    #[test]
+    fn test_probe_host_free_port() {
+        let host = find_free_host("127.0.0.1:11434").unwrap();
+        assert_eq!(probe_host(&host), PortStatus::Free);
+    }
+
+    #[test]
+    fn test_start_adopts_running_ollama() {
+        let host = serve_forever(OLLAMA_VERSION_RESPONSE);
+        let dir = tempfile::tempdir().unwrap();
+        let mut sidecar = test_sidecar(dir.path(), &host);
+
+        // Would fail to spawn the fake binary if it did not adopt
+        sidecar.start().unwrap();
+
+        assert!(sidecar.is_adopted());
+        assert!(sidecar.is_running());
+        assert!(sidecar.is_supervised());
+        assert_eq!(sidecar.host(), host);
+
+        // Dropping must not affect the adopted server
+        sidecar.stop().unwrap();
+        assert_eq!(probe_host(&host), PortStatus::Ollama);
+    }
+
+    #[test]
+    fn test_restart_replaces_adopted_server_that_died() {
+        let (host, kill_server) = serve_until_stopped(OLLAMA_VERSION_RESPONSE);
+        let dir = tempfile::tempdir().unwrap();
+        let mut sidecar = test_sidecar(dir.path(), &host);
+        sidecar.start().unwrap();
+        assert!(sidecar.is_adopted());
+
+        kill_server();
+        assert!(!sidecar.is_running());
+        assert!(!sidecar.is_adopted());
+        assert!(sidecar.is_supervised());
+
+        // Now tries to spawn its own server (the fake binary can't run)
+        assert!(sidecar.restart().unwrap_err().starts_with("Failed to spawn Ollama process"));
+    }
+
+    #[test]
+    fn test_start_moves_off_conflicting_port() {
+        let host = serve_forever("HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nhi");
+        let dir = tempfile::tempdir().unwrap();
+        let mut sidecar = test_sidecar(dir.path(), &host);
+
+        // Spawning the fake binary fails, but only after a new port was chosen
+        assert!(sidecar.start().is_err());
+        assert!(!sidecar.is_adopted());
+        assert_ne!(sidecar.host(), host);
+    }
+
+    #[test]
+    fn test_relocate_api_base_url() {
+        assert_eq!(
+            relocate_api_base_url("http://localhost:11434/v1", "127.0.0.1:11434", "127.0.0.1:40123"),
+            Some("http://127.0.0.1:40123/v1".to_string())
+        );
+        // Remote servers and other ports are left alone
+        assert_eq!(
+            relocate_api_base_url("http://192.168.1.20:11434/v1", "127.0.0.1:11434", "127.0.0.1:40123"),
+            None
+        );
+        assert_eq!(
+            relocate_api_base_url("http://localhost:8080/v1", "127.0.0.1:11434", "127.0.0.1:40123"),
+            None
+        );
+        // Back to the default port once it is free again
+        assert_eq!(
+            relocate_api_base_url("http://127.0.0.1:40123/v1", "127.0.0.1:40123", "127.0.0.1:11434"),
+            Some("http://127.0.0.1:11434/v1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_log_buffer_keeps_most_recent_lines() {
+        let buffer = LogBuffer::default();
+        for i in 0..LOG_BUFFER_LINES + 10 {
+            buffer.push(format!("line {}", i));
+        }
+
+        let lines = buffer.lines();
+        assert_eq!(lines.len(), LOG_BUFFER_LINES);
+        assert_eq!(lines[0], "line 10");
+        assert_eq!(lines.last().unwrap(), &format!("line {}", LOG_BUFFER_LINES + 9));
+    }
+
+    #[test]
+    fn test_log_buffer_captures_pipe_output() {
+        let buffer = LogBuffer::default();
+        buffer.capture(std::io::Cursor::new(b"first\nsecond\n".to_vec()), "test");
+
+        // The capture thread finishes as soon as the reader is exhausted
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while buffer.lines().len() < 2 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        assert_eq!(buffer.lines(), vec!["first".to_string(), "second".to_string()]);
+    }
+}
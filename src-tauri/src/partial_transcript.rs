@@ -0,0 +1,127 @@
+//! Live Partial Transcripts
+//!
+//! While the user is still speaking, the voice pipeline re-runs Whisper on the
+//! growing recording every few hundred milliseconds. Each pass yields a new
+//! hypothesis for the whole utterance, and the tail of it changes from pass to pass
+//! as more audio arrives.
+//!
+//! `PartialTranscriptTracker` turns these hypotheses into a stable caption: words
+//! that two consecutive passes agree on become the stable prefix (which never
+//! shrinks), the rest is reported as tentative.
+
+use serde::Serialize;
+
+/// Payload of the `partial_transcription` event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PartialTranscription {
+    /// Words confirmed by consecutive passes (only ever grows during an utterance)
+    pub stable: String,
+    /// Latest guess for the rest of the utterance (may still change)
+    pub tentative: String,
+}
+
+/// Tracks partial hypotheses for one utterance
+#[derive(Debug, Default)]
+pub struct PartialTranscriptTracker {
+    previous: Vec<String>,
+    stable: Vec<String>,
+    last_emitted: Option<PartialTranscription>,
+}
+
+impl PartialTranscriptTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed the hypothesis from the latest Whisper pass
+    ///
+    /// Returns the caption to emit, or None if it hasn't changed since the last call.
+    pub fn update(&mut self, hypothesis: &str) -> Option<PartialTranscription> {
+        let words: Vec<String> = hypothesis.split_whitespace().map(str::to_string).collect();
+
+        // Extend the stable prefix with the words this pass agrees on with the last
+        // one, as long as it doesn't contradict what was already confirmed
+        let agreed = common_prefix_len(&self.previous, &words);
+        if agreed > self.stable.len() && common_prefix_len(&self.stable, &words) == self.stable.len() {
+            self.stable = words[..agreed].to_vec();
+        }
+
+        let tentative = if common_prefix_len(&self.stable, &words) == self.stable.len() {
+            words[self.stable.len()..].join(" ")
+        } else {
+            // This pass contradicts confirmed words; keep them and wait for the next pass
+            String::new()
+        };
+        self.previous = words;
+
+        let caption = PartialTranscription {
+            stable: self.stable.join(" "),
+            tentative,
+        };
+        if self.last_emitted.as_ref() == Some(&caption) {
+            return None;
+        }
+
+        self.last_emitted = Some(caption.clone());
+        Some(caption)
+    }
+}
+
+/// Number of leading words two hypotheses share
+///
+/// Case and punctuation are ignored: Whisper often revises "hello" to "Hello," once
+/// the sentence continues.
+fn common_prefix_len(a: &[String], b: &[String]) -> usize {
+    a.iter()
+        .zip(b)
+        .take_while(|(a, b)| normalize_word(a) == normalize_word(b))
+        .count()
+}
+
+fn normalize_word(word: &str) -> String {
+    word.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stable_prefix_grows_with_agreement() {
+        let mut tracker = PartialTranscriptTracker::new();
+
+        let first = tracker.update("turn on").unwrap();
+        assert_eq!(first.stable, "");
+        assert_eq!(first.tentative, "turn on");
+
+        let second = tracker.update("Turn on the kitchen").unwrap();
+        assert_eq!(second.stable, "Turn on");
+        assert_eq!(second.tentative, "the kitchen");
+
+        let third = tracker.update("Turn on the kitchen lights.").unwrap();
+        assert_eq!(third.stable, "Turn on the kitchen");
+        assert_eq!(third.tentative, "lights.");
+
+        // Same hypothesis again only confirms the last word
+        let fourth = tracker.update("Turn on the kitchen lights.").unwrap();
+        assert_eq!(fourth.stable, "Turn on the kitchen lights.");
+        assert_eq!(fourth.tentative, "");
+
+        // Nothing changed: nothing to emit
+        assert!(tracker.update("Turn on the kitchen lights.").is_none());
+    }
+
+    #[test]
+    fn test_stable_prefix_is_never_retracted() {
+        let mut tracker = PartialTranscriptTracker::new();
+        tracker.update("play some jazz");
+        tracker.update("play some jazz music");
+
+        let revised = tracker.update("play sam jazz music").unwrap();
+        assert_eq!(revised.stable, "play some jazz");
+        assert_eq!(revised.tentative, "");
+    }
+}
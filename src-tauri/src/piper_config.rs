@@ -0,0 +1,253 @@
+//! Piper Voice Configuration
+//!
+//! Every Piper voice ships with a `.onnx.json` config next to the model. It holds the
+//! output sample rate (16 kHz or 22.05 kHz depending on the voice), the speakers of
+//! multi-speaker voices (`speaker_id_map`), the voice's default inference settings and
+//! its language and quality (shown in the voice catalog).
+//!
+//! `SynthesisOptions` are a user's overrides (see `user_tts_preferences`); resolved
+//! against the config they become the `SynthesisParams` passed to piper.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Piper's defaults for configs without an `inference` section
+const DEFAULT_LENGTH_SCALE: f32 = 1.0;
+const DEFAULT_NOISE_SCALE: f32 = 0.667;
+/// Not part of the config; piper's own default
+const DEFAULT_SENTENCE_SILENCE: f32 = 0.2;
+
+/// Accepted ranges for user overrides
+pub const LENGTH_SCALE_RANGE: (f32, f32) = (0.25, 4.0);
+pub const NOISE_SCALE_RANGE: (f32, f32) = (0.0, 2.0);
+pub const SENTENCE_SILENCE_RANGE: (f32, f32) = (0.0, 5.0);
+
+/// The parts of a voice config Aura uses
+#[derive(Debug, Clone, Serialize)]
+pub struct VoiceConfig {
+    /// Language code, e.g. "en_US"
+    pub language: Option<String>,
+    /// "x_low", "low", "medium" or "high"
+    pub quality: Option<String>,
+    pub sample_rate: u32,
+    pub num_speakers: u32,
+    /// Speaker name -> speaker id (empty for single-speaker voices)
+    pub speaker_id_map: BTreeMap<String, i64>,
+    pub length_scale: f32,
+    pub noise_scale: f32,
+    pub sentence_silence: f32,
+}
+
+#[derive(Deserialize)]
+struct RawConfig {
+    audio: RawAudio,
+    #[serde(default)]
+    num_speakers: Option<u32>,
+    #[serde(default)]
+    speaker_id_map: BTreeMap<String, i64>,
+    #[serde(default)]
+    inference: RawInference,
+    #[serde(default)]
+    language: Option<RawLanguage>,
+}
+
+#[derive(Deserialize)]
+struct RawAudio {
+    sample_rate: u32,
+    #[serde(default)]
+    quality: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawLanguage {
+    code: String,
+}
+
+#[derive(Deserialize, Default)]
+struct RawInference {
+    length_scale: Option<f32>,
+    noise_scale: Option<f32>,
+}
+
+impl VoiceConfig {
+    /// Read the config of a voice model (`<model>.onnx.json`)
+    pub fn load(config_path: &Path) -> Result<Self, String> {
+        let json = std::fs::read_to_string(config_path)
+            .map_err(|e| format!("Failed to read voice config {:?}: {}", config_path, e))?;
+        Self::parse(&json).map_err(|e| format!("Invalid voice config {:?}: {}", config_path, e))
+    }
+
+    fn parse(json: &str) -> Result<Self, String> {
+        let raw: RawConfig = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        if raw.audio.sample_rate == 0 {
+            return Err("sample_rate is 0".to_string());
+        }
+
+        Ok(Self {
+            language: raw.language.map(|language| language.code),
+            quality: raw.audio.quality,
+            sample_rate: raw.audio.sample_rate,
+            num_speakers: raw.num_speakers.unwrap_or(1).max(raw.speaker_id_map.len() as u32),
+            speaker_id_map: raw.speaker_id_map,
+            length_scale: raw.inference.length_scale.unwrap_or(DEFAULT_LENGTH_SCALE),
+            noise_scale: raw.inference.noise_scale.unwrap_or(DEFAULT_NOISE_SCALE),
+            sentence_silence: DEFAULT_SENTENCE_SILENCE,
+        })
+    }
+
+    /// Apply a user's overrides to the voice defaults
+    ///
+    /// An unknown speaker name falls back to the default speaker (the user may have
+    /// picked it for a different voice).
+    pub fn resolve(&self, options: &SynthesisOptions) -> SynthesisParams {
+        let speaker_id = options.speaker.as_ref().and_then(|speaker| {
+            let id = self.speaker_id_map.get(speaker).copied();
+            if id.is_none() {
+                log::warn!("Speaker '{}' not in this voice, using the default speaker", speaker);
+            }
+            id
+        });
+
+        SynthesisParams {
+            speaker_id,
+            length_scale: options.length_scale.unwrap_or(self.length_scale),
+            noise_scale: options.noise_scale.unwrap_or(self.noise_scale),
+            sentence_silence: options.sentence_silence.unwrap_or(self.sentence_silence),
+        }
+    }
+}
+
+/// A user's synthesis overrides (None = voice default)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SynthesisOptions {
+    pub speaker: Option<String>,
+    /// Speech rate: larger is slower
+    pub length_scale: Option<f32>,
+    /// Voice variability
+    pub noise_scale: Option<f32>,
+    /// Pause after each sentence, in seconds
+    pub sentence_silence: Option<f32>,
+}
+
+impl SynthesisOptions {
+    /// Check the overrides are in range
+    pub fn validate(&self) -> Result<(), String> {
+        let checks = [
+            ("length_scale", self.length_scale, LENGTH_SCALE_RANGE),
+            ("noise_scale", self.noise_scale, NOISE_SCALE_RANGE),
+            ("sentence_silence", self.sentence_silence, SENTENCE_SILENCE_RANGE),
+        ];
+        for (name, value, (min, max)) in checks {
+            if let Some(value) = value {
+                if !(min..=max).contains(&value) {
+                    return Err(format!("{} must be between {} and {} (got {})", name, min, max, value));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parameters for one piper invocation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SynthesisParams {
+    pub speaker_id: Option<i64>,
+    pub length_scale: f32,
+    pub noise_scale: f32,
+    pub sentence_silence: f32,
+}
+
+impl SynthesisParams {
+    /// Command line arguments for everything except the speaker
+    ///
+    /// The speaker can change per request; these need a new piper process.
+    pub fn process_args(&self) -> Vec<String> {
+        vec![
+            "--length_scale".to_string(),
+            self.length_scale.to_string(),
+            "--noise_scale".to_string(),
+            self.noise_scale.to_string(),
+            "--sentence_silence".to_string(),
+            self.sentence_silence.to_string(),
+        ]
+    }
+
+    /// Whether a piper process started with `other` can synthesize with these
+    pub fn same_process(&self, other: &SynthesisParams) -> bool {
+        self.length_scale == other.length_scale
+            && self.noise_scale == other.noise_scale
+            && self.sentence_silence == other.sentence_silence
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MULTI_SPEAKER: &str = r#"{
+        "audio": {"sample_rate": 16000, "quality": "low"},
+        "espeak": {"voice": "en-us"},
+        "language": {"code": "en_GB", "family": "en", "region": "GB"},
+        "inference": {"noise_scale": 0.5, "length_scale": 1.1, "noise_w": 0.8},
+        "num_speakers": 3,
+        "speaker_id_map": {"p225": 0, "p226": 1, "p227": 2}
+    }"#;
+
+    #[test]
+    fn test_parse_config() {
+        let config = VoiceConfig::parse(MULTI_SPEAKER).unwrap();
+        assert_eq!(config.sample_rate, 16000);
+        assert_eq!(config.language.as_deref(), Some("en_GB"));
+        assert_eq!(config.quality.as_deref(), Some("low"));
+        assert_eq!(config.num_speakers, 3);
+        assert_eq!(config.speaker_id_map.get("p226"), Some(&1));
+        assert_eq!(config.length_scale, 1.1);
+        assert_eq!(config.noise_scale, 0.5);
+
+        let single = VoiceConfig::parse(r#"{"audio": {"sample_rate": 22050}}"#).unwrap();
+        assert_eq!(single.num_speakers, 1);
+        assert_eq!(single.language, None);
+        assert!(single.speaker_id_map.is_empty());
+        assert_eq!(single.length_scale, DEFAULT_LENGTH_SCALE);
+        assert_eq!(single.sentence_silence, DEFAULT_SENTENCE_SILENCE);
+
+        assert!(VoiceConfig::parse(r#"{"num_speakers": 1}"#).is_err());
+    }
+
+    #[test]
+    fn test_resolve_options() {
+        let config = VoiceConfig::parse(MULTI_SPEAKER).unwrap();
+
+        let defaults = config.resolve(&SynthesisOptions::default());
+        assert_eq!(defaults.speaker_id, None);
+        assert_eq!(defaults.length_scale, 1.1);
+
+        let options = SynthesisOptions {
+            speaker: Some("p227".to_string()),
+            length_scale: Some(0.8),
+            noise_scale: None,
+            sentence_silence: Some(0.5),
+        };
+        let params = config.resolve(&options);
+        assert_eq!(params.speaker_id, Some(2));
+        assert_eq!((params.length_scale, params.noise_scale, params.sentence_silence), (0.8, 0.5, 0.5));
+        assert!(!params.same_process(&defaults));
+
+        let unknown = SynthesisOptions {
+            speaker: Some("nobody".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(config.resolve(&unknown).speaker_id, None);
+    }
+
+    #[test]
+    fn test_validate_options() {
+        assert!(SynthesisOptions::default().validate().is_ok());
+        let too_slow = SynthesisOptions {
+            length_scale: Some(10.0),
+            ..Default::default()
+        };
+        assert!(too_slow.validate().unwrap_err().starts_with("length_scale"));
+    }
+}
@@ -0,0 +1,268 @@
+//! Persistent Piper Worker
+//!
+//! Spawning `piper` for every sentence reloads the ONNX voice each time, which costs
+//! hundreds of milliseconds before any audio appears. `PiperWorker` keeps one piper
+//! process running in JSON-input mode instead:
+//! - Each utterance is one JSON line on stdin: `{"text": ..., "output_file": ...}`,
+//!   plus `speaker_id` for multi-speaker voices
+//! - Piper writes the utterance's audio to that file and prints its path on stdout
+//!   when done; the path line frames the utterance
+//! - stderr (piper's log) is drained on a background thread
+//!
+//! The audio goes through a scratch WAV file per utterance rather than piper's raw
+//! output mode on purpose. With `--output_raw` piper writes every utterance's samples
+//! back to back on stdout with nothing in between; the only sign an utterance is done
+//! is its "Real-time factor" log line on stderr, a separate pipe with no ordering
+//! against stdout, so the tail of one sentence could be played as the start of the
+//! next. The path line is in-band on a single stream, and reading a few hundred KB of
+//! WAV back from the temp directory is negligible next to synthesis itself.
+//!
+//! Speech rate, noise and sentence silence are command line arguments, so a worker
+//! only serves requests with the settings it was started with.
+//!
+//! The worker doesn't restart itself: `TextToSpeech` drops a worker that exited or
+//! stopped responding, starts a new one for the next sentence, and falls back to
+//! one-shot synthesis if restarts keep failing.
+
+use crate::piper_config::SynthesisParams;
+use serde_json::json;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+/// Longest wait for one utterance (includes loading the voice on first use)
+const SYNTHESIS_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Distinguishes the scratch directories of workers in the same process
+static NEXT_WORKER: AtomicU64 = AtomicU64::new(1);
+
+/// A long-lived piper process
+pub struct PiperWorker {
+    child: Child,
+    stdin: ChildStdin,
+    /// Lines printed on stdout (finished output files)
+    finished: Receiver<String>,
+    /// Scratch directory for utterance files
+    output_dir: PathBuf,
+    next_utterance: u64,
+    params: SynthesisParams,
+}
+
+impl PiperWorker {
+    /// Start piper with the given voice and settings
+    ///
+    /// Returns once the process is running; the voice loads in the background and
+    /// the first `synthesize` call waits for it.
+    pub fn spawn(
+        piper_path: &Path,
+        model_path: &Path,
+        espeak_data_path: &Path,
+        params: SynthesisParams,
+    ) -> Result<Self, String> {
+        let output_dir = std::env::temp_dir().join(format!(
+            "nivora-aura-piper-{}-{}",
+            std::process::id(),
+            NEXT_WORKER.fetch_add(1, Ordering::SeqCst)
+        ));
+        std::fs::create_dir_all(&output_dir)
+            .map_err(|e| format!("Failed to create piper output directory: {}", e))?;
+
+        let mut cmd = Command::new(piper_path);
+        cmd.arg("--model")
+            .arg(model_path)
+            .arg("--json-input")
+            .arg("--output_dir")
+            .arg(&output_dir)
+            .arg("--espeak_data")
+            .arg(espeak_data_path)
+            .args(params.process_args())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        // Add library path for bundled .so files
+        if let Some(piper_dir) = piper_path.parent() {
+            cmd.env("LD_LIBRARY_PATH", piper_dir);
+        }
+
+        let mut child = match cmd.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                let _ = std::fs::remove_dir_all(&output_dir);
+                return Err(format!("Failed to spawn piper worker: {}", e));
+            }
+        };
+
+        let stdin = child.stdin.take().ok_or("Failed to open piper worker stdin")?;
+        let stdout = child.stdout.take().ok_or("Failed to open piper worker stdout")?;
+        let stderr = child.stderr.take().ok_or("Failed to open piper worker stderr")?;
+
+        // stdout is read on a thread so a hung piper can be timed out
+        let (finished_tx, finished) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if finished_tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        // Piper blocks if its log isn't read
+        std::thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                log::debug!("piper: {}", line);
+            }
+        });
+
+        log::info!("Started piper worker (pid {})", child.id());
+
+        Ok(Self {
+            child,
+            stdin,
+            finished,
+            output_dir,
+            next_utterance: 1,
+            params,
+        })
+    }
+
+    /// Whether this worker was started with the settings `params` need
+    pub fn serves(&self, params: &SynthesisParams) -> bool {
+        self.params.same_process(params)
+    }
+
+    /// Whether the process is still alive
+    pub fn is_running(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    /// Synthesize one utterance, returning its 16-bit PCM samples
+    ///
+    /// An error means the worker is unusable (exited, hung or produced no audio) and
+    /// should be dropped.
+    pub fn synthesize(&mut self, text: &str, speaker_id: Option<i64>) -> Result<Vec<i16>, String> {
+        let output_file = self.output_dir.join(format!("utterance-{}.wav", self.next_utterance));
+        self.next_utterance += 1;
+
+        let mut request = json!({
+            "text": text,
+            "output_file": output_file.to_string_lossy(),
+        });
+        if let Some(speaker_id) = speaker_id {
+            request["speaker_id"] = json!(speaker_id);
+        }
+        writeln!(self.stdin, "{}", request)
+            .and_then(|_| self.stdin.flush())
+            .map_err(|e| format!("Failed to send text to piper worker: {}", e))?;
+
+        // Wait for our file; anything else is left over from an earlier request
+        loop {
+            let line = match self.finished.recv_timeout(SYNTHESIS_TIMEOUT) {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Timeout) => {
+                    return Err(format!("Piper worker timed out after {:?}", SYNTHESIS_TIMEOUT))
+                }
+                Err(RecvTimeoutError::Disconnected) => return Err("Piper worker exited".to_string()),
+            };
+
+            let path = Path::new(line.trim());
+            if path == output_file {
+                break;
+            }
+            log::debug!("Ignoring stale piper output: {}", line);
+            if path.starts_with(&self.output_dir) {
+                let _ = std::fs::remove_file(path);
+            }
+        }
+
+        let samples = read_samples(&output_file);
+        let _ = std::fs::remove_file(&output_file);
+        let samples = samples?;
+        if samples.is_empty() {
+            return Err("Piper produced no audio (empty output)".to_string());
+        }
+        Ok(samples)
+    }
+}
+
+impl Drop for PiperWorker {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = std::fs::remove_dir_all(&self.output_dir);
+    }
+}
+
+fn read_samples(path: &Path) -> Result<Vec<i16>, String> {
+    let reader = hound::WavReader::open(path).map_err(|e| format!("Failed to read piper output: {}", e))?;
+    reader
+        .into_samples::<i16>()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read piper output: {}", e))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    /// A stand-in for piper: answers each request with a copy of `template`, and
+    /// exits on the text "crash"
+    fn fake_piper(dir: &Path, template: &Path) -> PathBuf {
+        let script = dir.join("piper");
+        std::fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\n\
+                 while read -r line; do\n\
+                 case \"$line\" in *'\"text\":\"crash\"'*) exit 1;; esac\n\
+                 out=$(printf '%s' \"$line\" | sed 's/.*\"output_file\":\"\\([^\"]*\\)\".*/\\1/')\n\
+                 cp '{}' \"$out\"\n\
+                 echo \"$out\"\n\
+                 done\n",
+                template.display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        script
+    }
+
+    #[test]
+    fn test_worker_frames_utterances_and_reports_crash() {
+        let dir = tempfile::tempdir().unwrap();
+        let template = dir.path().join("template.wav");
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 22050,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&template, spec).unwrap();
+        for sample in [1i16, -2, 3] {
+            writer.write_sample(sample).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let params = SynthesisParams {
+            speaker_id: None,
+            length_scale: 1.0,
+            noise_scale: 0.667,
+            sentence_silence: 0.2,
+        };
+        let piper = fake_piper(dir.path(), &template);
+        let mut worker = PiperWorker::spawn(&piper, Path::new("voice.onnx"), dir.path(), params).unwrap();
+        let output_dir = worker.output_dir.clone();
+
+        assert_eq!(worker.synthesize("Hello there.", None).unwrap(), vec![1, -2, 3]);
+        assert_eq!(worker.synthesize("Second \"quoted\"\nline.", Some(2)).unwrap(), vec![1, -2, 3]);
+        assert!(worker.is_running());
+        assert!(!worker.serves(&SynthesisParams { length_scale: 1.5, ..params }));
+
+        assert_eq!(worker.synthesize("crash", None).unwrap_err(), "Piper worker exited");
+        drop(worker);
+        assert!(!output_dir.exists());
+    }
+}
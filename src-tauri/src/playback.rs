@@ -0,0 +1,601 @@
+//! TTS Playback Controller
+//!
+//! Owns the audio output stream and plays spoken answers one after another. Every
+//! `speak_text` call (or streamed answer) becomes an utterance in the queue, and its
+//! sentences are appended while it is still being synthesized, so the TTS engine is
+//! free again as soon as synthesis is done.
+//!
+//! The controller:
+//! - Stops everything, pauses/resumes, or skips the current utterance
+//! - Reports the queue length (`tts_get_status`)
+//! - Emits `tts_playback_started` when the first audio starts playing and
+//!   `tts_playback_finished` when the queue has drained (or was stopped)
+//! - Reports playback to the `PlaybackMonitor`, which moves the voice pipeline in and
+//!   out of `VoiceState::Speaking` and lets barge-in stop the audio. An utterance
+//!   queued while the answer is still being generated only counts once its audio
+//!   plays, and the echo envelope is frozen while paused
+
+use crate::barge_in::PlaybackMonitor;
+use rodio::mixer::Mixer;
+use rodio::{Sink, Source};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::watch;
+
+/// How often finished utterances are noticed and the next one started (only while
+/// something is queued)
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Receives `tts_playback_started` / `tts_playback_finished` with the current status
+type PlaybackEvents = Box<dyn Fn(&str, PlaybackStatus) + Send + Sync>;
+
+/// Playback state reported to the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct PlaybackStatus {
+    pub playing: bool,
+    pub paused: bool,
+    /// Utterances waiting or playing, including the current one
+    pub queue_length: usize,
+}
+
+/// One queued answer, played through its own sink
+struct QueuedUtterance {
+    id: u64,
+    sink: Sink,
+    /// All sentences have been appended
+    complete: AtomicBool,
+    /// Skipped or stopped
+    cancelled: AtomicBool,
+    /// Appended audio (samples, sample rate) not yet reported to the monitor
+    unreported: Mutex<Vec<(Vec<f32>, u32)>>,
+    done: watch::Sender<bool>,
+}
+
+impl QueuedUtterance {
+    fn is_done(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst) || (self.complete.load(Ordering::SeqCst) && self.sink.empty())
+    }
+
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        self.sink.stop();
+    }
+
+    fn lock_unreported(&self) -> std::sync::MutexGuard<'_, Vec<(Vec<f32>, u32)>> {
+        self.unreported.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Utterance queue, separate from the output device and the app so it can be driven
+/// by any sinks
+struct PlaybackQueue {
+    monitor: PlaybackMonitor,
+    events: PlaybackEvents,
+    utterances: Mutex<VecDeque<Arc<QueuedUtterance>>>,
+    /// Signalled when an utterance is queued
+    queued: Condvar,
+    paused: AtomicBool,
+    /// Whether playback was reported to the monitor and `tts_playback_started` sent,
+    /// without a matching finish
+    playing: AtomicBool,
+    next_id: AtomicU64,
+}
+
+/// Queue of spoken answers on the default output device
+///
+/// Cloning shares the same queue.
+#[derive(Clone)]
+pub struct PlaybackController {
+    queue: Arc<PlaybackQueue>,
+    mixer: Arc<Mutex<Option<Mixer>>>,
+}
+
+impl PlaybackController {
+    /// Create the controller; the output device is opened on first use
+    pub fn new(app_handle: AppHandle, monitor: PlaybackMonitor) -> Self {
+        let queue = Arc::new(PlaybackQueue::new(
+            monitor,
+            Box::new(move |event, status| {
+                if let Err(e) = app_handle.emit(event, status) {
+                    log::error!("Failed to emit {} event: {}", event, e);
+                }
+            }),
+        ));
+
+        let pump = queue.clone();
+        std::thread::spawn(move || loop {
+            pump.wait_until_queued();
+            std::thread::sleep(POLL_INTERVAL);
+            pump.advance();
+        });
+
+        Self {
+            queue,
+            mixer: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Add an utterance to the end of the queue
+    ///
+    /// Audio appended to it plays once the utterances before it have finished.
+    pub fn enqueue(&self) -> Result<Utterance, String> {
+        let mixer = self.mixer()?;
+        Ok(self.queue.push(Sink::connect_new(&mixer)))
+    }
+
+    /// Stop the current utterance and drop everything queued
+    pub fn stop(&self) {
+        self.queue.stop();
+    }
+
+    /// Pause the current utterance (queued ones wait behind it)
+    ///
+    /// Returns false if nothing is playing.
+    pub fn pause(&self) -> bool {
+        self.queue.pause()
+    }
+
+    /// Resume after `pause`
+    pub fn resume(&self) -> bool {
+        self.queue.resume()
+    }
+
+    /// Skip the current utterance and continue with the next one
+    ///
+    /// Returns false if nothing is playing.
+    pub fn skip(&self) -> bool {
+        self.queue.skip()
+    }
+
+    pub fn status(&self) -> PlaybackStatus {
+        self.queue.status()
+    }
+
+    /// Mixer of the output stream, opening the default device if needed
+    fn mixer(&self) -> Result<Mixer, String> {
+        let mut mixer = self.mixer.lock().map_err(|e| format!("Failed to lock audio output: {}", e))?;
+        if let Some(mixer) = mixer.as_ref() {
+            return Ok(mixer.clone());
+        }
+
+        let opened = open_output()?;
+        *mixer = Some(opened.clone());
+        Ok(opened)
+    }
+}
+
+impl PlaybackQueue {
+    fn new(monitor: PlaybackMonitor, events: PlaybackEvents) -> Self {
+        Self {
+            monitor,
+            events,
+            utterances: Mutex::new(VecDeque::new()),
+            queued: Condvar::new(),
+            paused: AtomicBool::new(false),
+            playing: AtomicBool::new(false),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    fn lock_queue(&self) -> std::sync::MutexGuard<'_, VecDeque<Arc<QueuedUtterance>>> {
+        self.utterances.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Block until at least one utterance is queued
+    fn wait_until_queued(&self) {
+        let queue = self.lock_queue();
+        let _queue = self
+            .queued
+            .wait_while(queue, |queue| queue.is_empty())
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+    }
+
+    /// Queue an utterance played through `sink`
+    fn push(self: &Arc<Self>, sink: Sink) -> Utterance {
+        // Held back until it reaches the front of the queue
+        sink.pause();
+
+        let utterance = Arc::new(QueuedUtterance {
+            id: self.next_id.fetch_add(1, Ordering::SeqCst),
+            sink,
+            complete: AtomicBool::new(false),
+            cancelled: AtomicBool::new(false),
+            unreported: Mutex::new(Vec::new()),
+            done: watch::channel(false).0,
+        });
+        log::debug!("Queued utterance {}", utterance.id);
+
+        self.lock_queue().push_back(utterance.clone());
+        self.queued.notify_all();
+        // Start right away if nothing else is playing
+        self.advance();
+
+        Utterance {
+            state: utterance,
+            queue: self.clone(),
+        }
+    }
+
+    fn pause(&self) -> bool {
+        let queue = self.lock_queue();
+        let Some(front) = queue.front() else {
+            return false;
+        };
+        self.paused.store(true, Ordering::SeqCst);
+        front.sink.pause();
+        self.monitor.pause();
+        log::info!("TTS playback paused");
+        true
+    }
+
+    fn resume(&self) -> bool {
+        let queue = self.lock_queue();
+        self.paused.store(false, Ordering::SeqCst);
+        let Some(front) = queue.front() else {
+            return false;
+        };
+        front.sink.play();
+        self.monitor.resume();
+        log::info!("TTS playback resumed");
+        true
+    }
+
+    fn skip(self: &Arc<Self>) -> bool {
+        let skipped = self.lock_queue().front().cloned();
+        let Some(skipped) = skipped else {
+            return false;
+        };
+        log::info!("Skipping utterance {}", skipped.id);
+        skipped.cancel();
+        self.advance();
+        true
+    }
+
+    fn status(&self) -> PlaybackStatus {
+        let queue_length = self.lock_queue().len();
+        PlaybackStatus {
+            playing: queue_length > 0,
+            paused: self.paused.load(Ordering::SeqCst),
+            queue_length,
+        }
+    }
+
+    fn stop(self: &Arc<Self>) {
+        let queue = self.lock_queue();
+        if queue.is_empty() {
+            return;
+        }
+        log::info!("Stopping TTS playback ({} utterances)", queue.len());
+        for utterance in queue.iter() {
+            utterance.cancel();
+        }
+        drop(queue);
+        self.paused.store(false, Ordering::SeqCst);
+        self.advance();
+    }
+
+    /// Drop finished utterances, start the next one and send start/finish events
+    fn advance(self: &Arc<Self>) {
+        let mut queue = self.lock_queue();
+        while queue.front().is_some_and(|front| front.is_done()) {
+            if let Some(finished) = queue.pop_front() {
+                log::debug!("Utterance {} finished", finished.id);
+                finished.sink.stop();
+                finished.done.send_replace(true);
+            }
+        }
+        let front = queue.front().cloned();
+        drop(queue);
+
+        match front {
+            Some(front) => {
+                if !self.paused.load(Ordering::SeqCst) && front.sink.is_paused() {
+                    front.sink.play();
+                }
+                if !front.sink.is_paused() && !front.sink.empty() {
+                    self.report_playing(&front);
+                }
+            }
+            None => {
+                if self.playing.swap(false, Ordering::SeqCst) {
+                    self.paused.store(false, Ordering::SeqCst);
+                    self.monitor.end();
+                    self.emit("tts_playback_finished");
+                }
+            }
+        }
+    }
+
+    /// Tell the monitor about audio of `front` that is now playing
+    fn report_playing(self: &Arc<Self>, front: &QueuedUtterance) {
+        if !self.playing.swap(true, Ordering::SeqCst) {
+            // Barge-in stops the whole queue
+            let queue = Arc::downgrade(self);
+            self.monitor.begin(&[], 0, move || {
+                if let Some(queue) = queue.upgrade() {
+                    queue.stop();
+                }
+            });
+            self.emit("tts_playback_started");
+        }
+
+        let unreported = std::mem::take(&mut *front.lock_unreported());
+        for (samples, sample_rate) in unreported {
+            self.monitor.extend(&samples, sample_rate);
+        }
+    }
+
+    fn emit(&self, event: &str) {
+        (self.events)(event, self.status());
+    }
+}
+
+/// Open the default output device
+///
+/// The stream lives on its own thread for the rest of the app's lifetime (it can't be
+/// moved between threads); sinks connect to it through the mixer.
+fn open_output() -> Result<Mixer, String> {
+    log::debug!("Opening audio output device...");
+    let (tx, rx) = std::sync::mpsc::channel();
+
+    std::thread::spawn(move || {
+        // rodio 0.21 API
+        match rodio::OutputStreamBuilder::open_default_stream() {
+            Ok(stream) => {
+                let _ = tx.send(Ok(stream.mixer().clone()));
+                loop {
+                    std::thread::park();
+                }
+            }
+            Err(e) => {
+                let _ = tx.send(Err(format!("Failed to open audio output device: {}", e)));
+            }
+        }
+    });
+
+    rx.recv().map_err(|_| "Audio output thread exited".to_string())?
+}
+
+/// An utterance being filled with audio
+///
+/// Dropping it (or calling `finish`) marks it complete: it is done once its audio has
+/// played.
+pub struct Utterance {
+    state: Arc<QueuedUtterance>,
+    queue: Arc<PlaybackQueue>,
+}
+
+impl Utterance {
+    /// Append audio to the utterance
+    ///
+    /// `samples` (normalized to -1.0..1.0) are the same audio as `source`; they feed
+    /// the barge-in echo estimate. Returns false if the utterance was skipped or
+    /// stopped, in which case the rest of it can be dropped.
+    pub fn append<S>(&self, source: S, samples: &[f32], sample_rate: u32) -> bool
+    where
+        S: Source + Send + 'static,
+    {
+        if self.state.cancelled.load(Ordering::SeqCst) {
+            return false;
+        }
+        self.state.lock_unreported().push((samples.to_vec(), sample_rate));
+        self.state.sink.append(source);
+        // Reported to the monitor as soon as it plays
+        self.queue.advance();
+        true
+    }
+
+    /// No more audio will be appended; returns a handle to wait for the end of playback
+    pub fn finish(self) -> PlaybackFinished {
+        PlaybackFinished(self.state.done.subscribe())
+    }
+}
+
+impl Drop for Utterance {
+    fn drop(&mut self) {
+        self.state.complete.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Resolves when an utterance has played, been skipped or been stopped
+pub struct PlaybackFinished(watch::Receiver<bool>);
+
+impl PlaybackFinished {
+    pub async fn wait(mut self) {
+        let _ = self.0.wait_for(|done| *done).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rodio::buffer::SamplesBuffer;
+    use rodio::queue::SourcesQueueOutput;
+
+    const SAMPLE_RATE: u32 = 16000;
+
+    /// Queue that records the events it sends
+    fn test_queue() -> (Arc<PlaybackQueue>, Arc<Mutex<Vec<String>>>) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sent = events.clone();
+        let queue = PlaybackQueue::new(
+            PlaybackMonitor::new(),
+            Box::new(move |event, _| sent.lock().unwrap().push(event.to_string())),
+        );
+        (Arc::new(queue), events)
+    }
+
+    fn append_audio(utterance: &Utterance) -> bool {
+        let samples = vec![0.1; 160];
+        utterance.append(SamplesBuffer::new(1, SAMPLE_RATE, samples.clone()), &samples, SAMPLE_RATE)
+    }
+
+    /// Pull audio out of a sink like the output device would
+    fn play_out(output: &mut SourcesQueueOutput) {
+        output.by_ref().take(SAMPLE_RATE as usize / 10).for_each(drop);
+    }
+
+    fn is_finished(finished: &PlaybackFinished) -> bool {
+        *finished.0.borrow()
+    }
+
+    #[test]
+    fn test_utterance_is_done_once_complete_and_played() {
+        let (queue, events) = test_queue();
+        let (sink, mut output) = Sink::new();
+        let utterance = queue.push(sink);
+        let state = utterance.state.clone();
+
+        assert!(append_audio(&utterance));
+        play_out(&mut output);
+        assert!(!state.is_done(), "more audio may still be appended");
+
+        let finished = utterance.finish();
+        assert!(state.is_done());
+        assert!(!is_finished(&finished), "not noticed before the next advance");
+
+        queue.advance();
+        assert!(is_finished(&finished));
+        assert_eq!(queue.status().queue_length, 0);
+        assert_eq!(*events.lock().unwrap(), ["tts_playback_started", "tts_playback_finished"]);
+        assert!(!queue.monitor.is_active());
+    }
+
+    #[test]
+    fn test_complete_utterance_is_not_done_while_audio_plays() {
+        let (queue, _) = test_queue();
+        let (sink, mut output) = Sink::new();
+        let utterance = queue.push(sink);
+        let state = utterance.state.clone();
+
+        assert!(append_audio(&utterance));
+        let finished = utterance.finish();
+        assert!(!state.is_done());
+
+        queue.advance();
+        assert!(!is_finished(&finished));
+        play_out(&mut output);
+        queue.advance();
+        assert!(is_finished(&finished));
+    }
+
+    #[test]
+    fn test_advance_holds_back_queued_utterances() {
+        let (queue, events) = test_queue();
+        let (first_sink, mut first_output) = Sink::new();
+        let (second_sink, _second_output) = Sink::new();
+        let first = queue.push(first_sink);
+        let second = queue.push(second_sink);
+        let second_state = second.state.clone();
+
+        assert!(append_audio(&first));
+        assert!(append_audio(&second));
+        assert!(!first.state.sink.is_paused());
+        assert!(second_state.sink.is_paused(), "waits for the first utterance");
+        assert_eq!(queue.status().queue_length, 2);
+
+        let first = first.finish();
+        play_out(&mut first_output);
+        queue.advance();
+
+        assert!(is_finished(&first));
+        assert!(!second_state.sink.is_paused());
+        assert_eq!(queue.status().queue_length, 1);
+        // The queue kept playing, so no finish in between
+        assert_eq!(*events.lock().unwrap(), ["tts_playback_started"]);
+    }
+
+    #[test]
+    fn test_skip_moves_to_next_utterance() {
+        let (queue, _) = test_queue();
+        let (first_sink, _first_output) = Sink::new();
+        let (second_sink, _second_output) = Sink::new();
+        let first = queue.push(first_sink);
+        let second = queue.push(second_sink);
+        assert!(append_audio(&first));
+        assert!(append_audio(&second));
+
+        assert!(queue.skip());
+        assert!(!append_audio(&first), "skipped utterances take no more audio");
+        assert!(is_finished(&first.finish()));
+        assert!(!second.state.sink.is_paused());
+        assert_eq!(queue.status().queue_length, 1);
+        assert!(queue.monitor.is_active());
+    }
+
+    #[test]
+    fn test_skip_with_empty_queue() {
+        let (queue, events) = test_queue();
+        assert!(!queue.skip());
+        assert!(events.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_stop_drops_whole_queue() {
+        let (queue, events) = test_queue();
+        let (first_sink, _first_output) = Sink::new();
+        let (second_sink, _second_output) = Sink::new();
+        let first = queue.push(first_sink);
+        let second = queue.push(second_sink);
+        assert!(append_audio(&first));
+        assert!(append_audio(&second));
+        assert!(queue.pause());
+
+        queue.stop();
+
+        assert!(is_finished(&first.finish()));
+        assert!(is_finished(&second.finish()));
+        let status = queue.status();
+        assert!(!status.playing && !status.paused);
+        assert_eq!(*events.lock().unwrap(), ["tts_playback_started", "tts_playback_finished"]);
+        assert!(!queue.monitor.is_active());
+    }
+
+    #[test]
+    fn test_barge_in_stops_queue() {
+        let (queue, _) = test_queue();
+        let (sink, _output) = Sink::new();
+        let utterance = queue.push(sink);
+        assert!(append_audio(&utterance));
+
+        assert!(queue.monitor.stop());
+        assert!(is_finished(&utterance.finish()));
+        assert_eq!(queue.status().queue_length, 0);
+    }
+
+    #[test]
+    fn test_pump_wakes_up_when_utterance_is_queued() {
+        let (queue, _) = test_queue();
+        let (woken_tx, woken_rx) = std::sync::mpsc::channel();
+        let pump = queue.clone();
+        std::thread::spawn(move || {
+            pump.wait_until_queued();
+            let _ = woken_tx.send(());
+        });
+
+        assert!(woken_rx.recv_timeout(Duration::from_millis(100)).is_err(), "idle while the queue is empty");
+        let (sink, _output) = Sink::new();
+        let _utterance = queue.push(sink);
+        assert!(woken_rx.recv_timeout(Duration::from_secs(1)).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_playback_finished_resolves() {
+        let (queue, _) = test_queue();
+        let (sink, _output) = Sink::new();
+        let utterance = queue.push(sink);
+        assert!(append_audio(&utterance));
+        let finished = utterance.finish();
+
+        let waiter = tokio::spawn(finished.wait());
+        queue.stop();
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("wait() should resolve once stopped")
+            .unwrap();
+    }
+}
@@ -0,0 +1,357 @@
+//! Context-window-aware prompt assembly
+//!
+//! Fits the parts of a chat request into the model's context window:
+//! - Token estimation for each part (system prompt, history, web search context, question)
+//! - Prompt budget from the configured cap, the model's context length and the
+//!   tokens reserved for the answer (which shrink if the window is too small for both)
+//! - Lowest-priority parts are truncated first
+//!
+//! Priority, highest first: the user's question, the system prompt (with the
+//! conversation's rolling summary), web search context, recent history, older
+//! history. The question, system prompt and summary are always sent; search context
+//! is cut at paragraph boundaries and history is dropped oldest turn first.
+
+use crate::database::Message;
+use crate::llm::ChatMessage;
+
+/// Fewest prompt tokens we ever plan for, even if the context window looks smaller
+const MIN_PROMPT_BUDGET: u32 = 256;
+
+/// Marker appended to web search context that had to be cut short
+const TRUNCATION_MARKER: &str = "[... remaining search results omitted to fit the context window ...]\n\n";
+
+/// Conversation material sent along with the user's question
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PromptContext<'a> {
+    /// Prior turns not covered by `summary`, oldest first (excluding the current prompt)
+    pub history: &'a [Message],
+    /// Rolling summary of the turns before `history` (see `summarizer`)
+    pub summary: Option<&'a str>,
+    /// Web search results for the prompt when online mode is enabled
+    pub search_context: Option<&'a str>,
+}
+
+/// Rough token estimate for budget checks
+///
+/// Uses ~4 characters per token for English text, but never fewer tokens than
+/// words (short words and punctuation-heavy text tokenize worse than 4 chars).
+pub fn estimate_tokens(text: &str) -> u32 {
+    let chars = text.chars().count() as u32;
+    let words = text.split_whitespace().count() as u32;
+    // Every message also carries a few tokens of role/formatting overhead
+    chars.div_ceil(4).max(words) + 4
+}
+
+/// Token limits for one request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenBudget {
+    /// Tokens available for the prompt
+    pub prompt: u32,
+    /// Tokens the answer may use (`max_tokens` of the request)
+    pub max_output: u32,
+}
+
+/// Split the context window between the prompt and the answer
+///
+/// The prompt gets what the answer leaves free, up to the configured cap. If that is
+/// less than `MIN_PROMPT_BUDGET` (or half the window, for tiny windows) the answer is
+/// shortened instead, so prompt and answer together never exceed the window.
+///
+/// # Arguments
+/// * `budget_cap` - User-configured prompt budget (`context_token_budget`)
+/// * `context_length` - Model context window, if known
+/// * `max_output_tokens` - Tokens requested for the generated answer
+pub fn token_budget(budget_cap: u32, context_length: Option<u32>, max_output_tokens: u32) -> TokenBudget {
+    let Some(context_length) = context_length else {
+        return TokenBudget {
+            prompt: budget_cap,
+            max_output: max_output_tokens,
+        };
+    };
+
+    let min_prompt = MIN_PROMPT_BUDGET.min(context_length / 2);
+    let prompt = budget_cap.min(context_length.saturating_sub(max_output_tokens).max(min_prompt));
+    TokenBudget {
+        prompt,
+        max_output: max_output_tokens.min(context_length - prompt),
+    }
+}
+
+/// Combine web search context and the user's question into the final user message
+///
+/// The question always comes last so small models don't lose it behind the context.
+pub fn augment_prompt(search_context: Option<&str>, user_prompt: &str) -> String {
+    match search_context {
+        Some(context) if !context.is_empty() => format!("{}\nUser Question: {}", context, user_prompt),
+        _ => user_prompt.to_string(),
+    }
+}
+
+/// Append the conversation summary to the system prompt
+fn system_message(system_prompt: &str, summary: Option<&str>) -> String {
+    match summary {
+        Some(summary) if !summary.trim().is_empty() => format!(
+            "{}\n\nSummary of the earlier conversation:\n{}",
+            system_prompt,
+            summary.trim()
+        ),
+        _ => system_prompt.to_string(),
+    }
+}
+
+/// Cut web search context down to `budget` tokens at paragraph boundaries
+///
+/// Returns None when not even the first paragraph fits.
+fn truncate_search_context(context: &str, budget: u32) -> Option<String> {
+    if estimate_tokens(context) <= budget {
+        return Some(context.to_string());
+    }
+
+    let marker_cost = estimate_tokens(TRUNCATION_MARKER);
+    let mut kept = String::new();
+    for paragraph in context.split_inclusive("\n\n") {
+        let mut candidate = kept.clone();
+        candidate.push_str(paragraph);
+        if estimate_tokens(&candidate) + marker_cost > budget {
+            break;
+        }
+        kept = candidate;
+    }
+
+    if kept.is_empty() {
+        None
+    } else {
+        kept.push_str(TRUNCATION_MARKER);
+        Some(kept)
+    }
+}
+
+/// Assemble the messages array: system prompt, as much recent history as fits, then the prompt
+///
+/// The system prompt (with the summary) and user question are always included, even
+/// if they alone exceed the budget. Web search context gets the remaining budget next
+/// (truncated or dropped if needed), then history is walked from newest to oldest so
+/// the most recent turns are kept.
+pub(crate) fn build_messages(
+    system_prompt: &str,
+    context: &PromptContext<'_>,
+    user_prompt: &str,
+    token_budget: u32,
+) -> Vec<ChatMessage> {
+    let system_prompt = system_message(system_prompt, context.summary);
+    let mut remaining = token_budget
+        .saturating_sub(estimate_tokens(&system_prompt))
+        .saturating_sub(estimate_tokens(user_prompt));
+
+    let search_context = context
+        .search_context
+        .filter(|context| !context.is_empty())
+        .and_then(|context| {
+            let truncated = truncate_search_context(context, remaining);
+            match &truncated {
+                None => log::warn!("Dropping web search context: no room left in the context window"),
+                Some(kept) if kept.len() < context.len() => {
+                    log::warn!("Truncated web search context to fit the context window")
+                }
+                Some(_) => {}
+            }
+            truncated
+        });
+    if let Some(context) = &search_context {
+        // The context shares the user message, so it carries no extra message overhead
+        remaining = remaining.saturating_sub(estimate_tokens(context).saturating_sub(4));
+    }
+
+    let mut kept: Vec<ChatMessage> = Vec::new();
+    for message in context.history.iter().rev() {
+        if message.role != "user" && message.role != "assistant" {
+            continue;
+        }
+
+        let cost = estimate_tokens(&message.content);
+        if cost > remaining {
+            break;
+        }
+        remaining -= cost;
+        kept.push(ChatMessage::new(&message.role, &message.content));
+    }
+    kept.reverse();
+
+    // Don't open the replayed history with an orphaned assistant reply
+    while kept.first().map(|m| m.role == "assistant").unwrap_or(false) {
+        kept.remove(0);
+    }
+
+    let mut messages = Vec::with_capacity(kept.len() + 2);
+    messages.push(ChatMessage::new("system", &system_prompt));
+    messages.extend(kept);
+    messages.push(ChatMessage::new("user", &augment_prompt(search_context.as_deref(), user_prompt)));
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(id: i64, role: &str, content: &str) -> Message {
+        Message {
+            id,
+            conversation_id: 1,
+            role: role.to_string(),
+            content: content.to_string(),
+            timestamp: String::new(),
+        }
+    }
+
+    fn with_history(history: &[Message]) -> PromptContext<'_> {
+        PromptContext {
+            history,
+            ..Default::default()
+        }
+    }
+
+    fn with_search(context: &str) -> PromptContext<'_> {
+        PromptContext {
+            search_context: Some(context),
+            ..Default::default()
+        }
+    }
+
+    fn search_context(sources: usize) -> String {
+        let mut context = String::from("=== Web Search Results ===\n\n");
+        for i in 1..=sources {
+            context.push_str(&format!("[Source {}]\nTitle: Result {}\nContent: {}\n\n", i, i, "word ".repeat(40)));
+        }
+        context.push_str("=== End of Search Results ===\n\n");
+        context
+    }
+
+    #[test]
+    fn test_build_messages_replays_history() {
+        let history = vec![
+            message(1, "user", "What's the weather today?"),
+            message(2, "assistant", "Sunny and 22 degrees."),
+        ];
+
+        let messages = build_messages("system", &with_history(&history), "and what about tomorrow?", 2048);
+
+        let roles: Vec<&str> = messages.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, vec!["system", "user", "assistant", "user"]);
+        assert_eq!(messages[1].content, "What's the weather today?");
+        assert_eq!(messages[3].content, "and what about tomorrow?");
+    }
+
+    #[test]
+    fn test_build_messages_drops_oldest_turns_first() {
+        let long_turn = "x".repeat(400); // ~104 tokens each
+        let history = vec![
+            message(1, "user", &long_turn),
+            message(2, "assistant", &long_turn),
+            message(3, "user", "recent question"),
+            message(4, "assistant", "recent answer"),
+        ];
+
+        let messages = build_messages("system", &with_history(&history), "follow-up", 120);
+
+        let contents: Vec<&str> = messages.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, vec!["system", "recent question", "recent answer", "follow-up"]);
+    }
+
+    #[test]
+    fn test_build_messages_skips_leading_assistant_turn() {
+        let history = vec![
+            message(1, "user", &"x".repeat(400)),
+            message(2, "assistant", "short answer"),
+        ];
+
+        let messages = build_messages("system", &with_history(&history), "next", 40);
+
+        let roles: Vec<&str> = messages.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, vec!["system", "user"]);
+    }
+
+    #[test]
+    fn test_search_context_is_kept_before_history() {
+        let context = search_context(2);
+        let history = vec![
+            message(1, "user", &"x".repeat(400)),
+            message(2, "assistant", &"y".repeat(400)),
+        ];
+
+        let prompt_context = PromptContext {
+            history: &history,
+            summary: None,
+            search_context: Some(&context),
+        };
+
+        let messages = build_messages("system", &prompt_context, "what's new?", 200);
+
+        assert_eq!(messages.len(), 2);
+        assert!(messages[1].content.starts_with("=== Web Search Results ==="));
+        assert!(messages[1].content.ends_with("User Question: what's new?"));
+    }
+
+    #[test]
+    fn test_search_context_truncated_but_question_kept() {
+        let context = search_context(10);
+
+        let messages = build_messages("system", &with_search(&context), "what's new?", 200);
+        let user_message = &messages[1].content;
+
+        assert!(user_message.contains("[Source 1]"));
+        assert!(!user_message.contains("[Source 10]"));
+        assert!(user_message.contains("remaining search results omitted"));
+        assert!(user_message.ends_with("User Question: what's new?"));
+        assert!(estimate_tokens(user_message) <= 200);
+    }
+
+    #[test]
+    fn test_search_context_dropped_when_nothing_fits() {
+        let context = search_context(3);
+
+        let messages = build_messages("system", &with_search(&context), "what's new?", 20);
+
+        assert_eq!(messages[1].content, "what's new?");
+    }
+
+    #[test]
+    fn test_summary_is_added_to_system_message() {
+        let history = vec![message(9, "user", "and the day after?")];
+        let prompt_context = PromptContext {
+            history: &history,
+            summary: Some("The user is planning a trip to Oslo."),
+            search_context: None,
+        };
+
+        let messages = build_messages("system", &prompt_context, "thanks", 2048);
+
+        assert_eq!(messages.len(), 3);
+        assert!(messages[0].content.starts_with("system\n\nSummary of the earlier conversation:"));
+        assert!(messages[0].content.ends_with("The user is planning a trip to Oslo."));
+        assert_eq!(messages[1].content, "and the day after?");
+    }
+
+    #[test]
+    fn test_token_budget_respects_context_window() {
+        let budget = |prompt, max_output| TokenBudget { prompt, max_output };
+
+        // Unknown context length: the configured cap applies
+        assert_eq!(token_budget(2048, None, 512), budget(2048, 512));
+        // Small window: leave room for the answer
+        assert_eq!(token_budget(2048, Some(2048), 512), budget(1536, 512));
+        // Large window: the configured cap still applies
+        assert_eq!(token_budget(2048, Some(131072), 512), budget(2048, 512));
+        // Too small for both: the prompt keeps its minimum and the answer shrinks
+        assert_eq!(token_budget(2048, Some(600), 512), budget(MIN_PROMPT_BUDGET, 600 - MIN_PROMPT_BUDGET));
+        // Tiny window: split evenly
+        assert_eq!(token_budget(2048, Some(200), 512), budget(100, 100));
+    }
+
+    #[test]
+    fn test_estimate_tokens_counts_words() {
+        assert_eq!(estimate_tokens(""), 4);
+        assert_eq!(estimate_tokens("abcdefgh"), 6);
+        // Many short words cost at least one token each
+        assert_eq!(estimate_tokens("a b c d e f g h"), 12);
+    }
+}
@@ -14,6 +14,9 @@ const SPOTIFY_TOKEN_EXPIRY: &str = "spotify_token_expiry";
 /// Keyring entry name for Home Assistant token
 const HA_ACCESS_TOKEN: &str = "ha_access_token";
 
+/// Keyring entry name prefix for fallback LLM backend API keys
+const LLM_BACKEND_API_KEY_PREFIX: &str = "llm_backend_api_key";
+
 /// Save API key to the OS keyring
 ///
 /// Uses the native credential storage:
@@ -315,6 +318,50 @@ pub fn is_ha_connected() -> bool {
     load_ha_access_token().is_ok()
 }
 
+// =============================================================================
+// Fallback LLM Backend API Keys
+// =============================================================================
+
+/// Get the keyring entry name for a fallback LLM backend's API key
+fn get_llm_backend_api_key_name(backend_name: &str) -> String {
+    format!("{}_{}", LLM_BACKEND_API_KEY_PREFIX, backend_name)
+}
+
+/// Save a fallback LLM backend's API key to the OS keyring (empty key deletes it)
+pub fn save_llm_backend_api_key(backend_name: &str, api_key: &str) -> Result<(), String> {
+    log::info!("Saving API key for LLM backend '{}' to OS keyring", backend_name);
+
+    let entry = Entry::new(SERVICE_NAME, &get_llm_backend_api_key_name(backend_name))
+        .map_err(|e| format!("Failed to create keyring entry: {}", e))?;
+
+    if api_key.is_empty() {
+        return match entry.delete_credential() {
+            Ok(_) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(format!("Failed to delete LLM backend API key: {}", e)),
+        };
+    }
+
+    entry
+        .set_password(api_key)
+        .map_err(|e| format!("Failed to save LLM backend API key to keyring: {}", e))
+}
+
+/// Load a fallback LLM backend's API key from the OS keyring
+///
+/// Returns `None` if no key is stored (many local servers don't need one)
+pub fn load_llm_backend_api_key(backend_name: &str) -> Option<String> {
+    let entry = Entry::new(SERVICE_NAME, &get_llm_backend_api_key_name(backend_name)).ok()?;
+
+    match entry.get_password() {
+        Ok(api_key) if !api_key.is_empty() => Some(api_key),
+        Ok(_) | Err(keyring::Error::NoEntry) => None,
+        Err(e) => {
+            log::warn!("Failed to load API key for LLM backend '{}': {}", backend_name, e);
+            None
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
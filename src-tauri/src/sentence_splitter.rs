@@ -0,0 +1,200 @@
+//! Sentence Splitting for Streaming TTS
+//!
+//! Piper synthesizes a whole input before returning any audio, so long answers are
+//! spoken sentence by sentence: the first sentence plays while the next is being
+//! synthesized. `SentenceSplitter` also works on streamed LLM output, handing out
+//! each sentence as soon as it is complete.
+//!
+//! A sentence ends at `.`, `!`, `?` or `…` followed by whitespace (closing quotes and
+//! brackets stay with the sentence), or at a line break. Decimal numbers, common
+//! abbreviations ("e.g.", "Dr.") and numbers that are list markers ("1. Open"),
+//! dates ("the 3. March") or continued in lowercase don't end a sentence.
+
+const TERMINATORS: &[char] = &['.', '!', '?', '…'];
+const CLOSING: &[char] = &['"', '\'', ')', ']', '”', '’', '»'];
+const ABBREVIATIONS: &[&str] = &["mr.", "mrs.", "ms.", "dr.", "st.", "vs.", "e.g.", "i.e.", "approx."];
+const MONTHS: &[&str] = &[
+    "january", "february", "march", "april", "may", "june",
+    "july", "august", "september", "october", "november", "december",
+];
+
+/// Splits text into sentences, incrementally
+#[derive(Debug, Default)]
+pub struct SentenceSplitter {
+    buffer: String,
+}
+
+impl SentenceSplitter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the next piece of text; returns the sentences it completes
+    pub fn push(&mut self, text: &str) -> Vec<String> {
+        self.buffer.push_str(text);
+
+        let mut sentences = Vec::new();
+        while let Some(end) = sentence_end(&self.buffer) {
+            let sentence = self.buffer[..end].trim().to_string();
+            self.buffer.drain(..end);
+            if !sentence.is_empty() {
+                sentences.push(sentence);
+            }
+        }
+        sentences
+    }
+
+    /// End of the text: returns the last sentence if it had no terminator
+    pub fn finish(&mut self) -> Option<String> {
+        let rest = self.buffer.trim().to_string();
+        self.buffer.clear();
+        (!rest.is_empty()).then_some(rest)
+    }
+}
+
+/// Split complete text into sentences
+pub fn split_sentences(text: &str) -> Vec<String> {
+    let mut splitter = SentenceSplitter::new();
+    let mut sentences = splitter.push(text);
+    sentences.extend(splitter.finish());
+    sentences
+}
+
+/// Byte offset just past the first complete sentence, if there is one
+///
+/// A terminator at the very end of the text isn't a boundary yet: the next piece of a
+/// stream may continue it ("3." + "5 degrees").
+fn sentence_end(text: &str) -> Option<usize> {
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((index, c)) = chars.next() {
+        if c == '\n' {
+            return Some(index + c.len_utf8());
+        }
+        if !TERMINATORS.contains(&c) {
+            continue;
+        }
+
+        // Include repeated terminators ("?!", "...") and closing quotes/brackets
+        let mut end = index + c.len_utf8();
+        while let Some(&(next_index, next)) = chars.peek() {
+            if TERMINATORS.contains(&next) || CLOSING.contains(&next) {
+                end = next_index + next.len_utf8();
+                chars.next();
+            } else {
+                break;
+            }
+        }
+
+        match chars.peek() {
+            Some(&(_, next)) if next.is_whitespace() => match is_boundary(&text[..end], &text[end..]) {
+                Some(true) => return Some(end),
+                Some(false) => {}
+                // Depends on the next word, which hasn't arrived yet
+                None => return None,
+            },
+            Some(_) => {}
+            None => return None,
+        }
+    }
+
+    None
+}
+
+/// Whether the terminator at the end of `text` ends a sentence, given the text after it
+///
+/// A period after an abbreviation never does. After a number it doesn't if the number
+/// is a list marker ("1. Open the app", "Steps: 2. Ask"), a date ("the 3. March") or
+/// the next word is lowercase ("page 3. then"). Returns None if that depends on a
+/// next word that isn't complete yet.
+fn is_boundary(text: &str, rest: &str) -> Option<bool> {
+    if !text.ends_with('.') {
+        return Some(true);
+    }
+
+    let mut words = text.split_whitespace().rev();
+    let last_word = words.next().unwrap_or("").to_lowercase();
+    if ABBREVIATIONS.contains(&last_word.as_str()) {
+        return Some(false);
+    }
+
+    let number = last_word.trim_end_matches('.');
+    if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+        return Some(true);
+    }
+
+    let list_marker = words.next().is_none_or(|previous| previous.ends_with(':'));
+    if list_marker {
+        return Some(false);
+    }
+
+    let next_word = rest.split_whitespace().next()?;
+    if next_word.starts_with(char::is_lowercase) {
+        return Some(false);
+    }
+
+    let name = next_word.trim_end_matches(|c: char| !c.is_alphabetic()).to_lowercase();
+    let word_complete = rest.trim_end().len() < rest.len() || name.len() < next_word.len();
+    if !word_complete && !name.is_empty() && MONTHS.iter().any(|month| month.starts_with(&name)) {
+        return None;
+    }
+    Some(!MONTHS.contains(&name.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_sentences() {
+        assert_eq!(
+            split_sentences("It's 21.5 degrees inside. Want me to turn on the heating? Done!"),
+            vec!["It's 21.5 degrees inside.", "Want me to turn on the heating?", "Done!"]
+        );
+        assert_eq!(
+            split_sentences("He said \"hello.\" Then he left... Really?! Yes"),
+            vec!["He said \"hello.\"", "Then he left...", "Really?!", "Yes"]
+        );
+        assert_eq!(split_sentences("   "), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_lists_and_abbreviations_stay_together() {
+        assert_eq!(
+            split_sentences("Steps:\n1. Open the app. 2. Ask Dr. Smith, e.g. by phone."),
+            vec!["Steps:", "1. Open the app.", "2. Ask Dr. Smith, e.g. by phone."]
+        );
+    }
+
+    #[test]
+    fn test_sentences_can_end_with_numbers() {
+        assert_eq!(
+            split_sentences("The temperature is 21. Want more?"),
+            vec!["The temperature is 21.", "Want more?"]
+        );
+        assert_eq!(
+            split_sentences("Steps: 1. Open the app. Turn to page 3. then read on. It's due on the 3. March. Ok?"),
+            vec!["Steps: 1. Open the app.", "Turn to page 3. then read on.", "It's due on the 3. March.", "Ok?"]
+        );
+
+        // The next word decides, so wait for it
+        let mut splitter = SentenceSplitter::new();
+        assert!(splitter.push("The temperature is 21. ").is_empty());
+        assert_eq!(splitter.push("Want more?"), vec!["The temperature is 21."]);
+        assert_eq!(splitter.push(" It's the 3. Ma"), vec!["Want more?"]);
+        assert_eq!(splitter.push("rch. Ok"), vec!["It's the 3. March."]);
+    }
+
+    #[test]
+    fn test_streamed_text_waits_for_boundary() {
+        let mut splitter = SentenceSplitter::new();
+        assert!(splitter.push("The temperature is 3").is_empty());
+        // "3." could still become "3.5"
+        assert!(splitter.push(".").is_empty());
+        assert!(splitter.push("5 degrees.").is_empty());
+        assert_eq!(splitter.push(" It's cold"), vec!["The temperature is 3.5 degrees."]);
+        assert_eq!(splitter.push(" outside! Bring"), vec!["It's cold outside!"]);
+        assert_eq!(splitter.finish(), Some("Bring".to_string()));
+        assert_eq!(splitter.finish(), None);
+    }
+}
@@ -0,0 +1,226 @@
+//! Speech-to-Text Vocabulary Biasing
+//!
+//! Whisper often mishears proper names ("Lumos lamp", "Chill Vibes" playlist). Passing
+//! the names the user is likely to say as Whisper's initial prompt biases decoding
+//! towards their spelling. The vocabulary is built from:
+//! - User shortcut names (`user_ha_shortcuts`)
+//! - Home Assistant areas and friendly names of controllable entities
+//! - Spotify playlist titles seen by the music commands
+//!
+//! Home Assistant terms are refreshed on every entity sync, playlist titles whenever
+//! playlists are fetched. The voice pipeline reads the prompt for each transcription.
+
+use crate::entity_manager::{extract_domain, Entity};
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+
+/// Whisper's prompt holds at most 224 tokens; stay well below
+const MAX_PROMPT_CHARS: usize = 600;
+
+/// Domains whose entities are addressed by name in voice commands
+const VOICE_DOMAINS: &[&str] = &[
+    "light", "switch", "cover", "climate", "media_player", "scene", "script", "fan", "lock", "vacuum",
+];
+
+pub type SttVocabularyState = Arc<SttVocabulary>;
+
+#[derive(Default)]
+struct Terms {
+    shortcuts: Vec<String>,
+    areas: Vec<String>,
+    entities: Vec<String>,
+    playlists: Vec<String>,
+}
+
+/// Names to bias Whisper towards, shared between Home Assistant, Spotify and the
+/// voice pipeline
+#[derive(Default)]
+pub struct SttVocabulary {
+    terms: RwLock<Terms>,
+    prompt: RwLock<String>,
+}
+
+impl SttVocabulary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the Home Assistant terms (after an entity sync or shortcut change)
+    pub fn set_home_terms(&self, entities: &[Entity], shortcut_names: Vec<String>) {
+        let mut entity_names = Vec::new();
+        let mut areas = Vec::new();
+        for entity in entities {
+            let voice_domain = extract_domain(&entity.entity_id)
+                .is_some_and(|domain| VOICE_DOMAINS.contains(&domain.as_str()));
+            if !voice_domain {
+                continue;
+            }
+
+            if let Some(name) = &entity.attributes.friendly_name {
+                entity_names.push(name.clone());
+            }
+            if let Some(area_id) = &entity.attributes.area_id {
+                areas.push(area_name(area_id));
+            }
+        }
+        // Stable order, so the prompt only changes when the names do
+        entity_names.sort();
+        areas.sort();
+
+        self.update(|terms| {
+            terms.shortcuts = shortcut_names;
+            terms.areas = areas;
+            terms.entities = entity_names;
+        });
+    }
+
+    /// Replace the cached playlist titles
+    pub fn set_playlists(&self, titles: Vec<String>) {
+        self.update(|terms| terms.playlists = titles);
+    }
+
+    /// Current initial prompt for Whisper (empty if there is nothing to bias towards)
+    pub fn prompt(&self) -> String {
+        self.prompt.read().map(|prompt| prompt.clone()).unwrap_or_default()
+    }
+
+    fn update(&self, change: impl FnOnce(&mut Terms)) {
+        let Ok(mut terms) = self.terms.write() else {
+            return;
+        };
+        change(&mut terms);
+
+        let prompt = build_prompt(&[&terms.shortcuts, &terms.areas, &terms.playlists, &terms.entities]);
+        log::debug!("STT vocabulary updated ({} chars): {}", prompt.len(), prompt);
+        if let Ok(mut current) = self.prompt.write() {
+            *current = prompt;
+        }
+    }
+}
+
+/// "living_room" -> "Living Room"
+fn area_name(area_id: &str) -> String {
+    area_id
+        .split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Join the term groups (most important first) into a comma-separated glossary
+///
+/// Duplicates are dropped case-insensitively; terms that don't fit in
+/// `MAX_PROMPT_CHARS` are left out.
+fn build_prompt(groups: &[&Vec<String>]) -> String {
+    let mut seen = HashSet::new();
+    let mut prompt = String::new();
+
+    for term in groups.iter().flat_map(|group| group.iter()) {
+        let term = term.trim();
+        if term.is_empty() || !seen.insert(term.to_lowercase()) {
+            continue;
+        }
+
+        let separator = if prompt.is_empty() { 0 } else { 2 };
+        // +1 for the final period
+        if prompt.len() + separator + term.len() + 1 > MAX_PROMPT_CHARS {
+            continue;
+        }
+        if !prompt.is_empty() {
+            prompt.push_str(", ");
+        }
+        prompt.push_str(term);
+    }
+
+    if !prompt.is_empty() {
+        prompt.push('.');
+    }
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity_manager::EntityAttributes;
+    use chrono::Utc;
+    use std::collections::HashMap;
+
+    fn entity(entity_id: &str, name: &str, area: Option<&str>) -> Entity {
+        Entity {
+            entity_id: entity_id.to_string(),
+            state: "off".to_string(),
+            attributes: EntityAttributes {
+                friendly_name: Some(name.to_string()),
+                area_id: area.map(|a| a.to_string()),
+                device_class: None,
+                brightness: None,
+                rgb_color: None,
+                color_temp: None,
+                unit_of_measurement: None,
+                temperature: None,
+                humidity: None,
+                hvac_mode: None,
+                current_temperature: None,
+                target_temperature: None,
+                current_position: None,
+                extra: HashMap::new(),
+            },
+            last_changed: Utc::now(),
+            last_updated: Utc::now(),
+        }
+    }
+
+    fn strings(terms: &[&str]) -> Vec<String> {
+        terms.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn test_area_name() {
+        assert_eq!(area_name("living_room"), "Living Room");
+        assert_eq!(area_name("kitchen"), "Kitchen");
+    }
+
+    #[test]
+    fn test_build_prompt_dedups_in_priority_order() {
+        let shortcuts = strings(&["Movie Night"]);
+        let areas = strings(&["Kitchen", "Living Room"]);
+        let entities = strings(&["kitchen", "Lava Lamp", "movie night"]);
+
+        let prompt = build_prompt(&[&shortcuts, &areas, &entities]);
+        assert_eq!(prompt, "Movie Night, Kitchen, Living Room, Lava Lamp.");
+        assert_eq!(build_prompt(&[&Vec::new()]), "");
+    }
+
+    #[test]
+    fn test_build_prompt_is_capped() {
+        let many: Vec<String> = (0..200).map(|i| format!("Playlist number {}", i)).collect();
+        let prompt = build_prompt(&[&many]);
+        assert!(prompt.len() <= MAX_PROMPT_CHARS);
+        assert!(prompt.starts_with("Playlist number 0, Playlist number 1,"));
+        assert!(prompt.ends_with('.'));
+    }
+
+    #[test]
+    fn test_vocabulary_uses_voice_domains_and_playlists() {
+        let vocabulary = SttVocabulary::new();
+        assert_eq!(vocabulary.prompt(), "");
+
+        vocabulary.set_home_terms(
+            &[
+                entity("light.lava_lamp", "Lava Lamp", Some("living_room")),
+                entity("sensor.outdoor_temperature", "Outdoor Temperature", Some("garden")),
+            ],
+            strings(&["Good Night"]),
+        );
+        vocabulary.set_playlists(strings(&["Chill Vibes"]));
+
+        assert_eq!(vocabulary.prompt(), "Good Night, Living Room, Chill Vibes, Lava Lamp.");
+    }
+}
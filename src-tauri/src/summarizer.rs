@@ -0,0 +1,199 @@
+//! Rolling Conversation Summarization
+//!
+//! Long conversations outgrow the model's context window. Instead of silently
+//! dropping the oldest turns, a background task folds them into a running summary
+//! per conversation (stored in `conversation_summaries`). `prepare_prompt` sends that
+//! summary with the system prompt in place of the turns it covers.
+
+use crate::database::{ConversationSummary, DatabaseState, Message};
+use crate::llm::{GenerationParams, LLMEngine};
+use crate::prompt_builder::estimate_tokens;
+use std::sync::Arc;
+use tokio::sync::Mutex as TokioMutex;
+
+/// Summarize once the unsummarized history uses this share (%) of the prompt budget
+const SUMMARY_TRIGGER_PERCENT: u32 = 60;
+
+/// The most recent messages always stay verbatim
+const KEEP_RECENT_MESSAGES: usize = 6;
+
+/// Generation parameters for the summary itself (short and factual)
+const SUMMARY_MAX_TOKENS: u32 = 300;
+const SUMMARY_TEMPERATURE: f32 = 0.3;
+
+/// Messages newer than the ones already covered by the summary
+pub fn unsummarized<'a>(messages: &'a [Message], summary: Option<&ConversationSummary>) -> &'a [Message] {
+    match summary {
+        Some(summary) => {
+            let start = messages
+                .iter()
+                .position(|m| m.id > summary.summarized_through_id)
+                .unwrap_or(messages.len());
+            &messages[start..]
+        }
+        None => messages,
+    }
+}
+
+/// Pick the oldest unsummarized turns to fold into the summary, if the history is
+/// getting too large for the prompt budget
+fn turns_to_summarize(unsummarized: &[Message], token_budget: u32) -> Option<&[Message]> {
+    if unsummarized.len() <= KEEP_RECENT_MESSAGES {
+        return None;
+    }
+
+    let history_tokens: u32 = unsummarized.iter().map(|m| estimate_tokens(&m.content)).sum();
+    if history_tokens * 100 <= token_budget * SUMMARY_TRIGGER_PERCENT {
+        return None;
+    }
+
+    Some(&unsummarized[..unsummarized.len() - KEEP_RECENT_MESSAGES])
+}
+
+/// Instruction asking the model to extend the previous summary with new turns
+fn summary_prompt(previous_summary: Option<&str>, turns: &[Message]) -> String {
+    let mut prompt = String::from(
+        "Summarize the conversation below for your own memory. Keep names, facts, \
+         decisions, open questions and user preferences; drop small talk. \
+         Write at most 150 words in the third person and reply with the summary only.\n\n",
+    );
+
+    if let Some(previous) = previous_summary {
+        prompt.push_str("Summary so far:\n");
+        prompt.push_str(previous);
+        prompt.push_str("\n\n");
+    }
+
+    prompt.push_str("New messages:\n");
+    for message in turns {
+        let speaker = if message.role == "user" { "User" } else { "Assistant" };
+        prompt.push_str(&format!("{}: {}\n", speaker, message.content));
+    }
+
+    prompt
+}
+
+/// Fold old turns of a long conversation into its rolling summary
+///
+/// Does nothing while the unsummarized history still fits comfortably in the
+/// prompt budget. Returns true if a new summary was written.
+pub async fn summarize_if_needed(
+    conversation_id: i64,
+    db: &DatabaseState,
+    llm_engine: &Arc<TokioMutex<LLMEngine>>,
+) -> Result<bool, String> {
+    let (messages, previous) = {
+        let database = db.lock().await;
+        (
+            database.load_messages(conversation_id)?,
+            database.get_conversation_summary(conversation_id)?,
+        )
+    };
+
+    // Only a copy of the engine is used: the user's next prompt must not wait for the
+    // summary, and cancelling that prompt must not cancel the summary instead
+    let mut llm = llm_engine.lock().await.detached();
+
+    let (summary, through_id) = {
+        let pending = unsummarized(&messages, previous.as_ref());
+        let turns = match turns_to_summarize(pending, llm.prompt_token_budget()) {
+            Some(turns) => turns,
+            None => return Ok(false),
+        };
+
+        log::info!(
+            "Summarizing {} older messages of conversation {}",
+            turns.len(),
+            conversation_id
+        );
+
+        // Summaries always use the default assistant, not the conversation's persona
+        llm.set_generation_params(GenerationParams {
+            max_tokens: SUMMARY_MAX_TOKENS,
+            temperature: SUMMARY_TEMPERATURE,
+            ..GenerationParams::default()
+        });
+        let prompt = summary_prompt(previous.as_ref().map(|s| s.summary.as_str()), turns);
+        let summary = llm.generate_response(&prompt).await?;
+
+        let through_id = turns.last().map(|m| m.id).unwrap_or_default();
+        (summary.trim().to_string(), through_id)
+    };
+
+    if summary.is_empty() {
+        return Err("LLM returned an empty summary".to_string());
+    }
+
+    let database = db.lock().await;
+
+    // Another summarizer run may have finished meanwhile; keep whichever covers more
+    let current = database.get_conversation_summary(conversation_id)?;
+    if current.map(|s| s.summarized_through_id >= through_id).unwrap_or(false) {
+        return Ok(false);
+    }
+
+    database.save_conversation_summary(conversation_id, &summary, through_id)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(id: i64, role: &str, content: &str) -> Message {
+        Message {
+            id,
+            conversation_id: 1,
+            role: role.to_string(),
+            content: content.to_string(),
+            timestamp: String::new(),
+        }
+    }
+
+    fn conversation(count: i64, content: &str) -> Vec<Message> {
+        (1..=count)
+            .map(|id| message(id, if id % 2 == 1 { "user" } else { "assistant" }, content))
+            .collect()
+    }
+
+    #[test]
+    fn test_unsummarized_skips_covered_messages() {
+        let messages = conversation(6, "hi");
+        let summary = ConversationSummary {
+            conversation_id: 1,
+            summary: "Greetings.".to_string(),
+            summarized_through_id: 4,
+            updated_at: String::new(),
+        };
+
+        let pending = unsummarized(&messages, Some(&summary));
+        assert_eq!(pending.iter().map(|m| m.id).collect::<Vec<_>>(), vec![5, 6]);
+        assert_eq!(unsummarized(&messages, None).len(), 6);
+    }
+
+    #[test]
+    fn test_turns_to_summarize_waits_for_large_history() {
+        // Short conversation: nothing to do
+        assert!(turns_to_summarize(&conversation(10, "hi"), 2048).is_none());
+
+        // Long turns but only the recent ones: nothing to do
+        let long = "x".repeat(2000);
+        assert!(turns_to_summarize(&conversation(KEEP_RECENT_MESSAGES as i64, &long), 2048).is_none());
+
+        // Large history: everything but the most recent turns is summarized
+        let messages = conversation(10, &long);
+        let turns = turns_to_summarize(&messages, 2048).unwrap();
+        assert_eq!(turns.len(), 10 - KEEP_RECENT_MESSAGES);
+        assert_eq!(turns.last().unwrap().id, 4);
+    }
+
+    #[test]
+    fn test_summary_prompt_includes_previous_summary() {
+        let turns = vec![message(5, "user", "Book the 9am train"), message(6, "assistant", "Done.")];
+
+        let prompt = summary_prompt(Some("The user is travelling to Oslo."), &turns);
+
+        assert!(prompt.contains("Summary so far:\nThe user is travelling to Oslo."));
+        assert!(prompt.contains("User: Book the 9am train\nAssistant: Done.\n"));
+    }
+}
@@ -0,0 +1,427 @@
+//! LLM Tool Calling
+//!
+//! Lets the model act on the user's behalf through OpenAI-style function calling:
+//! - `Tool` trait: a named action with a JSON-schema parameter description
+//! - `ToolRegistry`: the set of tools advertised to the model in the `tools` array
+//! - Built-in tools for Home Assistant services, Spotify playback and web search
+//!
+//! The regex intent parsers (`MusicIntentParser`, `SmartHomeIntentParser`) remain the
+//! fast path for well-formed commands; tools cover the phrasings they miss.
+
+use crate::database::DatabaseState;
+use crate::secrets;
+use crate::spotify_client::{format_currently_playing, format_track_info, SpotifyClient};
+use crate::web_search::{self, SearchBackend};
+use crate::HAClientState;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+/// An action the LLM can invoke via a tool call
+#[async_trait]
+pub trait Tool: Send + Sync {
+    /// Unique function name sent to the model (e.g., "home_assistant_call_service")
+    fn name(&self) -> &str;
+
+    /// Human-readable description the model uses to decide when to call the tool
+    fn description(&self) -> &str;
+
+    /// JSON schema describing the tool's arguments
+    fn parameters(&self) -> Value;
+
+    /// Run the tool with the model-provided arguments and return a textual result
+    async fn execute(&self, arguments: Value) -> Result<String, String>;
+}
+
+/// Tool definition in OpenAI `tools` array format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolDefinition {
+    #[serde(rename = "type")]
+    pub kind: String, // Always "function"
+    pub function: FunctionDefinition,
+}
+
+/// Function signature advertised to the model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionDefinition {
+    pub name: String,
+    pub description: String,
+    pub parameters: Value,
+}
+
+/// Registry of tools available to the LLM
+///
+/// Tools are kept in registration order so the `tools` array is stable
+/// between requests.
+#[derive(Default)]
+pub struct ToolRegistry {
+    tools: Vec<Arc<dyn Tool>>,
+}
+
+impl ToolRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a tool (replaces any existing tool with the same name)
+    pub fn register(&mut self, tool: Arc<dyn Tool>) {
+        log::info!("Registering LLM tool: {}", tool.name());
+        self.tools.retain(|t| t.name() != tool.name());
+        self.tools.push(tool);
+    }
+
+    /// Check if no tools are registered
+    pub fn is_empty(&self) -> bool {
+        self.tools.is_empty()
+    }
+
+    /// Tool definitions for the request's `tools` array
+    pub fn definitions(&self) -> Vec<ToolDefinition> {
+        self.tools
+            .iter()
+            .map(|tool| ToolDefinition {
+                kind: "function".to_string(),
+                function: FunctionDefinition {
+                    name: tool.name().to_string(),
+                    description: tool.description().to_string(),
+                    parameters: tool.parameters(),
+                },
+            })
+            .collect()
+    }
+
+    /// Execute a tool call by name
+    ///
+    /// # Arguments
+    /// * `name` - Function name from the model's tool call
+    /// * `arguments` - JSON-encoded arguments string from the model (may be empty)
+    pub async fn execute(&self, name: &str, arguments: &str) -> Result<String, String> {
+        let tool = self
+            .tools
+            .iter()
+            .find(|t| t.name() == name)
+            .ok_or_else(|| format!("Unknown tool: {}", name))?;
+
+        let arguments: Value = if arguments.trim().is_empty() {
+            json!({})
+        } else {
+            serde_json::from_str(arguments)
+                .map_err(|e| format!("Invalid arguments for tool {}: {}", name, e))?
+        };
+
+        log::info!("Executing tool {} with arguments: {}", name, arguments);
+        tool.execute(arguments).await
+    }
+}
+
+/// Shared tool registry for Tauri state
+pub type ToolRegistryState = Arc<ToolRegistry>;
+
+/// Get a required string argument
+fn required_str<'a>(arguments: &'a Value, key: &str) -> Result<&'a str, String> {
+    arguments
+        .get(key)
+        .and_then(|v| v.as_str())
+        .filter(|v| !v.trim().is_empty())
+        .ok_or_else(|| format!("Missing required argument: {}", key))
+}
+
+// =============================================================================
+// Home Assistant
+// =============================================================================
+
+/// Calls any Home Assistant service (lights, switches, climate, scenes, ...)
+pub struct HomeAssistantServiceTool {
+    ha_client: HAClientState,
+}
+
+impl HomeAssistantServiceTool {
+    pub fn new(ha_client: HAClientState) -> Self {
+        Self { ha_client }
+    }
+}
+
+#[async_trait]
+impl Tool for HomeAssistantServiceTool {
+    fn name(&self) -> &str {
+        "home_assistant_call_service"
+    }
+
+    fn description(&self) -> &str {
+        "Control a smart home device through Home Assistant by calling a service, \
+         e.g. domain 'light' service 'turn_on' on entity 'light.kitchen'."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "domain": {
+                    "type": "string",
+                    "description": "Service domain, e.g. light, switch, climate, scene, script, media_player"
+                },
+                "service": {
+                    "type": "string",
+                    "description": "Service name, e.g. turn_on, turn_off, toggle, set_temperature"
+                },
+                "entity_id": {
+                    "type": "string",
+                    "description": "Target entity ID, e.g. light.living_room"
+                },
+                "data": {
+                    "type": "object",
+                    "description": "Optional service data, e.g. {\"brightness_pct\": 50}"
+                }
+            },
+            "required": ["domain", "service", "entity_id"]
+        })
+    }
+
+    async fn execute(&self, arguments: Value) -> Result<String, String> {
+        let domain = required_str(&arguments, "domain")?;
+        let service = required_str(&arguments, "service")?;
+        let entity_id = required_str(&arguments, "entity_id")?;
+        let data = arguments.get("data").filter(|d| d.is_object()).cloned();
+
+        let client_lock = self.ha_client.lock().await;
+        let client = client_lock
+            .as_ref()
+            .ok_or("Home Assistant is not connected")?;
+
+        client.call_service(domain, service, entity_id, data).await?;
+
+        Ok(format!("Called {}.{} on {}", domain, service, entity_id))
+    }
+}
+
+// =============================================================================
+// Spotify
+// =============================================================================
+
+/// Controls Spotify playback on the global (non user-scoped) account
+pub struct SpotifyPlaybackTool {
+    db: DatabaseState,
+}
+
+impl SpotifyPlaybackTool {
+    pub fn new(db: DatabaseState) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl Tool for SpotifyPlaybackTool {
+    fn name(&self) -> &str {
+        "spotify_playback"
+    }
+
+    fn description(&self) -> &str {
+        "Control Spotify music playback: play a song, pause, resume, skip to the next \
+         or previous track, or report what is currently playing."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["play", "pause", "resume", "next", "previous", "current_track"],
+                    "description": "Playback action to perform"
+                },
+                "query": {
+                    "type": "string",
+                    "description": "Song name to search for (required for 'play')"
+                },
+                "artist": {
+                    "type": "string",
+                    "description": "Optional artist name to narrow the search"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, arguments: Value) -> Result<String, String> {
+        let action = required_str(&arguments, "action")?;
+
+        let client_id = {
+            let database = self.db.lock().await;
+            database.load_settings()?.spotify_client_id
+        };
+
+        if client_id.is_empty() || !secrets::is_spotify_connected() {
+            return Err("Spotify is not connected. Please connect your Spotify account in Settings.".to_string());
+        }
+
+        let client = SpotifyClient::new(client_id).map_err(|e| e.to_string())?;
+
+        match action {
+            "play" => {
+                let query = required_str(&arguments, "query")?;
+                let artist = arguments.get("artist").and_then(|v| v.as_str());
+
+                let tracks = client
+                    .search_track(query, artist, 10)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                let track = tracks
+                    .first()
+                    .ok_or_else(|| format!("No tracks found for '{}'", query))?;
+
+                client.play_track(&track.uri).await.map_err(|e| e.to_string())?;
+                Ok(format!("Now playing {}", format_track_info(track)))
+            }
+            "pause" => {
+                client.pause().await.map_err(|e| e.to_string())?;
+                Ok("Playback paused".to_string())
+            }
+            "resume" => {
+                client.resume().await.map_err(|e| e.to_string())?;
+                Ok("Playback resumed".to_string())
+            }
+            "next" => {
+                client.next().await.map_err(|e| e.to_string())?;
+                Ok("Skipped to the next track".to_string())
+            }
+            "previous" => {
+                client.previous().await.map_err(|e| e.to_string())?;
+                Ok("Went back to the previous track".to_string())
+            }
+            "current_track" => {
+                let current = client.get_current_track().await.map_err(|e| e.to_string())?;
+                Ok(format_currently_playing(&current))
+            }
+            other => Err(format!("Unknown Spotify action: {}", other)),
+        }
+    }
+}
+
+// =============================================================================
+// Web Search
+// =============================================================================
+
+/// Searches the web with the configured backend (only when online mode is enabled)
+pub struct WebSearchTool {
+    db: DatabaseState,
+}
+
+impl WebSearchTool {
+    pub fn new(db: DatabaseState) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl Tool for WebSearchTool {
+    fn name(&self) -> &str {
+        "web_search"
+    }
+
+    fn description(&self) -> &str {
+        "Search the web for current information such as news, weather or facts \
+         that may have changed recently."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Search query"
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    async fn execute(&self, arguments: Value) -> Result<String, String> {
+        let query = required_str(&arguments, "query")?;
+
+        let settings = {
+            let database = self.db.lock().await;
+            database.load_settings()?
+        };
+
+        // Respect the privacy opt-in: never reach the network in offline mode
+        if !settings.online_mode_enabled {
+            return Err("Web search is disabled (online mode is off)".to_string());
+        }
+
+        let backend = SearchBackend::from_settings(&settings)?;
+        let results = web_search::search_web(query, backend, settings.max_search_results as usize)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if results.is_empty() {
+            return Ok(format!("No web results found for '{}'", query));
+        }
+
+        Ok(web_search::format_search_context(&results))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct EchoTool;
+
+    #[async_trait]
+    impl Tool for EchoTool {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn description(&self) -> &str {
+            "Echo the text argument"
+        }
+
+        fn parameters(&self) -> Value {
+            json!({
+                "type": "object",
+                "properties": { "text": { "type": "string" } },
+                "required": ["text"]
+            })
+        }
+
+        async fn execute(&self, arguments: Value) -> Result<String, String> {
+            required_str(&arguments, "text").map(|t| t.to_string())
+        }
+    }
+
+    #[test]
+    fn test_definitions_use_openai_format() {
+        let mut registry = ToolRegistry::new();
+        registry.register(Arc::new(EchoTool));
+
+        let definitions = serde_json::to_value(registry.definitions()).unwrap();
+
+        assert_eq!(definitions[0]["type"], "function");
+        assert_eq!(definitions[0]["function"]["name"], "echo");
+        assert_eq!(definitions[0]["function"]["parameters"]["required"][0], "text");
+    }
+
+    #[test]
+    fn test_register_replaces_same_name() {
+        let mut registry = ToolRegistry::new();
+        registry.register(Arc::new(EchoTool));
+        registry.register(Arc::new(EchoTool));
+
+        assert_eq!(registry.definitions().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_execute_parses_arguments() {
+        let mut registry = ToolRegistry::new();
+        registry.register(Arc::new(EchoTool));
+
+        assert_eq!(registry.execute("echo", r#"{"text":"hello"}"#).await.unwrap(), "hello");
+        assert!(registry.execute("echo", "").await.is_err());
+        assert!(registry.execute("echo", "not json").await.is_err());
+        assert!(registry.execute("missing", "{}").await.is_err());
+    }
+}
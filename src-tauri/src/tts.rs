@@ -1,24 +1,38 @@
-use rodio::Sink;
+use crate::piper_config::{SynthesisOptions, SynthesisParams, VoiceConfig};
+use crate::piper_worker::PiperWorker;
+use crate::playback::{PlaybackController, PlaybackFinished};
+use crate::sentence_splitter::split_sentences;
 use std::io::{Cursor, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
+/// Consecutive worker failures before falling back to one-shot synthesis for good
+const MAX_WORKER_FAILURES: u32 = 3;
+
 /// Text-to-Speech engine for Aura using subprocess-based Piper TTS
 ///
 /// This module handles converting text responses to spoken audio using
 /// the high-quality Piper neural TTS engine via subprocess execution.
 ///
 /// Architecture:
-/// - Spawns piper executable as child process
-/// - Pipes text to stdin
-/// - Captures raw PCM audio from stdout
-/// - Converts PCM to WAV format in-memory using hound
-/// - Plays audio through speakers using rodio
+/// - Splits the text into sentences
+/// - Sends each sentence to a persistent piper process (see `piper_worker`), which is
+///   restarted if it crashes and returns each sentence's audio as a scratch WAV file
+/// - Falls back to one piper process per sentence (text on stdin, raw PCM audio on
+///   stdout) if the worker keeps failing
+/// - Converts PCM to WAV format in-memory using hound, at the sample rate from the
+///   voice's config (see `piper_config`)
+/// - Appends each sentence to an utterance in the `PlaybackController` queue while
+///   the next is synthesized
 /// - 100% offline, stable subprocess architecture
 pub struct TextToSpeech {
     piper_path: PathBuf,
     model_path: PathBuf,
     espeak_data_path: PathBuf,
+    config: VoiceConfig,
+    player: PlaybackController,
+    worker: Option<PiperWorker>,
+    worker_failures: u32,
 }
 
 impl TextToSpeech {
@@ -28,6 +42,7 @@ impl TextToSpeech {
     /// * `piper_path` - Path to the piper executable binary
     /// * `model_path` - Path to the Piper voice model (.onnx file)
     /// * `espeak_data_path` - Path to the espeak-ng data directory
+    /// * `player` - Playback queue the synthesized audio goes to
     ///
     /// # Returns
     /// A configured TTS engine ready for speech synthesis
@@ -36,9 +51,14 @@ impl TextToSpeech {
     /// Returns error if:
     /// - Piper binary doesn't exist
     /// - Model file doesn't exist
-    /// - Model config (.json) doesn't exist
+    /// - Model config (.json) doesn't exist or is invalid
     /// - eSpeak-NG data directory doesn't exist
-    pub fn new(piper_path: PathBuf, model_path: PathBuf, espeak_data_path: PathBuf) -> Result<Self, String> {
+    pub fn new(
+        piper_path: PathBuf,
+        model_path: PathBuf,
+        espeak_data_path: PathBuf,
+        player: PlaybackController,
+    ) -> Result<Self, String> {
         log::info!("Initializing subprocess-based Piper TTS engine...");
         log::info!("  Piper binary: {:?}", piper_path);
         log::info!("  Voice model: {:?}", model_path);
@@ -84,45 +104,169 @@ impl TextToSpeech {
             ));
         }
 
+        let config = VoiceConfig::load(&config_path)?;
+
         log::info!("  Config file: {:?}", config_path);
+        log::info!("  Sample rate: {} Hz, {} speaker(s)", config.sample_rate, config.num_speakers);
         log::info!("  eSpeak-NG data: {:?}", espeak_data_path);
+
+        // Start the worker now so the voice is loaded before the first answer
+        let default_params = config.resolve(&SynthesisOptions::default());
+        let worker = PiperWorker::spawn(&piper_path, &model_path, &espeak_data_path, default_params)
+            .map_err(|e| log::warn!("Piper worker unavailable, will retry on first use: {}", e))
+            .ok();
+
         log::info!("✓ Subprocess-based Piper TTS engine initialized successfully");
-        log::info!("  - Using piper binary for synthesis");
+        log::info!("  - Using persistent piper worker for synthesis");
         log::info!("  - 100% offline, stable subprocess architecture");
 
         Ok(TextToSpeech {
             piper_path,
             model_path,
             espeak_data_path,
+            config,
+            player,
+            worker,
+            worker_failures: 0,
         })
     }
 
     /// Speak the given text using subprocess-based Piper synthesis
     ///
-    /// This spawns the piper executable, pipes the text to stdin,
-    /// captures raw PCM audio from stdout, converts to WAV format,
-    /// and plays through the default audio device.
+    /// The text is split into sentences (see `sentence_splitter`) which are
+    /// synthesized one at a time, so playback starts after the first sentence
+    /// instead of after the whole text.
     ///
     /// # Arguments
     /// * `text` - The text to speak
+    /// * `options` - The listener's speaker and speech settings
     ///
     /// # Returns
-    /// Ok(()) if synthesis and playback succeeded, Err with details if failed
-    pub fn speak(&mut self, text: &str) -> Result<(), String> {
-        if text.is_empty() {
+    /// A handle to wait for the end of playback once synthesis succeeded, Err with
+    /// details if it failed
+    pub fn speak(&mut self, text: &str, options: &SynthesisOptions) -> Result<PlaybackFinished, String> {
+        if text.trim().is_empty() {
             return Err("Cannot speak empty text".to_string());
         }
 
         log::info!(
             "Synthesizing speech: '{}' ({} chars)",
             if text.len() > 50 {
-                format!("{}...", &text[..50])
+                format!("{}...", text.chars().take(50).collect::<String>())
             } else {
                 text.to_string()
             },
             text.len()
         );
 
+        self.speak_sentences(split_sentences(text), options)
+    }
+
+    /// Speak sentences as they become available
+    ///
+    /// The sentences form one utterance in the playback queue; each is synthesized
+    /// and appended while the previous one is still playing. `sentences` may block
+    /// between items (e.g., a channel fed by a streaming LLM response). Returns once
+    /// the last sentence is synthesized, or early if the utterance is skipped or
+    /// stopped (including barge-in); the returned handle resolves when it has played.
+    ///
+    /// A sentence that fails to synthesize is skipped; the error is only returned if
+    /// nothing could be spoken at all.
+    pub fn speak_sentences(
+        &mut self,
+        sentences: impl IntoIterator<Item = String>,
+        options: &SynthesisOptions,
+    ) -> Result<PlaybackFinished, String> {
+        let params = self.config.resolve(options);
+        let sample_rate = self.config.sample_rate;
+        let utterance = self.player.enqueue()?;
+
+        let mut spoken = 0;
+        let mut last_error = None;
+        for sentence in sentences {
+            let (samples, source) = match self.synthesize(&sentence, &params).and_then(|samples| {
+                // Decode WAV from memory
+                let source = rodio::Decoder::new(Cursor::new(self.create_wav(&samples)?))
+                    .map_err(|e| format!("Failed to decode WAV audio: {}", e))?;
+                Ok((samples, source))
+            }) {
+                Ok(audio) => audio,
+                Err(e) => {
+                    log::error!("Failed to synthesize sentence '{}': {}", sentence, e);
+                    last_error = Some(e);
+                    continue;
+                }
+            };
+
+            // Skipped or stopped while this sentence was being synthesized
+            let normalized: Vec<f32> = samples.iter().map(|&s| s as f32 / i16::MAX as f32).collect();
+            if !utterance.append(source, &normalized, sample_rate) {
+                log::info!("Playback stopped, skipping remaining sentences");
+                break;
+            }
+
+            spoken += 1;
+            log::debug!("Queued sentence {} ({} samples)", spoken, samples.len());
+        }
+
+        let finished = utterance.finish();
+        match last_error {
+            Some(e) if spoken == 0 => Err(e),
+            _ => {
+                log::info!("Finished synthesizing ({} sentences)", spoken);
+                Ok(finished)
+            }
+        }
+    }
+
+    /// Synthesize one piece of text, preferably with the persistent worker
+    fn synthesize(&mut self, text: &str, params: &SynthesisParams) -> Result<Vec<i16>, String> {
+        if self.worker_failures < MAX_WORKER_FAILURES {
+            match self.synthesize_with_worker(text, params) {
+                Ok(samples) => {
+                    self.worker_failures = 0;
+                    return Ok(samples);
+                }
+                Err(e) => {
+                    // Dropping the worker kills it; the next sentence starts a new one
+                    self.worker = None;
+                    self.worker_failures += 1;
+                    log::warn!(
+                        "Piper worker failed ({}/{}): {}, using one-shot synthesis",
+                        self.worker_failures, MAX_WORKER_FAILURES, e
+                    );
+                    if self.worker_failures == MAX_WORKER_FAILURES {
+                        log::warn!("Piper worker keeps failing, disabled until the voice is reloaded");
+                    }
+                }
+            }
+        }
+
+        self.synthesize_once(text, params)
+    }
+
+    /// Synthesize with the persistent worker, (re)starting it if needed
+    fn synthesize_with_worker(&mut self, text: &str, params: &SynthesisParams) -> Result<Vec<i16>, String> {
+        if self.worker.as_mut().is_some_and(|worker| !worker.is_running()) {
+            log::warn!("Piper worker exited, restarting");
+            self.worker = None;
+        }
+        if self.worker.as_ref().is_some_and(|worker| !worker.serves(params)) {
+            log::info!("Speech settings changed, restarting piper worker");
+            self.worker = None;
+        }
+
+        let worker = match &mut self.worker {
+            Some(worker) => worker,
+            None => self.worker.insert(PiperWorker::spawn(&self.piper_path, &self.model_path, &self.espeak_data_path, *params)?),
+        };
+        worker.synthesize(text, params.speaker_id)
+    }
+
+    /// Synthesize one piece of text with a one-shot piper subprocess
+    ///
+    /// Pipes the text to stdin and captures the raw 16-bit PCM audio from stdout.
+    fn synthesize_once(&self, text: &str, params: &SynthesisParams) -> Result<Vec<i16>, String> {
         // Spawn piper subprocess
         log::debug!("Spawning piper subprocess...");
 
@@ -137,10 +281,14 @@ impl TextToSpeech {
             .arg("--output-raw")  // Output raw PCM instead of WAV
             .arg("--espeak_data")
             .arg(&self.espeak_data_path)
+            .args(params.process_args())
             .env("LD_LIBRARY_PATH", piper_dir) // Add library path for bundled .so files
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
+        if let Some(speaker_id) = params.speaker_id {
+            cmd.arg("--speaker").arg(speaker_id.to_string());
+        }
 
         let mut child = cmd.spawn()
             .map_err(|e| format!("Failed to spawn piper process: {}", e))?;
@@ -190,15 +338,7 @@ impl TextToSpeech {
             samples.push(sample);
         }
 
-        // Create in-memory WAV file using hound
-        log::debug!("Converting PCM to WAV format...");
-        let wav_data = self.create_wav(&samples)?;
-
-        // Play the synthesized audio
-        self.play_audio(&wav_data)?;
-
-        log::info!("Finished speaking");
-        Ok(())
+        Ok(samples)
     }
 
     /// Convert PCM samples to WAV format in-memory
@@ -211,10 +351,9 @@ impl TextToSpeech {
     fn create_wav(&self, samples: &[i16]) -> Result<Vec<u8>, String> {
         let mut wav_buffer = Cursor::new(Vec::new());
 
-        // Piper typically outputs at 22050 Hz, mono, 16-bit
         let spec = hound::WavSpec {
             channels: 1,
-            sample_rate: 22050,
+            sample_rate: self.config.sample_rate,
             bits_per_sample: 16,
             sample_format: hound::SampleFormat::Int,
         };
@@ -235,35 +374,29 @@ impl TextToSpeech {
         Ok(wav_buffer.into_inner())
     }
 
-    /// Play WAV audio data through the default audio device
-    ///
-    /// # Arguments
-    /// * `wav_data` - In-memory WAV file data
-    ///
-    /// # Returns
-    /// Ok(()) if playback succeeded, Err with details if failed
-    fn play_audio(&self, wav_data: &[u8]) -> Result<(), String> {
-        log::debug!("Initializing audio playback...");
-
-        // Get default audio output device (rodio 0.21 API)
-        let stream_handle = rodio::OutputStreamBuilder::open_default_stream()
-            .map_err(|e| format!("Failed to open audio output device: {}", e))?;
-
-        // Create audio sink for playback (rodio 0.21 API)
-        let sink = Sink::connect_new(stream_handle.mixer());
-
-        // Decode WAV from memory (clone data to give it 'static lifetime)
-        let cursor = Cursor::new(wav_data.to_vec());
-        let source = rodio::Decoder::new(cursor)
-            .map_err(|e| format!("Failed to decode WAV audio: {}", e))?;
-
-        log::debug!("Audio config: playing WAV file");
+    /// Sample rate, speakers and default settings of the loaded voice
+    pub fn voice_config(&self) -> &VoiceConfig {
+        &self.config
+    }
 
-        // Play and wait for completion
-        sink.append(source);
-        sink.sleep_until_end();
+    /// Path of the loaded voice model (.onnx file)
+    pub fn model_path(&self) -> &Path {
+        &self.model_path
+    }
 
-        log::debug!("Audio playback complete");
+    /// Load a different voice model
+    ///
+    /// The new voice is loaded (and its worker started) before the current one is
+    /// dropped, so on error the current voice stays active. Audio that is already
+    /// queued keeps playing.
+    pub fn switch_voice(&mut self, model_path: PathBuf) -> Result<(), String> {
+        log::info!("Switching TTS voice to {:?}", model_path);
+        *self = Self::new(
+            self.piper_path.clone(),
+            model_path,
+            self.espeak_data_path.clone(),
+            self.player.clone(),
+        )?;
         Ok(())
     }
 
@@ -0,0 +1,354 @@
+//! Voice Activity Detection
+//!
+//! Decides per audio frame whether the user is speaking, for end-of-speech detection
+//! while recording. Implementations behind the `VoiceActivityDetector` trait:
+//! - `EnergyVad` (default): RMS energy against the VAD sensitivity threshold, also
+//!   reported as a speech probability
+//! - `SileroVad`: Silero VAD neural model via sherpa-onnx, which ignores steady
+//!   background noise (fans, air conditioning) that keeps the energy VAD open.
+//!   sherpa-onnx only exposes the model's speech/non-speech decision, so Silero
+//!   frames carry no probability.
+//!
+//! Selected by the `vad_engine` setting ("energy" or "silero").
+
+use serde::Serialize;
+use sherpa_rs::silero_vad::{SileroVad as SherpaSileroVad, SileroVadConfig};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Silero VAD model file (inside the models directory)
+pub const SILERO_MODEL_FILE: &str = "silero_vad.onnx";
+const SILERO_MODEL_URL: &str = "https://github.com/k2-fsa/sherpa-onnx/releases/download/asr-models/silero_vad.onnx";
+
+/// Frames with a speech probability above this count as speech (Silero applies the
+/// same threshold internally)
+pub const SPEECH_THRESHOLD: f32 = 0.5;
+
+const SAMPLE_RATE: u32 = 16000;
+const FRAME_SAMPLES: usize = 512;        // 32ms at 16kHz, same as the capture chunk size
+const MIN_SEGMENT_MS: u32 = 100;         // Shorter bursts (clicks, taps) aren't speech segments
+
+/// A detector's verdict on one frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameDecision {
+    pub is_speech: bool,
+    /// Probability (0.0-1.0) that the frame contains speech, for detectors that have one
+    pub probability: Option<f32>,
+}
+
+/// Detects speech in 16 kHz mono audio frames
+pub trait VoiceActivityDetector: Send {
+    /// Short name for logs (e.g., "silero")
+    fn name(&self) -> &'static str;
+
+    /// Decide whether the next frame contains speech
+    fn analyze_frame(&mut self, frame: &[f32]) -> FrameDecision;
+
+    /// Forget state from previous audio (called when a new recording starts)
+    fn reset(&mut self);
+}
+
+/// Create the VAD selected by the `vad_engine` setting
+///
+/// Falls back to the energy VAD if the Silero model is missing or fails to load.
+pub fn create_vad(engine: &str, model_path: &Path, vad_sensitivity: Arc<Mutex<f32>>) -> Box<dyn VoiceActivityDetector> {
+    match engine {
+        "silero" => {
+            let model_file = model_path.join(SILERO_MODEL_FILE);
+            match SileroVad::new(&model_file) {
+                Ok(vad) => return Box::new(vad),
+                Err(e) => {
+                    log::warn!("Silero VAD unavailable ({}), using energy-based VAD", e);
+                    log::warn!("Download the model: wget {} -O {:?}", SILERO_MODEL_URL, model_file);
+                }
+            }
+        }
+        "energy" => {}
+        other => log::warn!("Unknown VAD engine '{}', using energy-based VAD", other),
+    }
+
+    Box::new(EnergyVad::new(vad_sensitivity))
+}
+
+/// Energy-based VAD: RMS energy relative to the sensitivity threshold
+///
+/// Energy at the threshold maps to `SPEECH_THRESHOLD`, so a frame counts as speech
+/// exactly when its energy exceeds the configured sensitivity.
+pub struct EnergyVad {
+    vad_sensitivity: Arc<Mutex<f32>>,
+}
+
+impl EnergyVad {
+    pub fn new(vad_sensitivity: Arc<Mutex<f32>>) -> Self {
+        Self { vad_sensitivity }
+    }
+
+    /// Frame energy scaled to a probability (0.0-1.0)
+    pub fn speech_probability(&self, frame: &[f32]) -> f32 {
+        let threshold = self.vad_sensitivity.lock().map(|s| *s).unwrap_or(0.02);
+        if threshold <= 0.0 {
+            return 1.0;
+        }
+
+        (calculate_rms_energy(frame) / threshold * SPEECH_THRESHOLD).clamp(0.0, 1.0)
+    }
+}
+
+impl VoiceActivityDetector for EnergyVad {
+    fn name(&self) -> &'static str {
+        "energy"
+    }
+
+    fn analyze_frame(&mut self, frame: &[f32]) -> FrameDecision {
+        let probability = self.speech_probability(frame);
+        FrameDecision {
+            is_speech: probability > SPEECH_THRESHOLD,
+            probability: Some(probability),
+        }
+    }
+
+    fn reset(&mut self) {}
+}
+
+/// Silero VAD via sherpa-onnx
+///
+/// sherpa-onnx only exposes the model's speech/non-speech decision (made with
+/// `SPEECH_THRESHOLD`), not its probability, so `analyze_frame` reports none.
+pub struct SileroVad {
+    vad: SherpaSileroVad,
+}
+
+impl SileroVad {
+    pub fn new(model_file: &Path) -> Result<Self, String> {
+        if !model_file.exists() {
+            return Err(format!("model not found: {:?}", model_file));
+        }
+
+        let config = SileroVadConfig {
+            model: model_file.to_string_lossy().to_string(),
+            threshold: SPEECH_THRESHOLD,
+            // End-of-speech timing is left to the pipeline's silence timeout
+            min_silence_duration: 0.1,
+            min_speech_duration: 0.1,
+            max_speech_duration: 30.0,
+            sample_rate: SAMPLE_RATE,
+            window_size: FRAME_SAMPLES as i32,
+            provider: Some("cpu".to_string()),
+            num_threads: Some(1),
+            debug: false,
+        };
+
+        let vad = SherpaSileroVad::new(config, 30.0)
+            .map_err(|e| format!("failed to load Silero VAD: {:?}", e))?;
+
+        log::info!("✓ Silero VAD loaded from {:?}", model_file);
+        Ok(Self { vad })
+    }
+}
+
+impl VoiceActivityDetector for SileroVad {
+    fn name(&self) -> &'static str {
+        "silero"
+    }
+
+    fn analyze_frame(&mut self, frame: &[f32]) -> FrameDecision {
+        self.vad.accept_waveform(frame.to_vec());
+        let is_speech = self.vad.is_speech();
+
+        // Completed segments aren't needed, only the per-frame decision
+        while !self.vad.is_empty() {
+            self.vad.pop();
+        }
+
+        FrameDecision {
+            is_speech,
+            probability: None,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.vad.clear();
+    }
+}
+
+/// A stretch of speech within a recording
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SpeechSegment {
+    pub start_seconds: f32,
+    pub end_seconds: f32,
+}
+
+/// Split a 16 kHz mono recording into speech segments
+///
+/// A segment ends after `min_silence_ms` without speech (the same rule the pipeline
+/// uses to end a recording); its end is the last speech frame. Segments shorter than
+/// `MIN_SEGMENT_MS` are dropped.
+pub fn detect_segments(vad: &mut dyn VoiceActivityDetector, samples: &[f32], min_silence_ms: u32) -> Vec<SpeechSegment> {
+    let frame_seconds = FRAME_SAMPLES as f32 / SAMPLE_RATE as f32;
+    let silence_frames = (min_silence_ms as f32 / 1000.0 / frame_seconds).round().max(1.0) as usize;
+
+    let mut segments = Vec::new();
+    let mut current: Option<(usize, usize)> = None; // (first speech frame, last speech frame)
+
+    let close = |first: usize, last: usize, segments: &mut Vec<SpeechSegment>| {
+        let segment = SpeechSegment {
+            start_seconds: first as f32 * frame_seconds,
+            end_seconds: ((last + 1) * FRAME_SAMPLES).min(samples.len()) as f32 / SAMPLE_RATE as f32,
+        };
+        if (segment.end_seconds - segment.start_seconds) * 1000.0 >= MIN_SEGMENT_MS as f32 {
+            segments.push(segment);
+        }
+    };
+
+    vad.reset();
+    for (index, frame) in samples.chunks(FRAME_SAMPLES).enumerate() {
+        let speech = vad.analyze_frame(frame).is_speech;
+
+        current = match (current, speech) {
+            (None, true) => Some((index, index)),
+            (Some((first, _)), true) => Some((first, index)),
+            (Some((first, last)), false) if index - last >= silence_frames => {
+                close(first, last, &mut segments);
+                None
+            }
+            (current, _) => current,
+        };
+    }
+
+    if let Some((first, last)) = current {
+        close(first, last, &mut segments);
+    }
+
+    segments
+}
+
+/// Calculate RMS energy of audio samples
+fn calculate_rms_energy(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+
+    let sum_squares: f32 = samples.iter().map(|&s| s * s).sum();
+    (sum_squares / samples.len() as f32).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// Boundaries tolerance: a couple of frames plus the quiet edges of syllables
+    const TOLERANCE_SECONDS: f32 = 0.15;
+
+    /// Bursts in the synthetic fixtures: two voiced-like tone bursts separated by 0.8s
+    /// of silence, once clean and once over low-frequency fan-like noise. They check
+    /// the energy VAD's segmentation, not how a detector handles real speech.
+    const SYNTHETIC_BURSTS: [(f32, f32); 2] = [(0.5, 1.7), (2.5, 3.5)];
+
+    fn fixture_path(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/vad").join(name)
+    }
+
+    fn fixture(name: &str) -> Vec<f32> {
+        let path = fixture_path(name);
+        let mut reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.spec().sample_rate, SAMPLE_RATE);
+        reader
+            .samples::<i16>()
+            .map(|s| s.unwrap() as f32 / i16::MAX as f32)
+            .collect()
+    }
+
+    /// Hand-labelled speech of a recording: `<start> <end>` seconds per line in
+    /// `<name>.labels`
+    fn labels(name: &str) -> Vec<(f32, f32)> {
+        let path = fixture_path(name).with_extension("labels");
+        std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("Failed to read {:?}: {}", path, e))
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let mut bounds = line.split_whitespace().map(|value| value.parse::<f32>().unwrap());
+                (bounds.next().unwrap(), bounds.next().unwrap())
+            })
+            .collect()
+    }
+
+    fn boundaries(segments: &[SpeechSegment]) -> Vec<(f32, f32)> {
+        segments.iter().map(|s| (s.start_seconds, s.end_seconds)).collect()
+    }
+
+    fn assert_boundaries(actual: &[SpeechSegment], expected: &[(f32, f32)]) {
+        assert_eq!(actual.len(), expected.len(), "segments: {:?}", boundaries(actual));
+        for (segment, (start, end)) in actual.iter().zip(expected) {
+            assert!(
+                (segment.start_seconds - start).abs() <= TOLERANCE_SECONDS
+                    && (segment.end_seconds - end).abs() <= TOLERANCE_SECONDS,
+                "segment {:?} doesn't match expected ({}, {})",
+                segment,
+                start,
+                end
+            );
+        }
+    }
+
+    fn energy_vad() -> EnergyVad {
+        EnergyVad::new(Arc::new(Mutex::new(0.02)))
+    }
+
+    #[test]
+    fn test_energy_vad_finds_utterances() {
+        let segments = detect_segments(&mut energy_vad(), &fixture("synthetic_bursts.wav"), 500);
+        assert_boundaries(&segments, &SYNTHETIC_BURSTS);
+    }
+
+    #[test]
+    fn test_energy_vad_stays_open_in_fan_noise() {
+        // The steady noise floor sits above the energy threshold, so the energy VAD
+        // never sees the pauses; this is what the Silero VAD is for
+        let samples = fixture("synthetic_bursts_fan_noise.wav");
+        let segments = detect_segments(&mut energy_vad(), &samples, 500);
+
+        assert_eq!(segments.len(), 1);
+        assert!(segments[0].end_seconds >= samples.len() as f32 / SAMPLE_RATE as f32 - TOLERANCE_SECONDS);
+    }
+
+    #[test]
+    fn test_energy_vad_probability_scale() {
+        let mut vad = energy_vad();
+        assert_eq!(vad.speech_probability(&[0.0; FRAME_SAMPLES]), 0.0);
+        assert!((vad.speech_probability(&[0.02; FRAME_SAMPLES]) - SPEECH_THRESHOLD).abs() < 1e-3);
+        assert_eq!(
+            vad.analyze_frame(&[0.5; FRAME_SAMPLES]),
+            FrameDecision { is_speech: true, probability: Some(1.0) }
+        );
+    }
+
+    #[test]
+    fn test_short_bursts_are_not_segments() {
+        let mut samples = vec![0.0; SAMPLE_RATE as usize];
+        samples[8000..8512].iter_mut().for_each(|s| *s = 0.3);
+
+        assert!(detect_segments(&mut energy_vad(), &samples, 500).is_empty());
+    }
+
+    /// Runs only with the Silero model at `$AURA_SILERO_VAD_MODEL` (see `SILERO_MODEL_URL`),
+    /// on the recorded speech fixtures (see `tests/fixtures/vad/README.md`)
+    #[test]
+    fn test_silero_vad_matches_energy_vad_and_ignores_noise() {
+        let Ok(model) = std::env::var("AURA_SILERO_VAD_MODEL") else {
+            eprintln!("AURA_SILERO_VAD_MODEL not set, skipping the Silero VAD test");
+            return;
+        };
+        let mut silero = SileroVad::new(Path::new(&model)).unwrap();
+
+        let clean = detect_segments(&mut silero, &fixture("speech_clean.wav"), 500);
+        assert_boundaries(&clean, &labels("speech_clean.wav"));
+        let energy = detect_segments(&mut energy_vad(), &fixture("speech_clean.wav"), 500);
+        assert_boundaries(&clean, &boundaries(&energy));
+
+        silero.reset();
+        let noisy = detect_segments(&mut silero, &fixture("speech_fan_noise.wav"), 500);
+        assert_boundaries(&noisy, &labels("speech_fan_noise.wav"));
+    }
+}
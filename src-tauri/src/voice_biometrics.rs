@@ -37,6 +37,14 @@ pub struct UserProfile {
     pub updated_at: String,
 }
 
+/// A recognized speaker and how closely the audio matched their voice print
+#[derive(Debug, Clone)]
+pub struct SpeakerMatch {
+    pub profile: UserProfile,
+    /// Cosine similarity to the voice print (at least `RECOGNITION_THRESHOLD`)
+    pub similarity: f32,
+}
+
 /// Voice biometrics error types
 #[derive(Debug, thiserror::Error)]
 pub enum BiometricsError {
@@ -187,11 +195,11 @@ impl VoiceBiometrics {
     /// * `audio` - Audio recording (PCM f32 samples at 16kHz)
     ///
     /// # Returns
-    /// Matched user profile if similarity exceeds threshold, None otherwise
+    /// Matched user profile and its similarity if it exceeds the threshold, None otherwise
     pub async fn identify_speaker(
         &self,
         audio: &[f32],
-    ) -> Result<Option<UserProfile>, BiometricsError> {
+    ) -> Result<Option<SpeakerMatch>, BiometricsError> {
         // Ensure model is loaded
         if !self.is_model_loaded().await {
             return Err(BiometricsError::ModelNotLoaded);
@@ -233,7 +241,10 @@ impl VoiceBiometrics {
                 log::info!("✓ Speaker identified: {} (similarity: {:.3})",
                            profile.name, best_similarity);
 
-                Ok(Some(profile))
+                Ok(Some(SpeakerMatch {
+                    profile,
+                    similarity: best_similarity,
+                }))
             } else {
                 Ok(None)
             }
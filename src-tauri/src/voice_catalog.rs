@@ -0,0 +1,363 @@
+//! Piper Voice Catalog
+//!
+//! A Piper voice is a `<id>.onnx` model with its `<id>.onnx.json` config. The catalog
+//! lists the voices found in two directories:
+//! - The bundled voices shipped with Aura (read-only)
+//! - The user's voices directory (`<data dir>/nivora-aura/voices`), where installed
+//!   voices go; a voice installed there overrides a bundled voice with the same id
+//!
+//! Voices are installed from a `.tar.gz`/`.tgz`/`.tar` archive containing one voice,
+//! or from a `.onnx` model with its `.onnx.json` next to it. The files are checked
+//! (SHA-256 of the archive, or of both model and config, if given; and the config must
+//! parse) in a staging directory before they are moved into place, so a broken
+//! download never shows up in the catalog.
+
+use crate::piper_config::VoiceConfig;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+pub type VoiceCatalogState = Arc<VoiceCatalog>;
+
+/// Distinguishes the staging and download directories of concurrent installs
+static NEXT_INSTALL: AtomicU64 = AtomicU64::new(1);
+
+/// A new directory name for one install's downloads (not created)
+pub fn download_dir() -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "nivora-aura-voice-{}-{}",
+        std::process::id(),
+        NEXT_INSTALL.fetch_add(1, Ordering::SeqCst)
+    ))
+}
+
+/// An installed voice
+#[derive(Debug, Clone, Serialize)]
+pub struct VoiceInfo {
+    /// File name of the model without `.onnx`, e.g. "en_US-lessac-medium"
+    pub id: String,
+    pub language: Option<String>,
+    pub quality: Option<String>,
+    pub sample_rate: u32,
+    pub num_speakers: u32,
+    /// Shipped with the app (can't be replaced in place)
+    pub bundled: bool,
+    pub model_path: PathBuf,
+}
+
+/// The voices directories
+pub struct VoiceCatalog {
+    bundled_dir: Option<PathBuf>,
+    user_dir: PathBuf,
+}
+
+impl VoiceCatalog {
+    pub fn new(bundled_dir: Option<PathBuf>, user_dir: PathBuf) -> Self {
+        // In a system install the bundled voices may already live in the user dir
+        let bundled_dir = bundled_dir.filter(|dir| *dir != user_dir);
+        Self { bundled_dir, user_dir }
+    }
+
+    /// Directory installed voices go to
+    pub fn user_dir(&self) -> &Path {
+        &self.user_dir
+    }
+
+    /// All usable voices, sorted by id
+    ///
+    /// Models without a readable config are skipped (and logged).
+    pub fn list(&self) -> Vec<VoiceInfo> {
+        let mut voices = BTreeMap::new();
+        if let Some(bundled_dir) = &self.bundled_dir {
+            for voice in scan_dir(bundled_dir, true) {
+                voices.insert(voice.id.clone(), voice);
+            }
+        }
+        for voice in scan_dir(&self.user_dir, false) {
+            voices.insert(voice.id.clone(), voice);
+        }
+        voices.into_values().collect()
+    }
+
+    pub fn find(&self, id: &str) -> Option<VoiceInfo> {
+        self.list().into_iter().find(|voice| voice.id == id)
+    }
+
+    /// Install a voice from an archive or a `.onnx` model into the user directory
+    ///
+    /// # Arguments
+    /// * `source` - `.tar.gz`, `.tgz` or `.tar` archive, or `.onnx` model with its
+    ///   `.onnx.json` alongside
+    /// * `sha256` - Expected SHA-256 (hex) of `source`
+    /// * `config_sha256` - Expected SHA-256 of the `.onnx.json` (`.onnx` models only)
+    ///
+    /// # Returns
+    /// The installed voice; an already installed voice with the same id is replaced
+    pub fn install(
+        &self,
+        source: &Path,
+        sha256: Option<&str>,
+        config_sha256: Option<&str>,
+    ) -> Result<VoiceInfo, String> {
+        log::info!("Installing Piper voice from {:?}", source);
+
+        if let Some(expected) = sha256 {
+            verify_sha256(source, expected)?;
+        }
+        if let Some(expected) = config_sha256 {
+            if source.extension().and_then(|ext| ext.to_str()) != Some("onnx") {
+                return Err("A config checksum only applies to .onnx models".to_string());
+            }
+            verify_sha256(&source.with_extension("onnx.json"), expected)?;
+        }
+
+        std::fs::create_dir_all(&self.user_dir)
+            .map_err(|e| format!("Failed to create voices directory: {}", e))?;
+
+        // Staged inside the voices directory so the final move is a rename
+        let staging = self.user_dir.join(format!(
+            ".install-{}-{}",
+            std::process::id(),
+            NEXT_INSTALL.fetch_add(1, Ordering::SeqCst)
+        ));
+        std::fs::create_dir_all(&staging)
+            .map_err(|e| format!("Failed to create staging directory: {}", e))?;
+
+        let result = self.install_staged(source, &staging);
+        let _ = std::fs::remove_dir_all(&staging);
+
+        let voice = result?;
+        log::info!("✓ Installed Piper voice '{}' ({:?})", voice.id, voice.model_path);
+        Ok(voice)
+    }
+
+    fn install_staged(&self, source: &Path, staging: &Path) -> Result<VoiceInfo, String> {
+        let name = source
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            let file = File::open(source).map_err(|e| format!("Failed to open {:?}: {}", source, e))?;
+            unpack(tar::Archive::new(flate2::read::GzDecoder::new(file)), staging)?;
+        } else if name.ends_with(".tar") {
+            let file = File::open(source).map_err(|e| format!("Failed to open {:?}: {}", source, e))?;
+            unpack(tar::Archive::new(file), staging)?;
+        } else if name.ends_with(".onnx") {
+            let config = source.with_extension("onnx.json");
+            for file in [source, config.as_path()] {
+                let file_name = file.file_name().ok_or("Invalid model path")?;
+                std::fs::copy(file, staging.join(file_name))
+                    .map_err(|e| format!("Failed to copy {:?}: {}", file, e))?;
+            }
+        } else {
+            return Err(format!(
+                "Unsupported voice file {:?}: expected .tar.gz, .tgz, .tar or .onnx",
+                source
+            ));
+        }
+
+        let staged = scan_dir(staging, false);
+        let voice = match staged.as_slice() {
+            [voice] => voice,
+            [] => return Err("No Piper voice (.onnx with .onnx.json) found".to_string()),
+            _ => return Err(format!("Expected one voice, found {}", staged.len())),
+        };
+
+        // Config first: a model without its config isn't listed
+        let model_path = self.user_dir.join(format!("{}.onnx", voice.id));
+        for path in [model_path.with_extension("onnx.json"), model_path.clone()] {
+            let file_name = path.file_name().ok_or("Invalid voice id")?;
+            std::fs::rename(staging.join(file_name), &path)
+                .map_err(|e| format!("Failed to install {:?}: {}", path, e))?;
+        }
+
+        Ok(VoiceInfo {
+            bundled: false,
+            model_path,
+            ..voice.clone()
+        })
+    }
+}
+
+/// Voices in one directory (not recursive)
+fn scan_dir(dir: &Path, bundled: bool) -> Vec<VoiceInfo> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut voices = Vec::new();
+    for path in entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+        if path.extension().and_then(|ext| ext.to_str()) != Some("onnx") || !path.is_file() {
+            continue;
+        }
+        let Some(id) = path.file_stem().map(|stem| stem.to_string_lossy().to_string()) else {
+            continue;
+        };
+
+        match VoiceConfig::load(&path.with_extension("onnx.json")) {
+            Ok(config) => voices.push(VoiceInfo {
+                id,
+                language: config.language,
+                quality: config.quality,
+                sample_rate: config.sample_rate,
+                num_speakers: config.num_speakers,
+                bundled,
+                model_path: path,
+            }),
+            Err(e) => log::warn!("Skipping voice {:?}: {}", path, e),
+        }
+    }
+    voices
+}
+
+/// Extract the voice files of an archive into `dest`
+///
+/// Only `.onnx` and `.onnx.json` files are extracted, by file name (directories in
+/// the archive are ignored, and with them any `..` in entry paths).
+fn unpack<R: Read>(mut archive: tar::Archive<R>, dest: &Path) -> Result<(), String> {
+    let entries = archive.entries().map_err(|e| format!("Failed to read archive: {}", e))?;
+    for entry in entries {
+        let mut entry = entry.map_err(|e| format!("Failed to read archive: {}", e))?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let path = entry.path().map_err(|e| format!("Failed to read archive: {}", e))?;
+        let Some(file_name) = path.file_name().map(|name| name.to_string_lossy().to_string()) else {
+            continue;
+        };
+        // Skips e.g. macOS `._voice.onnx` resource forks
+        if file_name.starts_with('.') || !(file_name.ends_with(".onnx") || file_name.ends_with(".onnx.json")) {
+            continue;
+        }
+
+        entry
+            .unpack(dest.join(&file_name))
+            .map_err(|e| format!("Failed to extract {}: {}", file_name, e))?;
+    }
+    Ok(())
+}
+
+/// Check a file against an expected SHA-256 (hex, case-insensitive)
+pub fn verify_sha256(path: &Path, expected: &str) -> Result<(), String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open {:?}: {}", path, e))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+    let actual = format!("{:x}", hasher.finalize());
+
+    if !actual.eq_ignore_ascii_case(expected.trim()) {
+        return Err(format!(
+            "Checksum mismatch for {:?}: expected {}, got {}",
+            path,
+            expected.trim(),
+            actual
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"{"audio": {"sample_rate": 22050, "quality": "medium"}, "language": {"code": "de_DE"}}"#;
+
+    fn write_voice(dir: &Path, id: &str) -> PathBuf {
+        let model = dir.join(format!("{}.onnx", id));
+        std::fs::write(&model, b"model").unwrap();
+        std::fs::write(model.with_extension("onnx.json"), CONFIG).unwrap();
+        model
+    }
+
+    #[test]
+    fn test_list_voices() {
+        let bundled = tempfile::tempdir().unwrap();
+        let user = tempfile::tempdir().unwrap();
+        write_voice(bundled.path(), "de_DE-thorsten-medium");
+        write_voice(bundled.path(), "en_US-lessac-medium");
+        write_voice(user.path(), "en_US-lessac-medium");
+        // No config: not listed
+        std::fs::write(user.path().join("broken.onnx"), b"model").unwrap();
+
+        let catalog = VoiceCatalog::new(Some(bundled.path().to_path_buf()), user.path().to_path_buf());
+        let voices = catalog.list();
+        assert_eq!(voices.len(), 2);
+        assert_eq!(voices[0].id, "de_DE-thorsten-medium");
+        assert!(voices[0].bundled);
+        assert_eq!(voices[0].language.as_deref(), Some("de_DE"));
+        assert_eq!(voices[0].quality.as_deref(), Some("medium"));
+        assert_eq!(voices[0].sample_rate, 22050);
+        // The installed copy wins
+        assert!(!voices[1].bundled);
+        assert!(catalog.find("broken").is_none());
+    }
+
+    #[test]
+    fn test_install_model_with_checksum() {
+        let downloads = tempfile::tempdir().unwrap();
+        let user = tempfile::tempdir().unwrap();
+        let model = write_voice(downloads.path(), "de_DE-thorsten-medium");
+        let catalog = VoiceCatalog::new(None, user.path().join("voices"));
+
+        let wrong = "0".repeat(64);
+        assert!(catalog.install(&model, Some(&wrong), None).unwrap_err().starts_with("Checksum mismatch"));
+        let sha256 = format!("{:x}", Sha256::digest(b"model"));
+        // A matching model doesn't cover a tampered config
+        assert!(catalog.install(&model, Some(&sha256), Some(&wrong)).unwrap_err().starts_with("Checksum mismatch"));
+        assert!(catalog.list().is_empty());
+
+        let config_sha256 = format!("{:x}", Sha256::digest(CONFIG.as_bytes()));
+        let voice = catalog.install(&model, Some(&sha256.to_uppercase()), Some(&config_sha256)).unwrap();
+        assert_eq!(voice.id, "de_DE-thorsten-medium");
+        assert!(voice.model_path.starts_with(catalog.user_dir()));
+        assert_eq!(catalog.list().len(), 1);
+
+        // Only the voice files end up in the voices directory
+        let entries = std::fs::read_dir(catalog.user_dir()).unwrap().count();
+        assert_eq!(entries, 2);
+    }
+
+    #[test]
+    fn test_install_archive() {
+        let downloads = tempfile::tempdir().unwrap();
+        let user = tempfile::tempdir().unwrap();
+        let model = write_voice(downloads.path(), "en_GB-alba-medium");
+        std::fs::write(downloads.path().join("MODEL_CARD"), b"card").unwrap();
+
+        let archive_path = downloads.path().join("en_GB-alba-medium.tar.gz");
+        let encoder = flate2::write::GzEncoder::new(
+            File::create(&archive_path).unwrap(),
+            flate2::Compression::default(),
+        );
+        let mut builder = tar::Builder::new(encoder);
+        builder.append_path_with_name(&model, "alba/en_GB-alba-medium.onnx").unwrap();
+        builder
+            .append_path_with_name(model.with_extension("onnx.json"), "alba/en_GB-alba-medium.onnx.json")
+            .unwrap();
+        builder.append_path_with_name(downloads.path().join("MODEL_CARD"), "alba/MODEL_CARD").unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let catalog = VoiceCatalog::new(None, user.path().to_path_buf());
+        let voice = catalog.install(&archive_path, None, None).unwrap();
+        assert_eq!(voice.id, "en_GB-alba-medium");
+        assert_eq!(voice.model_path, user.path().join("en_GB-alba-medium.onnx"));
+        assert!(!user.path().join("MODEL_CARD").exists());
+    }
+
+    #[test]
+    fn test_install_rejects_unknown_files() {
+        let downloads = tempfile::tempdir().unwrap();
+        let user = tempfile::tempdir().unwrap();
+        let zip = downloads.path().join("voice.zip");
+        std::fs::write(&zip, b"zip").unwrap();
+
+        let catalog = VoiceCatalog::new(None, user.path().to_path_buf());
+        assert!(catalog.install(&zip, None, None).unwrap_err().starts_with("Unsupported voice file"));
+    }
+}
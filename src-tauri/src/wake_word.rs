@@ -0,0 +1,518 @@
+//! Wake Word Detection
+//!
+//! Detectors behind the `WakeWordDetector` trait, fed with 16 kHz mono audio while
+//! the pipeline is listening for the wake word:
+//! - `KeywordWakeWordDetector`: keyword spotting with a small sherpa-onnx zipformer
+//!   model, listening for the configured wake phrases (each with its own sensitivity)
+//! - `EnergyWakeWordDetector`: fallback when the keyword model isn't installed; any
+//!   sustained sound above the energy threshold wakes the assistant
+//!
+//! Wake phrases are stored in the `wake_phrases` setting as JSON, e.g.
+//! `[{"phrase": "hey aura", "sensitivity": 0.75}]`.
+
+use serde::{Deserialize, Serialize};
+use sherpa_rs::sherpa_rs_sys as sys;
+use std::collections::{HashMap, HashSet};
+use std::ffi::{CStr, CString};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Keyword spotting model directory (inside the models directory)
+pub const KWS_MODEL_DIR: &str = "sherpa-onnx-kws-zipformer-gigaspeech-3.3M-2024-01-01";
+const KWS_MODEL_URL: &str = "https://github.com/k2-fsa/sherpa-onnx/releases/download/kws-models/sherpa-onnx-kws-zipformer-gigaspeech-3.3M-2024-01-01.tar.bz2";
+
+/// Wake phrase used when none are configured
+pub const DEFAULT_WAKE_PHRASE: &str = "hey aura";
+const DEFAULT_PHRASE_SENSITIVITY: f32 = 0.75;
+
+/// Label reported by the energy fallback (it can't tell phrases apart)
+const ENERGY_WAKE_LABEL: &str = "voice activity";
+
+// Energy fallback constants
+const VOICE_FRAMES_REQUIRED: usize = 10;     // Require consistent voice energy for wake word
+const WAKE_THRESHOLD_MULTIPLIER: f32 = 2.5;  // Wake word threshold relative to the VAD sensitivity
+
+// Keyword spotting constants
+const SAMPLE_RATE: u32 = 16000;
+const KWS_FEATURE_DIM: i32 = 80;
+const KWS_MAX_ACTIVE_PATHS: i32 = 4;
+
+/// A configured wake phrase
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WakePhrase {
+    /// Words to listen for (e.g., "hey aura")
+    pub phrase: String,
+    /// 0.0-1.0, higher triggers more easily (and falsely)
+    #[serde(default = "default_phrase_sensitivity")]
+    pub sensitivity: f32,
+}
+
+fn default_phrase_sensitivity() -> f32 {
+    DEFAULT_PHRASE_SENSITIVITY
+}
+
+impl Default for WakePhrase {
+    fn default() -> Self {
+        Self {
+            phrase: DEFAULT_WAKE_PHRASE.to_string(),
+            sensitivity: DEFAULT_PHRASE_SENSITIVITY,
+        }
+    }
+}
+
+/// Parse the `wake_phrases` setting
+///
+/// Blank phrases are dropped and sensitivities clamped to 0.0-1.0. Falls back to the
+/// default wake phrase if the setting is invalid or empty.
+pub fn parse_wake_phrases(json: &str) -> Vec<WakePhrase> {
+    let phrases: Vec<WakePhrase> = match serde_json::from_str::<Vec<WakePhrase>>(json) {
+        Ok(phrases) => phrases
+            .into_iter()
+            .filter(|p| !p.phrase.trim().is_empty())
+            .map(|p| WakePhrase {
+                phrase: p.phrase.trim().to_string(),
+                sensitivity: p.sensitivity.clamp(0.0, 1.0),
+            })
+            .collect(),
+        Err(e) => {
+            log::warn!("Invalid wake_phrases setting ({}), using default wake phrase", e);
+            Vec::new()
+        }
+    };
+
+    if phrases.is_empty() {
+        vec![WakePhrase::default()]
+    } else {
+        phrases
+    }
+}
+
+/// Detects the wake word in a stream of 16 kHz mono audio
+pub trait WakeWordDetector: Send {
+    /// Short name for logs (e.g., "keyword spotting")
+    fn name(&self) -> &'static str;
+
+    /// Feed the next chunk of audio
+    ///
+    /// Returns the detected wake phrase, if any.
+    fn process(&mut self, samples: &[f32]) -> Option<String>;
+
+    /// Forget buffered audio (after a detection or a gap in the stream)
+    fn reset(&mut self);
+}
+
+/// Create the best available detector
+///
+/// Uses keyword spotting if the model is installed and loads, otherwise falls back to
+/// the energy detector.
+pub fn create_detector(
+    model_path: &Path,
+    phrases: &[WakePhrase],
+    vad_sensitivity: Arc<Mutex<f32>>,
+) -> Box<dyn WakeWordDetector> {
+    let kws_dir = model_path.join(KWS_MODEL_DIR);
+    if !kws_dir.exists() {
+        log::warn!("Keyword spotting model not found: {:?}", kws_dir);
+        log::warn!("Download and unpack it into the models directory: {}", KWS_MODEL_URL);
+        log::warn!("Using energy-based wake word detection (any sustained sound wakes the assistant)");
+        return Box::new(EnergyWakeWordDetector::new(vad_sensitivity));
+    }
+
+    match KeywordWakeWordDetector::new(&kws_dir, phrases) {
+        Ok(detector) => Box::new(detector),
+        Err(e) => {
+            log::error!("Failed to load keyword spotting model: {}", e);
+            log::warn!("Using energy-based wake word detection");
+            Box::new(EnergyWakeWordDetector::new(vad_sensitivity))
+        }
+    }
+}
+
+/// Energy-based fallback: wakes on sustained sound above the threshold
+pub struct EnergyWakeWordDetector {
+    vad_sensitivity: Arc<Mutex<f32>>,
+    voice_frames: usize,
+}
+
+impl EnergyWakeWordDetector {
+    pub fn new(vad_sensitivity: Arc<Mutex<f32>>) -> Self {
+        Self {
+            vad_sensitivity,
+            voice_frames: 0,
+        }
+    }
+}
+
+impl WakeWordDetector for EnergyWakeWordDetector {
+    fn name(&self) -> &'static str {
+        "energy"
+    }
+
+    fn process(&mut self, samples: &[f32]) -> Option<String> {
+        let sensitivity = self.vad_sensitivity.lock().map(|s| *s).unwrap_or(0.02);
+        let wake_threshold = sensitivity * WAKE_THRESHOLD_MULTIPLIER;
+        let energy = calculate_rms_energy(samples);
+
+        if energy > wake_threshold {
+            self.voice_frames += 1;
+
+            // Require consistent voice activity to avoid false positives
+            if self.voice_frames >= VOICE_FRAMES_REQUIRED {
+                log::debug!("Voice activity detected (energy: {:.4}, threshold: {:.4})", energy, wake_threshold);
+                self.voice_frames = 0;
+                return Some(ENERGY_WAKE_LABEL.to_string());
+            }
+        } else if energy < sensitivity {
+            // Decay the counter during silence
+            self.voice_frames = self.voice_frames.saturating_sub(1);
+        }
+
+        None
+    }
+
+    fn reset(&mut self) {
+        self.voice_frames = 0;
+    }
+}
+
+/// Keyword spotting with a sherpa-onnx zipformer model
+///
+/// Audio is streamed into the spotter as it arrives; the stream is reset after each
+/// detection.
+pub struct KeywordWakeWordDetector {
+    stream: KeywordStream,
+    /// Keyword label reported by sherpa -> configured phrase
+    labels: HashMap<String, String>,
+}
+
+impl KeywordWakeWordDetector {
+    /// Load the model in `model_dir` and listen for `phrases`
+    pub fn new(model_dir: &Path, phrases: &[WakePhrase]) -> Result<Self, String> {
+        let encoder = find_model_file(model_dir, "encoder")?;
+        let decoder = find_model_file(model_dir, "decoder")?;
+        let joiner = find_model_file(model_dir, "joiner")?;
+        let tokens_path = model_dir.join("tokens.txt");
+
+        let tokens = std::fs::read_to_string(&tokens_path)
+            .map_err(|e| format!("Failed to read {:?}: {}", tokens_path, e))?;
+        let vocab = parse_tokens(&tokens);
+
+        // The model only knows its own tokens, so phrases are spelled out in them
+        let (keywords, labels) = keywords_file(phrases, &vocab);
+        if labels.is_empty() {
+            return Err("None of the wake phrases can be spelled with the model's tokens".to_string());
+        }
+
+        let stream = KeywordStream::new(&encoder, &decoder, &joiner, &tokens_path, &keywords)?;
+
+        log::info!(
+            "✓ Keyword spotting ready: {}",
+            labels.values().cloned().collect::<Vec<_>>().join(", ")
+        );
+
+        Ok(Self { stream, labels })
+    }
+}
+
+impl WakeWordDetector for KeywordWakeWordDetector {
+    fn name(&self) -> &'static str {
+        "keyword spotting"
+    }
+
+    fn process(&mut self, samples: &[f32]) -> Option<String> {
+        let keyword = self.stream.accept(samples)?;
+        let keyword = keyword.trim();
+        Some(
+            self.labels
+                .get(keyword)
+                .cloned()
+                .unwrap_or_else(|| keyword.to_string()),
+        )
+    }
+
+    fn reset(&mut self) {
+        self.stream.reset();
+    }
+}
+
+/// A keyword spotter and its audio stream (sherpa-onnx C API)
+///
+/// `sherpa_rs::keyword_spot::KeywordSpot` marks its stream finished on every call and
+/// never resets it, so it can't be fed audio continuously; this drives the stream
+/// directly instead.
+struct KeywordStream {
+    spotter: *const sys::SherpaOnnxKeywordSpotter,
+    stream: *const sys::SherpaOnnxOnlineStream,
+}
+
+// The pointers are only used through `&mut self`
+unsafe impl Send for KeywordStream {}
+
+impl KeywordStream {
+    fn new(encoder: &Path, decoder: &Path, joiner: &Path, tokens: &Path, keywords: &str) -> Result<Self, String> {
+        let c_string = |value: &str| {
+            CString::new(value).map_err(|_| format!("Invalid keyword spotter setting: {:?}", value))
+        };
+        let encoder = c_string(&encoder.to_string_lossy())?;
+        let decoder = c_string(&decoder.to_string_lossy())?;
+        let joiner = c_string(&joiner.to_string_lossy())?;
+        let tokens = c_string(&tokens.to_string_lossy())?;
+        let keywords_buf = c_string(keywords)?;
+        let provider = c_string("cpu")?;
+
+        // SAFETY: an all-zero config is valid (null strings, zero sizes); the strings
+        // outlive the call, which copies what it needs
+        let spotter = unsafe {
+            let mut config: sys::SherpaOnnxKeywordSpotterConfig = std::mem::zeroed();
+            config.feat_config.sample_rate = SAMPLE_RATE as i32;
+            config.feat_config.feature_dim = KWS_FEATURE_DIM;
+            config.model_config.transducer.encoder = encoder.as_ptr();
+            config.model_config.transducer.decoder = decoder.as_ptr();
+            config.model_config.transducer.joiner = joiner.as_ptr();
+            config.model_config.tokens = tokens.as_ptr();
+            config.model_config.num_threads = 1;
+            config.model_config.provider = provider.as_ptr();
+            config.max_active_paths = KWS_MAX_ACTIVE_PATHS;
+            config.num_trailing_blanks = 1;
+            config.keywords_score = 1.0;
+            config.keywords_threshold = 1.0 - DEFAULT_PHRASE_SENSITIVITY;
+            // Passed in memory: the model dir is read-only in bundled installs
+            config.keywords_buf = keywords_buf.as_ptr();
+            config.keywords_buf_size = keywords.len() as i32;
+            sys::SherpaOnnxCreateKeywordSpotter(&config)
+        };
+        if spotter.is_null() {
+            return Err("Failed to create keyword spotter".to_string());
+        }
+
+        // SAFETY: `spotter` is a valid spotter
+        let stream = unsafe { sys::SherpaOnnxCreateKeywordStream(spotter) };
+        if stream.is_null() {
+            // SAFETY: created above and not used again
+            unsafe { sys::SherpaOnnxDestroyKeywordSpotter(spotter) };
+            return Err("Failed to create keyword stream".to_string());
+        }
+
+        Ok(Self { spotter, stream })
+    }
+
+    /// Feed audio and decode what is ready; returns the keyword if one was spotted
+    fn accept(&mut self, samples: &[f32]) -> Option<String> {
+        // SAFETY: `spotter` and `stream` stay valid until drop; results are destroyed
+        // after reading them
+        unsafe {
+            sys::SherpaOnnxOnlineStreamAcceptWaveform(
+                self.stream,
+                SAMPLE_RATE as i32,
+                samples.as_ptr(),
+                samples.len() as i32,
+            );
+
+            while sys::SherpaOnnxIsKeywordStreamReady(self.spotter, self.stream) == 1 {
+                sys::SherpaOnnxDecodeKeywordStream(self.spotter, self.stream);
+
+                let result = sys::SherpaOnnxGetKeywordResult(self.spotter, self.stream);
+                if result.is_null() {
+                    continue;
+                }
+                let keyword = if (*result).keyword.is_null() {
+                    String::new()
+                } else {
+                    CStr::from_ptr((*result).keyword).to_string_lossy().to_string()
+                };
+                sys::SherpaOnnxDestroyKeywordResult(result);
+
+                if !keyword.trim().is_empty() {
+                    // Required after a detection, or the keyword keeps being reported
+                    self.reset();
+                    return Some(keyword);
+                }
+            }
+        }
+        None
+    }
+
+    fn reset(&mut self) {
+        // SAFETY: both valid until drop
+        unsafe { sys::SherpaOnnxResetKeywordStream(self.spotter, self.stream) };
+    }
+}
+
+impl Drop for KeywordStream {
+    fn drop(&mut self) {
+        // SAFETY: created in `new`, destroyed once
+        unsafe {
+            sys::SherpaOnnxDestroyOnlineStream(self.stream);
+            sys::SherpaOnnxDestroyKeywordSpotter(self.spotter);
+        }
+    }
+}
+
+/// Find `<prefix>*.onnx` in the model directory (preferring full precision over int8)
+fn find_model_file(model_dir: &Path, prefix: &str) -> Result<PathBuf, String> {
+    let mut candidates: Vec<PathBuf> = std::fs::read_dir(model_dir)
+        .map_err(|e| format!("Failed to read {:?}: {}", model_dir, e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            name.starts_with(prefix) && name.ends_with(".onnx")
+        })
+        .collect();
+
+    candidates.sort_by_key(|path| (path.to_string_lossy().contains("int8"), path.clone()));
+    candidates
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("No {}*.onnx found in {:?}", prefix, model_dir))
+}
+
+/// Token set from a sherpa `tokens.txt` ("<token> <id>" per line)
+fn parse_tokens(tokens: &str) -> HashSet<String> {
+    tokens
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Spell a phrase in BPE tokens by greedy longest match
+///
+/// Word-initial tokens carry the SentencePiece "▁" marker. Tries upper then lower
+/// case, since models differ in the casing of their vocabulary.
+fn tokenize_phrase(phrase: &str, vocab: &HashSet<String>) -> Option<Vec<String>> {
+    [phrase.to_uppercase(), phrase.to_lowercase()]
+        .iter()
+        .find_map(|phrase| {
+            let mut tokens = Vec::new();
+            for word in phrase.split_whitespace() {
+                let mut rest = format!("\u{2581}{}", word);
+                while !rest.is_empty() {
+                    let end = rest
+                        .char_indices()
+                        .map(|(i, c)| i + c.len_utf8())
+                        .rev()
+                        .find(|&end| vocab.contains(&rest[..end]))?;
+                    tokens.push(rest[..end].to_string());
+                    rest = rest[end..].to_string();
+                }
+            }
+            (!tokens.is_empty()).then_some(tokens)
+        })
+}
+
+/// Label sherpa reports for a phrase ("hey aura" -> "HEY_AURA")
+fn keyword_label(phrase: &str) -> String {
+    phrase
+        .split_whitespace()
+        .map(str::to_uppercase)
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Contents of the sherpa keywords file, plus the label -> phrase mapping
+///
+/// One line per phrase: `<tokens> #<threshold> @<label>`. Higher sensitivity means a
+/// lower trigger threshold. Phrases that can't be spelled in the model's tokens are
+/// skipped.
+fn keywords_file(phrases: &[WakePhrase], vocab: &HashSet<String>) -> (String, HashMap<String, String>) {
+    let mut contents = String::new();
+    let mut labels = HashMap::new();
+
+    for phrase in phrases {
+        let Some(tokens) = tokenize_phrase(&phrase.phrase, vocab) else {
+            log::warn!("Wake phrase '{}' can't be spelled with the keyword model's tokens, skipping", phrase.phrase);
+            continue;
+        };
+
+        let threshold = (1.0 - phrase.sensitivity).clamp(0.05, 0.95);
+        let label = keyword_label(&phrase.phrase);
+        contents.push_str(&format!("{} #{:.2} @{}\n", tokens.join(" "), threshold, label));
+        labels.insert(label, phrase.phrase.clone());
+    }
+
+    (contents, labels)
+}
+
+/// Calculate RMS energy of audio samples
+fn calculate_rms_energy(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+
+    let sum_squares: f32 = samples.iter().map(|&s| s * s).sum();
+    (sum_squares / samples.len() as f32).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vocab(tokens: &[&str]) -> HashSet<String> {
+        tokens.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_wake_phrases() {
+        let phrases = parse_wake_phrases(r#"[{"phrase": " ok computer ", "sensitivity": 1.5}, {"phrase": "hey aura"}, {"phrase": ""}]"#);
+        assert_eq!(
+            phrases,
+            vec![
+                WakePhrase { phrase: "ok computer".to_string(), sensitivity: 1.0 },
+                WakePhrase { phrase: "hey aura".to_string(), sensitivity: DEFAULT_PHRASE_SENSITIVITY },
+            ]
+        );
+
+        assert_eq!(parse_wake_phrases("[]"), vec![WakePhrase::default()]);
+        assert_eq!(parse_wake_phrases("not json"), vec![WakePhrase::default()]);
+    }
+
+    #[test]
+    fn test_tokenize_phrase_greedy_longest_match() {
+        let vocab = vocab(&["\u{2581}HE", "\u{2581}H", "Y", "EY", "\u{2581}A", "\u{2581}AU", "RA"]);
+
+        assert_eq!(
+            tokenize_phrase("Hey Aura", &vocab).unwrap(),
+            vec!["\u{2581}HE", "Y", "\u{2581}AU", "RA"]
+        );
+        // Words the vocabulary can't spell
+        assert_eq!(tokenize_phrase("hey zed", &vocab), None);
+    }
+
+    #[test]
+    fn test_keywords_file_lines() {
+        let vocab = vocab(&["\u{2581}hey", "\u{2581}aura", "\u{2581}ok", "\u{2581}comp", "uter"]);
+        let phrases = vec![
+            WakePhrase { phrase: "hey aura".to_string(), sensitivity: 0.75 },
+            WakePhrase { phrase: "ok computer".to_string(), sensitivity: 0.5 },
+            WakePhrase { phrase: "xyz".to_string(), sensitivity: 0.5 },
+        ];
+
+        let (contents, labels) = keywords_file(&phrases, &vocab);
+
+        assert_eq!(
+            contents,
+            "\u{2581}hey \u{2581}aura #0.25 @HEY_AURA\n\u{2581}ok \u{2581}comp uter #0.50 @OK_COMPUTER\n"
+        );
+        assert_eq!(labels.get("HEY_AURA").map(String::as_str), Some("hey aura"));
+        assert_eq!(labels.len(), 2);
+    }
+
+    #[test]
+    fn test_energy_detector_needs_sustained_sound() {
+        let mut detector = EnergyWakeWordDetector::new(Arc::new(Mutex::new(0.02)));
+        let loud = vec![0.2_f32; 512];
+        let quiet = vec![0.0_f32; 512];
+
+        // A short burst (a door slam) doesn't wake the assistant
+        for _ in 0..VOICE_FRAMES_REQUIRED - 1 {
+            assert!(detector.process(&loud).is_none());
+        }
+        for _ in 0..VOICE_FRAMES_REQUIRED {
+            assert!(detector.process(&quiet).is_none());
+        }
+
+        // Sustained sound does
+        let detections = (0..VOICE_FRAMES_REQUIRED).filter_map(|_| detector.process(&loud)).count();
+        assert_eq!(detections, 1);
+    }
+}
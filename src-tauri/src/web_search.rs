@@ -1,3 +1,4 @@
+use crate::database::Settings;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
@@ -11,6 +12,36 @@ pub enum SearchBackend {
     BraveSearch { api_key: String },
 }
 
+impl SearchBackend {
+    /// Select the search backend configured in settings
+    ///
+    /// Unknown backend names fall back to the default SearXNG instance.
+    pub fn from_settings(settings: &Settings) -> Result<Self, String> {
+        match settings.search_backend.as_str() {
+            "searxng" => Ok(SearchBackend::SearXNG {
+                instance_url: settings.searxng_instance_url.clone(),
+            }),
+            "brave" => {
+                // Get Brave API key from settings
+                let api_key = settings
+                    .brave_search_api_key
+                    .clone()
+                    .ok_or_else(|| {
+                        "Brave Search selected but no API key configured. Please set it in Settings.".to_string()
+                    })?;
+
+                Ok(SearchBackend::BraveSearch { api_key })
+            }
+            backend => {
+                log::warn!("Unknown search backend '{}', defaulting to SearXNG", backend);
+                Ok(SearchBackend::SearXNG {
+                    instance_url: "https://searx.be".to_string(),
+                })
+            }
+        }
+    }
+}
+
 /// Individual search result from web search
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchResult {
@@ -1,4 +1,4 @@
-import { useEffect, useState } from "react";
+import { useEffect, useRef, useState } from "react";
 import { listen } from "@tauri-apps/api/event";
 import { invoke } from "@tauri-apps/api/core";
 import Sidebar from "./components/Sidebar";
@@ -6,7 +6,7 @@ import ChatView from "./components/ChatView";
 import DevicesView from "./components/DevicesView";
 import SettingsModal from "./components/SettingsModal";
 import WelcomeWizard from "./components/WelcomeWizard";
-import { useChatStore } from "./store";
+import { useChatStore, LlmResponse } from "./store";
 import { showErrorToast } from "./utils/errorHandler";
 
 // Transcription result structure from backend
@@ -14,6 +14,7 @@ interface TranscriptionResult {
   text: string;
   duration_seconds: number;
   sample_count: number;
+  language: string | null;
   speaker_info: {
     user_id: number | null;
     user_name: string | null;
@@ -27,6 +28,8 @@ type ViewMode = "chat" | "devices";
 function App() {
   const [showWizard, setShowWizard] = useState<boolean | null>(null);
   const [activeView, setActiveView] = useState<ViewMode>("chat");
+  // Incremented per voice turn; a barge-in starts a new turn while the old one winds down
+  const voiceTurnRef = useRef(0);
 
   const setAppStatus = useChatStore((state) => state.setAppStatus);
   const appStatus = useChatStore((state) => state.appStatus);
@@ -98,11 +101,18 @@ function App() {
           server_address: dbSettings.server_address,
           api_key: apiKey,
           wake_word_enabled: dbSettings.wake_word_enabled,
+          wake_phrases: dbSettings.wake_phrases,
+          input_device_name: dbSettings.input_device_name ?? "",
           api_base_url: dbSettings.api_base_url,
           model_name: dbSettings.model_name,
           vad_sensitivity: dbSettings.vad_sensitivity ?? 0.02,
           vad_timeout_ms: dbSettings.vad_timeout_ms ?? 1280,
+          vad_engine: dbSettings.vad_engine ?? "energy",
+          barge_in_mode: dbSettings.barge_in_mode ?? "off",
           stt_model_name: dbSettings.stt_model_name ?? "ggml-tiny.bin",
+          stt_language: dbSettings.stt_language ?? "auto",
+          stt_partial_interval_ms: dbSettings.stt_partial_interval_ms ?? 500,
+          debug_capture_enabled: dbSettings.debug_capture_enabled ?? false,
           voice_preference: dbSettings.voice_preference ?? "male",
           online_mode_enabled: dbSettings.online_mode_enabled ?? false,
           search_backend: dbSettings.search_backend ?? "searxng",
@@ -124,35 +134,11 @@ function App() {
     });
   }, [setSettings]);
 
-  // Sync voice pipeline state with app status to prevent feedback loop
+  // Listen for wake word detection and barge-in events
   useEffect(() => {
-    const syncVoiceState = async () => {
-      try {
-        if (appStatus === "speaking") {
-          // Disable wake word detection while TTS is speaking to prevent feedback loop
-          await invoke("set_voice_state", { state: "speaking" });
-          console.log("Voice state: speaking (wake word disabled)");
-        } else if (appStatus === "idle") {
-          // Re-enable wake word detection when idle
-          await invoke("set_voice_state", { state: "listening_for_wake_word" });
-          console.log("Voice state: listening_for_wake_word (wake word enabled)");
-        }
-        // Note: "listening" and "processing" states maintain current voice state
-      } catch (error) {
-        showErrorToast(error, "Failed to sync voice state");
-      }
-    };
-
-    syncVoiceState().catch((err) => {
-      showErrorToast(err, "Uncaught error in syncVoiceState");
-    });
-  }, [appStatus]);
-
-  // Listen for wake word detection events
-  useEffect(() => {
-    // Listen for wake word detection events from Rust backend
-    const unlisten = listen<string>("wake_word_detected", async (event) => {
-      console.log("Wake word detected:", event.payload);
+    // Record, transcribe and answer one voice request
+    const runVoiceTurn = async () => {
+      const turn = ++voiceTurnRef.current;
 
       // Set input method to voice (using local variable to avoid closure issues)
       const inputMethod = "voice";
@@ -204,9 +190,11 @@ function App() {
           addMessage({ role: "user", content: transcription });
 
           // Call backend to process the prompt
-          const response = await invoke<string>("handle_user_prompt", {
+          const { content: response, backend } = await invoke<LlmResponse>("handle_user_prompt", {
             prompt: transcription,
+            conversationId,
           });
+          console.log(`LLM response from backend: ${backend}`);
 
           // Save assistant response to database
           await invoke("save_message", {
@@ -249,7 +237,10 @@ function App() {
             try {
               console.log("Speaking response...");
               setAppStatus("speaking");
-              await invoke("speak_text", { text: response });
+              await invoke("speak_text", {
+                text: response,
+                userId: result.speaker_info?.user_id ?? null,
+              });
               console.log("Finished speaking");
             } catch (speakError) {
               showErrorToast(speakError, "Failed to speak response");
@@ -260,6 +251,12 @@ function App() {
           console.warn("Empty transcription received");
         }
       } catch (error) {
+        // Interrupted by a barge-in: the new turn takes over
+        if (voiceTurnRef.current !== turn) {
+          console.log("Voice turn interrupted by barge-in");
+          return;
+        }
+
         showErrorToast(error, "Error during STT or prompt handling");
         const errorMsg = "Sorry, I couldn't understand that. Please try again.";
 
@@ -291,16 +288,31 @@ function App() {
           }
         }
       } finally {
-        // Reset to idle
-        setAppStatus("idle");
-        // Reset input method
-        setLastInputMethod(null);
+        // Reset to idle, unless a barge-in already started the next turn
+        if (voiceTurnRef.current === turn) {
+          setAppStatus("idle");
+          // Reset input method
+          setLastInputMethod(null);
+        }
       }
+    };
+
+    // Listen for wake word detection events from Rust backend
+    const unlistenWakeWord = listen<string>("wake_word_detected", async (event) => {
+      console.log("Wake word detected:", event.payload);
+      await runVoiceTurn();
+    });
+
+    // The user interrupted the spoken answer; the backend is already recording
+    const unlistenBargeIn = listen<string>("barge_in", async (event) => {
+      console.log("Barge-in:", event.payload);
+      await runVoiceTurn();
     });
 
-    // Cleanup listener on unmount
+    // Cleanup listeners on unmount
     return () => {
-      unlisten.then((fn) => fn());
+      unlistenWakeWord.then((fn) => fn());
+      unlistenBargeIn.then((fn) => fn());
     };
   }, [setAppStatus, addMessage, activeConversationId, conversations, updateConversationTitle, lastInputMethod, setLastInputMethod]);
 
@@ -1,6 +1,7 @@
-import React, { useState } from "react";
+import React, { useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { useChatStore } from "../store";
+import { listen } from "@tauri-apps/api/event";
+import { useChatStore, LlmResponse } from "../store";
 import { Mic } from "lucide-react";
 import { showErrorToast } from "../utils/errorHandler";
 
@@ -9,6 +10,7 @@ interface TranscriptionResult {
   text: string;
   duration_seconds: number;
   sample_count: number;
+  language: string | null;
   speaker_info: {
     user_id: number | null;
     user_name: string | null;
@@ -17,8 +19,15 @@ interface TranscriptionResult {
   } | null;
 }
 
+// Live caption emitted while the user is still speaking
+interface PartialTranscription {
+  stable: string;
+  tentative: string;
+}
+
 const InputBar: React.FC = () => {
   const [input, setInput] = useState("");
+  const [liveCaption, setLiveCaption] = useState("");
   const addMessage = useChatStore((state) => state.addMessage);
   const appStatus = useChatStore((state) => state.appStatus);
   const setAppStatus = useChatStore((state) => state.setAppStatus);
@@ -111,9 +120,12 @@ const InputBar: React.FC = () => {
         }
       } else {
         // Call backend command to get response for non-music commands
-        response = await invoke<string>("handle_user_prompt", {
+        const llmResponse = await invoke<LlmResponse>("handle_user_prompt", {
           prompt: userPrompt,
+          conversationId,
         });
+        console.log(`LLM response from backend: ${llmResponse.backend}`);
+        response = llmResponse.content;
       }
 
       // Set status back to idle
@@ -300,9 +312,12 @@ const InputBar: React.FC = () => {
         }
       } else {
         // Get LLM response for non-music commands
-        response = await invoke<string>("handle_user_prompt", {
+        const llmResponse = await invoke<LlmResponse>("handle_user_prompt", {
           prompt: transcribedText,
+          conversationId,
         });
+        console.log(`LLM response from backend: ${llmResponse.backend}`);
+        response = llmResponse.content;
       }
 
       // Save assistant response
@@ -364,10 +379,30 @@ const InputBar: React.FC = () => {
     }
   };
 
+  // Show partial transcriptions as a live caption while listening
+  useEffect(() => {
+    const unlistenPartial = listen<PartialTranscription>("partial_transcription", (event) => {
+      const { stable, tentative } = event.payload;
+      setLiveCaption([stable, tentative].filter(Boolean).join(" "));
+    });
+    const unlistenFinal = listen("final_transcription", () => setLiveCaption(""));
+
+    return () => {
+      unlistenPartial.then((fn) => fn());
+      unlistenFinal.then((fn) => fn());
+    };
+  }, []);
+
+  useEffect(() => {
+    if (appStatus !== "listening") {
+      setLiveCaption("");
+    }
+  }, [appStatus]);
+
   // Determine placeholder text based on app status
   const getPlaceholder = () => {
     if (appStatus === "listening") {
-      return "Listening... Speak now";
+      return liveCaption ? `${liveCaption}...` : "Listening... Speak now";
     } else if (appStatus === "processing") {
       return "Processing...";
     }
@@ -1,6 +1,7 @@
 import React, { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { useChatStore } from "../store";
+import { listen } from "@tauri-apps/api/event";
+import { useChatStore, WakePhrase, parseWakePhrases } from "../store";
 import {
   Dialog,
   DialogContent,
@@ -55,6 +56,50 @@ const CATEGORIES: Category[] = [
 
 const STORAGE_KEY = "aura_settings_active_category";
 
+// Select items can't have an empty value; stands in for input_device_name = ""
+const SYSTEM_DEFAULT_DEVICE = "__system_default__";
+
+interface InputConfigInfo {
+  channels: number;
+  min_sample_rate: number;
+  max_sample_rate: number;
+  sample_format: string;
+}
+
+interface InputDeviceInfo {
+  name: string;
+  is_default: boolean;
+  default_config: InputConfigInfo | null;
+  supported_configs: InputConfigInfo[];
+}
+
+interface CatalogVoice {
+  id: string;
+  language: string | null;
+  quality: string | null;
+  sample_rate: number;
+  num_speakers: number;
+  bundled: boolean;
+  model_path: string;
+  active: boolean;
+}
+
+interface DownloadProgress {
+  downloaded_bytes: number;
+  total_bytes: number | null;
+  percentage: number;
+}
+
+const describeVoice = (voice: CatalogVoice) =>
+  [
+    voice.language,
+    voice.quality,
+    `${(voice.sample_rate / 1000).toFixed(voice.sample_rate % 1000 === 0 ? 0 : 2)} kHz`,
+    voice.num_speakers > 1 ? `${voice.num_speakers} speakers` : null,
+  ]
+    .filter(Boolean)
+    .join(" · ");
+
 const SettingsModal: React.FC = () => {
   const isOpen = useChatStore((state) => state.isSettingsOpen);
   const closeSettings = useChatStore((state) => state.closeSettings);
@@ -73,12 +118,27 @@ const SettingsModal: React.FC = () => {
   const [apiKey, setApiKey] = useState(settings.api_key);
   const [serverAddress, setServerAddress] = useState(settings.server_address);
   const [wakeWordEnabled, setWakeWordEnabled] = useState(settings.wake_word_enabled);
+  const [wakePhrases, setWakePhrases] = useState<WakePhrase[]>(parseWakePhrases(settings.wake_phrases));
+  const [inputDeviceName, setInputDeviceName] = useState(settings.input_device_name ?? "");
+  const [inputDevices, setInputDevices] = useState<InputDeviceInfo[]>([]);
   const [apiBaseUrl, setApiBaseUrl] = useState(settings.api_base_url);
   const [modelName, setModelName] = useState(settings.model_name);
   const [vadSensitivity, setVadSensitivity] = useState(settings.vad_sensitivity);
   const [vadTimeoutMs, setVadTimeoutMs] = useState(settings.vad_timeout_ms);
+  const [vadEngine, setVadEngine] = useState(settings.vad_engine ?? "energy");
+  const [bargeInMode, setBargeInMode] = useState(settings.barge_in_mode ?? "off");
   const [sttModelName, setSttModelName] = useState(settings.stt_model_name);
+  const [sttLanguage, setSttLanguage] = useState(settings.stt_language ?? "auto");
+  const [sttPartialIntervalMs, setSttPartialIntervalMs] = useState(settings.stt_partial_interval_ms ?? 500);
+  const [debugCaptureEnabled, setDebugCaptureEnabled] = useState(settings.debug_capture_enabled ?? false);
   const [voicePreference, setVoicePreference] = useState(settings.voice_preference);
+  const [voices, setVoices] = useState<CatalogVoice[]>([]);
+  const [voiceId, setVoiceId] = useState("");
+  const [voiceSource, setVoiceSource] = useState("");
+  const [voiceSha256, setVoiceSha256] = useState("");
+  const [voiceConfigSha256, setVoiceConfigSha256] = useState("");
+  const [isInstallingVoice, setIsInstallingVoice] = useState(false);
+  const [voiceInstallProgress, setVoiceInstallProgress] = useState<DownloadProgress | null>(null);
   const [onlineModeEnabled, setOnlineModeEnabled] = useState(settings.online_mode_enabled);
   const [searchBackend, setSearchBackend] = useState(settings.search_backend);
   const [searxngInstanceUrl, setSearxngInstanceUrl] = useState(settings.searxng_instance_url);
@@ -114,17 +174,86 @@ const SettingsModal: React.FC = () => {
     fetchModels();
   }, [isOpen, settings.model_name]);
 
+  // Fetch available microphones when modal opens
+  useEffect(() => {
+    if (!isOpen) return;
+
+    invoke<InputDeviceInfo[]>("list_input_devices")
+      .then(setInputDevices)
+      .catch((error) => {
+        console.error("Failed to list input devices:", error);
+        setInputDevices([]);
+      });
+  }, [isOpen]);
+
+  // Fetch installed TTS voices when modal opens
+  const loadVoices = async () => {
+    try {
+      const list = await invoke<CatalogVoice[]>("tts_list_voices");
+      setVoices(list);
+      setVoiceId(list.find((v) => v.active)?.id ?? "");
+    } catch (error) {
+      console.error("Failed to list voices:", error);
+      setVoices([]);
+    }
+  };
+
+  useEffect(() => {
+    if (!isOpen) return;
+    loadVoices();
+  }, [isOpen]);
+
+  useEffect(() => {
+    const unlisten = listen<DownloadProgress>("voice_install_progress", (event) => {
+      setVoiceInstallProgress(event.payload);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const handleInstallVoice = async () => {
+    setIsInstallingVoice(true);
+    setVoiceInstallProgress(null);
+    try {
+      const voice = await invoke<CatalogVoice>("tts_install_voice", {
+        source: voiceSource.trim(),
+        sha256: voiceSha256.trim() || null,
+        configSha256: voiceConfigSha256.trim() || null,
+      });
+      setVoiceSource("");
+      setVoiceSha256("");
+      setVoiceConfigSha256("");
+      await loadVoices();
+      // Keep the active voice until Save, but preselect the new one
+      setVoiceId(voice.id);
+    } catch (error) {
+      showErrorToast(error, "Failed to install voice");
+    } finally {
+      setIsInstallingVoice(false);
+      setVoiceInstallProgress(null);
+    }
+  };
+
   // Update local state when settings change
   useEffect(() => {
     setLlmProvider(settings.llm_provider);
     setApiKey(settings.api_key);
     setServerAddress(settings.server_address);
     setWakeWordEnabled(settings.wake_word_enabled);
+    setWakePhrases(parseWakePhrases(settings.wake_phrases));
+    setInputDeviceName(settings.input_device_name ?? "");
     setApiBaseUrl(settings.api_base_url);
     setModelName(settings.model_name);
     setVadSensitivity(settings.vad_sensitivity);
     setVadTimeoutMs(settings.vad_timeout_ms);
+    setVadEngine(settings.vad_engine ?? "energy");
+    setBargeInMode(settings.barge_in_mode ?? "off");
     setSttModelName(settings.stt_model_name);
+    setSttLanguage(settings.stt_language ?? "auto");
+    setSttPartialIntervalMs(settings.stt_partial_interval_ms ?? 500);
+    setDebugCaptureEnabled(settings.debug_capture_enabled ?? false);
     setVoicePreference(settings.voice_preference);
     setOnlineModeEnabled(settings.online_mode_enabled);
     setSearchBackend(settings.search_backend);
@@ -153,6 +282,13 @@ const SettingsModal: React.FC = () => {
         vadSensitivity,
         vadTimeoutMs,
         sttModelName,
+        sttLanguage,
+        sttPartialIntervalMs,
+        vadEngine,
+        bargeInMode,
+        debugCaptureEnabled,
+        wakePhrases: JSON.stringify(wakePhrases.filter((p) => p.phrase.trim())),
+        inputDeviceName,
         voicePreference,
         onlineModeEnabled,
         searchBackend,
@@ -161,6 +297,12 @@ const SettingsModal: React.FC = () => {
         maxSearchResults,
       });
 
+      // Switch the TTS voice (takes effect with the next answer, no restart)
+      const activeVoice = voices.find((v) => v.active);
+      if (voiceId && voiceId !== activeVoice?.id) {
+        await invoke("tts_set_voice", { voiceId });
+      }
+
       // Reload voice pipeline with new settings
       await invoke("reload_voice_pipeline", {
         llmProvider,
@@ -179,12 +321,20 @@ const SettingsModal: React.FC = () => {
         server_address: serverAddress,
         api_key: apiKey,
         wake_word_enabled: wakeWordEnabled,
+        wake_phrases: JSON.stringify(wakePhrases.filter((p) => p.phrase.trim())),
+        input_device_name: inputDeviceName,
         api_base_url: apiBaseUrl,
         model_name: modelName,
         vad_sensitivity: vadSensitivity,
         vad_timeout_ms: vadTimeoutMs,
+        vad_engine: vadEngine,
+        barge_in_mode: bargeInMode,
         stt_model_name: sttModelName,
+        stt_language: sttLanguage,
+        stt_partial_interval_ms: sttPartialIntervalMs,
+        debug_capture_enabled: debugCaptureEnabled,
         voice_preference: voicePreference,
+        voice_model: voiceId || settings.voice_model,
         online_mode_enabled: onlineModeEnabled,
         search_backend: searchBackend,
         searxng_instance_url: searxngInstanceUrl,
@@ -211,12 +361,20 @@ const SettingsModal: React.FC = () => {
       setApiKey(settings.api_key);
       setServerAddress(settings.server_address);
       setWakeWordEnabled(settings.wake_word_enabled);
+      setWakePhrases(parseWakePhrases(settings.wake_phrases));
+      setInputDeviceName(settings.input_device_name ?? "");
       setApiBaseUrl(settings.api_base_url);
       setModelName(settings.model_name);
       setVadSensitivity(settings.vad_sensitivity);
       setVadTimeoutMs(settings.vad_timeout_ms);
+      setVadEngine(settings.vad_engine ?? "energy");
+      setBargeInMode(settings.barge_in_mode ?? "off");
       setSttModelName(settings.stt_model_name);
+      setSttLanguage(settings.stt_language ?? "auto");
+      setSttPartialIntervalMs(settings.stt_partial_interval_ms ?? 500);
+      setDebugCaptureEnabled(settings.debug_capture_enabled ?? false);
       setVoicePreference(settings.voice_preference);
+      setVoiceId(voices.find((v) => v.active)?.id ?? "");
       setOnlineModeEnabled(settings.online_mode_enabled);
       setSearchBackend(settings.search_backend);
       setSearxngInstanceUrl(settings.searxng_instance_url);
@@ -226,6 +384,10 @@ const SettingsModal: React.FC = () => {
     }
   };
 
+  const updateWakePhrase = (index: number, update: Partial<WakePhrase>) => {
+    setWakePhrases(wakePhrases.map((p, i) => (i === index ? { ...p, ...update } : p)));
+  };
+
   // Category content renderers (AC2, AC3)
   const renderLLMSettings = () => (
     <div className="space-y-5">
@@ -353,7 +515,7 @@ const SettingsModal: React.FC = () => {
               Enable Wake Word ("Hey Aura")
             </Label>
             <p className="text-xs text-gray-500">
-              Activate voice input hands-free with energy-based voice detection (100% offline)
+              Activate voice input hands-free with on-device keyword spotting (100% offline)
             </p>
           </div>
           <Switch
@@ -363,10 +525,49 @@ const SettingsModal: React.FC = () => {
           />
         </div>
         {wakeWordEnabled && (
-          <p className="text-xs text-gray-400 mt-2">
-            Wake word detection uses energy-based VAD - no additional models needed.
-            Adjust microphone sensitivity in Voice & Audio settings if needed.
-          </p>
+          <div className="space-y-3 mt-2">
+            {wakePhrases.map((wakePhrase, index) => (
+              <div key={index} className="flex items-center gap-3">
+                <Input
+                  type="text"
+                  value={wakePhrase.phrase}
+                  onChange={(e) => updateWakePhrase(index, { phrase: e.target.value })}
+                  placeholder="hey aura"
+                  className="flex-1 bg-gray-800 text-gray-100 border-gray-700 focus:ring-gray-600 placeholder-gray-500"
+                />
+                <input
+                  type="range"
+                  min="0.05"
+                  max="0.95"
+                  step="0.05"
+                  value={wakePhrase.sensitivity}
+                  onChange={(e) => updateWakePhrase(index, { sensitivity: parseFloat(e.target.value) })}
+                  title={`Sensitivity: ${Math.round(wakePhrase.sensitivity * 100)}%`}
+                  className="w-28 h-2 bg-gray-700 rounded-lg appearance-none cursor-pointer accent-gray-600"
+                />
+                <Button
+                  variant="outline"
+                  onClick={() => setWakePhrases(wakePhrases.filter((_, i) => i !== index))}
+                  disabled={wakePhrases.length === 1}
+                  className="bg-gray-800 hover:bg-gray-700 text-gray-200 border-gray-700"
+                >
+                  Remove
+                </Button>
+              </div>
+            ))}
+            <Button
+              variant="outline"
+              onClick={() => setWakePhrases([...wakePhrases, { phrase: "", sensitivity: 0.75 }])}
+              className="bg-gray-800 hover:bg-gray-700 text-gray-200 border-gray-700"
+            >
+              Add Wake Phrase
+            </Button>
+            <p className="text-xs text-gray-400">
+              Wake phrases need the keyword spotting model in the models directory; without it, any
+              sustained sound wakes the assistant (adjust microphone sensitivity in Voice & Audio settings).
+              Higher sensitivity triggers more easily but also more falsely.
+            </p>
+          </div>
         )}
       </div>
 
@@ -569,40 +770,174 @@ const SettingsModal: React.FC = () => {
         </p>
       </div>
 
+      {/* STT Language Selection */}
+      <div className="space-y-2">
+        <Label htmlFor="stt-language" className="text-gray-300">
+          Spoken Language
+        </Label>
+        <Select value={sttLanguage} onValueChange={setSttLanguage}>
+          <SelectTrigger
+            id="stt-language"
+            className="w-full bg-gray-800 text-gray-100 border-gray-700 focus:ring-gray-600"
+          >
+            <SelectValue placeholder="Select language" />
+          </SelectTrigger>
+          <SelectContent className="bg-gray-800 border-gray-700">
+            {[
+              ["auto", "Auto-detect"],
+              ["en", "English"],
+              ["de", "German"],
+              ["fr", "French"],
+              ["es", "Spanish"],
+              ["it", "Italian"],
+              ["pt", "Portuguese"],
+              ["nl", "Dutch"],
+              ["pl", "Polish"],
+              ["ja", "Japanese"],
+              ["zh", "Chinese"],
+            ].map(([code, label]) => (
+              <SelectItem
+                key={code}
+                value={code}
+                className="text-gray-100 focus:bg-gray-700 focus:text-gray-100"
+              >
+                {label}
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+        <p className="text-xs text-gray-500">
+          Auto-detect and other languages require a multilingual model; English models always transcribe English.
+        </p>
+      </div>
+
       {/* TTS Voice Selection */}
       <div className="space-y-2">
-        <Label htmlFor="voice-preference" className="text-gray-300">
-          Voice Preference
+        <Label htmlFor="voice-model" className="text-gray-300">
+          Voice
         </Label>
-        <Select value={voicePreference} onValueChange={setVoicePreference}>
+        <Select value={voiceId} onValueChange={setVoiceId} disabled={voices.length === 0}>
           <SelectTrigger
-            id="voice-preference"
+            id="voice-model"
             className="w-full bg-gray-800 text-gray-100 border-gray-700 focus:ring-gray-600"
           >
-            <SelectValue placeholder="Select voice" />
+            <SelectValue placeholder={voices.length === 0 ? "No voices found" : "Select voice"} />
+          </SelectTrigger>
+          <SelectContent className="bg-gray-800 border-gray-700">
+            {voices.map((voice) => (
+              <SelectItem
+                key={voice.id}
+                value={voice.id}
+                className="text-gray-100 focus:bg-gray-700 focus:text-gray-100"
+              >
+                {voice.id} ({describeVoice(voice)}){voice.bundled ? "" : " · installed"}
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+        <p className="text-xs text-gray-500">
+          Select the voice for text-to-speech output. Changes apply after clicking Save, without a restart.
+        </p>
+      </div>
+
+      {/* TTS Voice Installation */}
+      <div className="space-y-2">
+        <Label htmlFor="voice-source" className="text-gray-300">
+          Install Voice
+        </Label>
+        <Input
+          id="voice-source"
+          value={voiceSource}
+          onChange={(e) => setVoiceSource(e.target.value)}
+          placeholder="https://.../en_GB-alba-medium.onnx or /path/to/voice.tar.gz"
+          className="bg-gray-800 text-gray-100 border-gray-700 focus:ring-gray-600 placeholder-gray-500"
+          disabled={isInstallingVoice}
+        />
+        <Input
+          id="voice-sha256"
+          value={voiceSha256}
+          onChange={(e) => setVoiceSha256(e.target.value)}
+          placeholder="SHA-256 checksum (required for URLs)"
+          className="bg-gray-800 text-gray-100 border-gray-700 focus:ring-gray-600 placeholder-gray-500 font-mono text-xs"
+          disabled={isInstallingVoice}
+        />
+        {voiceSource.trim().toLowerCase().endsWith(".onnx") && (
+          <Input
+            id="voice-config-sha256"
+            value={voiceConfigSha256}
+            onChange={(e) => setVoiceConfigSha256(e.target.value)}
+            placeholder="SHA-256 checksum of the .onnx.json config (required for URLs)"
+            className="bg-gray-800 text-gray-100 border-gray-700 focus:ring-gray-600 placeholder-gray-500 font-mono text-xs"
+            disabled={isInstallingVoice}
+          />
+        )}
+        <Button
+          variant="outline"
+          onClick={handleInstallVoice}
+          disabled={isInstallingVoice || !voiceSource.trim()}
+          className="bg-gray-800 hover:bg-gray-700 text-gray-200 border-gray-700"
+        >
+          {isInstallingVoice
+            ? voiceInstallProgress
+              ? `Downloading... ${voiceInstallProgress.percentage.toFixed(0)}%`
+              : "Installing..."
+            : "Install Voice"}
+        </Button>
+        <p className="text-xs text-gray-500">
+          Piper voice as a .onnx model (with its .onnx.json alongside) or a .tar.gz archive, from a local path or URL.
+        </p>
+      </div>
+
+      <div className="border-t border-gray-800 pt-5"></div>
+
+      {/* Microphone Selection */}
+      <div className="space-y-2">
+        <Label htmlFor="input-device" className="text-gray-300">
+          Microphone
+        </Label>
+        <Select
+          value={inputDeviceName || SYSTEM_DEFAULT_DEVICE}
+          onValueChange={(value) => setInputDeviceName(value === SYSTEM_DEFAULT_DEVICE ? "" : value)}
+        >
+          <SelectTrigger
+            id="input-device"
+            className="w-full bg-gray-800 text-gray-100 border-gray-700 focus:ring-gray-600"
+          >
+            <SelectValue placeholder="Select microphone" />
           </SelectTrigger>
           <SelectContent className="bg-gray-800 border-gray-700">
             <SelectItem
-              value="male"
-              className="text-gray-100 focus:bg-gray-700 focus:text-gray-100"
-            >
-              Male Voice (Lessac)
-            </SelectItem>
-            <SelectItem
-              value="female"
+              value={SYSTEM_DEFAULT_DEVICE}
               className="text-gray-100 focus:bg-gray-700 focus:text-gray-100"
             >
-              Female Voice (Amy)
+              System Default
             </SelectItem>
+            {inputDeviceName && !inputDevices.some((d) => d.name === inputDeviceName) && (
+              <SelectItem
+                value={inputDeviceName}
+                className="text-gray-100 focus:bg-gray-700 focus:text-gray-100"
+              >
+                {inputDeviceName} (not connected)
+              </SelectItem>
+            )}
+            {inputDevices.map((device) => (
+              <SelectItem
+                key={device.name}
+                value={device.name}
+                className="text-gray-100 focus:bg-gray-700 focus:text-gray-100"
+              >
+                {device.name}
+                {device.default_config &&
+                  ` (${device.default_config.max_sample_rate / 1000} kHz, ${device.default_config.channels === 1 ? "mono" : `${device.default_config.channels} ch`})`}
+              </SelectItem>
+            ))}
           </SelectContent>
         </Select>
         <p className="text-xs text-gray-500">
-          Select the voice for text-to-speech output. Changes apply after clicking Save.
+          Audio is converted to 16 kHz mono automatically. Falls back to the system default if the device is unplugged.
         </p>
       </div>
 
-      <div className="border-t border-gray-800 pt-5"></div>
-
       {/* Microphone Sensitivity Slider */}
       <div className="space-y-2">
         <div className="flex items-center justify-between">
@@ -648,6 +983,118 @@ const SettingsModal: React.FC = () => {
           How long to wait for silence before ending recording. Default: 1.28s
         </p>
       </div>
+
+      {/* End-of-Speech Detection */}
+      <div className="space-y-2">
+        <Label htmlFor="vad-engine" className="text-gray-300">
+          End-of-Speech Detection
+        </Label>
+        <Select value={vadEngine} onValueChange={setVadEngine}>
+          <SelectTrigger
+            id="vad-engine"
+            className="w-full bg-gray-800 text-gray-100 border-gray-700 focus:ring-gray-600"
+          >
+            <SelectValue placeholder="Select detector" />
+          </SelectTrigger>
+          <SelectContent className="bg-gray-800 border-gray-700">
+            <SelectItem
+              value="energy"
+              className="text-gray-100 focus:bg-gray-700 focus:text-gray-100"
+            >
+              Energy (Default)
+            </SelectItem>
+            <SelectItem
+              value="silero"
+              className="text-gray-100 focus:bg-gray-700 focus:text-gray-100"
+            >
+              Silero Neural VAD (Noisy rooms)
+            </SelectItem>
+          </SelectContent>
+        </Select>
+        <p className="text-xs text-gray-500">
+          Silero ignores steady background noise like fans. Requires silero_vad.onnx in the models directory.
+        </p>
+      </div>
+
+      {/* Barge-In */}
+      <div className="space-y-2">
+        <Label htmlFor="barge-in-mode" className="text-gray-300">
+          Interrupt While Speaking
+        </Label>
+        <Select value={bargeInMode} onValueChange={setBargeInMode}>
+          <SelectTrigger
+            id="barge-in-mode"
+            className="w-full bg-gray-800 text-gray-100 border-gray-700 focus:ring-gray-600"
+          >
+            <SelectValue placeholder="Select mode" />
+          </SelectTrigger>
+          <SelectContent className="bg-gray-800 border-gray-700">
+            <SelectItem
+              value="off"
+              className="text-gray-100 focus:bg-gray-700 focus:text-gray-100"
+            >
+              Off (Default)
+            </SelectItem>
+            <SelectItem
+              value="voice"
+              className="text-gray-100 focus:bg-gray-700 focus:text-gray-100"
+            >
+              When I Start Talking
+            </SelectItem>
+            <SelectItem
+              value="wake_word"
+              className="text-gray-100 focus:bg-gray-700 focus:text-gray-100"
+            >
+              When I Say the Wake Phrase
+            </SelectItem>
+          </SelectContent>
+        </Select>
+        <p className="text-xs text-gray-500">
+          Stops the spoken answer and listens for a new request. Use the wake phrase option with loud speakers or no headset.
+        </p>
+      </div>
+
+      {/* Live Caption Interval Slider */}
+      <div className="space-y-2">
+        <div className="flex items-center justify-between">
+          <Label htmlFor="stt-partial-interval" className="text-gray-300">
+            Live Captions
+          </Label>
+          <span className="text-sm text-gray-400">
+            {sttPartialIntervalMs === 0 ? "Off" : `every ${(sttPartialIntervalMs / 1000).toFixed(1)}s`}
+          </span>
+        </div>
+        <input
+          type="range"
+          id="stt-partial-interval"
+          min="0"
+          max="2000"
+          step="100"
+          value={sttPartialIntervalMs}
+          onChange={(e) => setSttPartialIntervalMs(parseInt(e.target.value))}
+          className="w-full h-2 bg-gray-700 rounded-lg appearance-none cursor-pointer accent-gray-600"
+        />
+        <p className="text-xs text-gray-500">
+          How often to update the transcript while you speak. Shorter intervals use more CPU. Default: 0.5s
+        </p>
+      </div>
+
+      {/* Debug Capture */}
+      <div className="flex items-center justify-between">
+        <div className="space-y-0.5">
+          <Label htmlFor="debug-capture-enabled" className="text-gray-300">
+            Save Recordings for Debugging
+          </Label>
+          <p className="text-xs text-gray-500">
+            Keeps the last 50 utterances as WAV files with their transcription in the app data "captures" folder
+          </p>
+        </div>
+        <Switch
+          id="debug-capture-enabled"
+          checked={debugCaptureEnabled}
+          onCheckedChange={setDebugCaptureEnabled}
+        />
+      </div>
     </div>
   );
 
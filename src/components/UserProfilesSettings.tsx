@@ -42,6 +42,33 @@ interface UserHAPreferences {
   updated_at: string;
 }
 
+// TTS voice preferences types
+interface UserTtsPreferences {
+  user_id: number;
+  speaker: string | null;
+  length_scale: number | null;
+  noise_scale: number | null;
+  sentence_silence: number | null;
+  updated_at: string;
+}
+
+interface VoiceConfig {
+  sample_rate: number;
+  num_speakers: number;
+  speaker_id_map: Record<string, number>;
+  length_scale: number;
+  noise_scale: number;
+  sentence_silence: number;
+}
+
+type TtsSetting = "length_scale" | "noise_scale" | "sentence_silence";
+
+const TTS_SLIDERS: { field: TtsSetting; label: string; min: number; max: number; step: number; unit: string }[] = [
+  { field: "length_scale", label: "Speaking Pace (higher is slower)", min: 0.5, max: 2.0, step: 0.05, unit: "×" },
+  { field: "noise_scale", label: "Voice Variability", min: 0, max: 1.5, step: 0.05, unit: "" },
+  { field: "sentence_silence", label: "Pause Between Sentences", min: 0, max: 2.0, step: 0.05, unit: "s" },
+];
+
 interface HAEntity {
   entity_id: string;
   state: string;
@@ -77,6 +104,10 @@ const UserProfilesSettings: React.FC = () => {
   const [selectedEntity, setSelectedEntity] = useState("");
   const [isCreatingShortcut, setIsCreatingShortcut] = useState(false);
 
+  // TTS voice state
+  const [voiceConfig, setVoiceConfig] = useState<VoiceConfig | null>(null);
+  const [ttsPreferences, setTtsPreferences] = useState<Record<number, UserTtsPreferences>>({});
+
   // Load user profiles and migration status on mount
   useEffect(() => {
     loadData();
@@ -97,6 +128,22 @@ const UserProfilesSettings: React.FC = () => {
       const settings = await invoke<any>("load_settings");
       setClientId(settings.spotify_client_id || "");
 
+      // Load the voice's speakers/defaults and each user's TTS preferences
+      try {
+        setVoiceConfig(await invoke<VoiceConfig>("tts_get_voice_config"));
+
+        const ttsMap: Record<number, UserTtsPreferences> = {};
+        for (const profile of profiles) {
+          const prefs = await invoke<UserTtsPreferences | null>("get_user_tts_preferences", { userId: profile.id });
+          if (prefs) {
+            ttsMap[profile.id] = prefs;
+          }
+        }
+        setTtsPreferences(ttsMap);
+      } catch (ttsError) {
+        console.warn("Failed to load TTS voice preferences:", ttsError);
+      }
+
       // Load Home Assistant status
       try {
         const haConnected = await invoke<HAStatus>("ha_get_status");
@@ -286,6 +333,36 @@ const UserProfilesSettings: React.FC = () => {
     }
   };
 
+  const handleUpdateTtsPreferences = async (
+    userId: number,
+    changes: Partial<Pick<UserTtsPreferences, "speaker" | TtsSetting>>
+  ) => {
+    const currentPrefs = ttsPreferences[userId] || {
+      user_id: userId,
+      speaker: null,
+      length_scale: null,
+      noise_scale: null,
+      sentence_silence: null,
+      updated_at: new Date().toISOString(),
+    };
+
+    const updatedPrefs = { ...currentPrefs, ...changes };
+
+    try {
+      await invoke("update_user_tts_preferences", {
+        userId,
+        speaker: updatedPrefs.speaker,
+        lengthScale: updatedPrefs.length_scale,
+        noiseScale: updatedPrefs.noise_scale,
+        sentenceSilence: updatedPrefs.sentence_silence,
+      });
+
+      setTtsPreferences(prev => ({ ...prev, [userId]: updatedPrefs }));
+    } catch (error) {
+      showErrorToast(error, "Failed to update voice preferences");
+    }
+  };
+
   if (isLoading) {
     return (
       <div className="space-y-4">
@@ -422,6 +499,70 @@ const UserProfilesSettings: React.FC = () => {
                   </div>
                 )}
 
+                {/* Voice Preferences */}
+                {voiceConfig && (
+                  <div className="mt-4 pt-4 border-t border-gray-700 space-y-2">
+                    <div className="flex items-center gap-2 text-sm font-medium text-gray-200">
+                      <span>🔊</span>
+                      <span>Voice</span>
+                      <button
+                        onClick={() => handleUpdateTtsPreferences(profile.id, {
+                          speaker: null,
+                          length_scale: null,
+                          noise_scale: null,
+                          sentence_silence: null,
+                        })}
+                        className="ml-auto text-xs text-gray-400 hover:text-gray-200"
+                      >
+                        Reset to defaults
+                      </button>
+                    </div>
+
+                    {voiceConfig.num_speakers > 1 && (
+                      <div>
+                        <label className="text-xs text-gray-400 block mb-1">Speaker:</label>
+                        <select
+                          value={ttsPreferences[profile.id]?.speaker || ""}
+                          onChange={(e) => handleUpdateTtsPreferences(profile.id, { speaker: e.target.value || null })}
+                          className="w-full bg-gray-800 border border-gray-600 rounded px-3 py-1.5 text-sm text-gray-200 focus:outline-none focus:ring-2 focus:ring-blue-500"
+                        >
+                          <option value="">Voice default</option>
+                          {Object.keys(voiceConfig.speaker_id_map).map((speaker) => (
+                            <option key={speaker} value={speaker}>
+                              {speaker}
+                            </option>
+                          ))}
+                        </select>
+                      </div>
+                    )}
+
+                    {TTS_SLIDERS.map(({ field, label, min, max, step, unit }) => {
+                      const value = ttsPreferences[profile.id]?.[field] ?? voiceConfig[field];
+                      return (
+                        <div key={field}>
+                          <label className="text-xs text-gray-400 flex justify-between mb-1">
+                            <span>{label}:</span>
+                            <span>{value.toFixed(2)}{unit}</span>
+                          </label>
+                          <input
+                            type="range"
+                            min={min}
+                            max={max}
+                            step={step}
+                            value={value}
+                            onChange={(e) => {
+                              const changes: Partial<Record<TtsSetting, number>> = {};
+                              changes[field] = parseFloat(e.target.value);
+                              handleUpdateTtsPreferences(profile.id, changes);
+                            }}
+                            className="w-full"
+                          />
+                        </div>
+                      );
+                    })}
+                  </div>
+                )}
+
                 {/* Home Assistant Shortcuts */}
                 {haStatus?.connected && (
                   <div className="mt-4 pt-4 border-t border-gray-700">
@@ -587,6 +728,13 @@ const UserProfilesSettings: React.FC = () => {
               <li>• Unknown speakers fall back to the global account (if any)</li>
             </ul>
           </div>
+          <div>
+            <p className="text-xs font-medium text-gray-300 mb-1">🔊 Voice:</p>
+            <ul className="text-xs text-gray-400 space-y-1">
+              <li>• Choose a speaker (multi-speaker voices), pace and pauses per user</li>
+              <li>• Spoken answers use the identified speaker's settings</li>
+            </ul>
+          </div>
           {haStatus?.connected && (
             <div>
               <p className="text-xs font-medium text-gray-300 mb-1">🏠 Smart Home:</p>
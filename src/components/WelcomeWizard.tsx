@@ -7,6 +7,8 @@ interface SetupStatus {
   first_run_complete: boolean;
   whisper_model_exists: boolean;
   whisper_model_path: string;
+  llm_model_name: string;
+  llm_model_installed: boolean | null; // null when Ollama isn't reachable
 }
 
 interface DownloadProgress {
@@ -15,6 +17,14 @@ interface DownloadProgress {
   percentage: number;
 }
 
+interface OllamaPullProgress {
+  model: string;
+  status: string;
+  total: number | null;
+  completed: number | null;
+  percentage: number | null;
+}
+
 type WizardStep = "checking" | "dependencies" | "downloading" | "theme" | "complete";
 
 export default function WelcomeWizard({ onComplete }: { onComplete: () => void }) {
@@ -27,6 +37,8 @@ export default function WelcomeWizard({ onComplete }: { onComplete: () => void }
     percentage: 0,
   });
   const [error, setError] = useState<string | null>(null);
+  const [pullingModel, setPullingModel] = useState(false);
+  const [pullProgress, setPullProgress] = useState<OllamaPullProgress | null>(null);
 
   // Check setup status on mount
   useEffect(() => {
@@ -44,6 +56,17 @@ export default function WelcomeWizard({ onComplete }: { onComplete: () => void }
     };
   }, []);
 
+  // Listen for Ollama model pull progress events
+  useEffect(() => {
+    const unlisten = listen<OllamaPullProgress>("ollama_pull_progress", (event) => {
+      setPullProgress(event.payload);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   const checkStatus = async () => {
     try {
       setError(null);
@@ -82,6 +105,25 @@ export default function WelcomeWizard({ onComplete }: { onComplete: () => void }
     }
   };
 
+  const handlePullLlmModel = async () => {
+    if (!status) return;
+
+    try {
+      setPullingModel(true);
+      setPullProgress(null);
+      setError(null);
+
+      await invoke("ollama_pull_model", { model: status.llm_model_name });
+
+      // Refresh status after download
+      await checkStatus();
+    } catch (err) {
+      setError(`Failed to download ${status.llm_model_name}: ${err}`);
+    } finally {
+      setPullingModel(false);
+    }
+  };
+
   const handleContinueToTheme = async () => {
     try {
       setError(null);
@@ -184,6 +226,61 @@ export default function WelcomeWizard({ onComplete }: { onComplete: () => void }
               </div>
             </div>
 
+            {/* LLM Model Status (optional - Ollama may be replaced by another server) */}
+            {status.llm_model_installed !== null && (
+              <div className="flex items-start gap-4 p-4 bg-gray-700 rounded-lg">
+                <div className="flex-shrink-0">
+                  {status.llm_model_installed ? (
+                    <svg className="w-6 h-6 text-green-500" fill="currentColor" viewBox="0 0 20 20">
+                      <path fillRule="evenodd" d="M10 18a8 8 0 100-16 8 8 0 000 16zm3.707-9.293a1 1 0 00-1.414-1.414L9 10.586 7.707 9.293a1 1 0 00-1.414 1.414l2 2a1 1 0 001.414 0l4-4z" clipRule="evenodd" />
+                    </svg>
+                  ) : (
+                    <svg className="w-6 h-6 text-yellow-500" fill="currentColor" viewBox="0 0 20 20">
+                      <path fillRule="evenodd" d="M8.257 3.099c.765-1.36 2.722-1.36 3.486 0l5.58 9.92c.75 1.334-.213 2.98-1.742 2.98H4.42c-1.53 0-2.493-1.646-1.743-2.98l5.58-9.92zM11 13a1 1 0 11-2 0 1 1 0 012 0zm-1-8a1 1 0 00-1 1v3a1 1 0 002 0V6a1 1 0 00-1-1z" clipRule="evenodd" />
+                    </svg>
+                  )}
+                </div>
+                <div className="flex-1">
+                  <h3 className="text-white font-semibold mb-1">AI Model ({status.llm_model_name})</h3>
+                  <p className="text-gray-400 text-sm mb-2">
+                    {status.llm_model_installed
+                      ? "✓ Model is installed in Ollama"
+                      : "Download the language model into Ollama (optional, can be done later)"}
+                  </p>
+                  {!status.llm_model_installed && !pullingModel && (
+                    <button
+                      onClick={handlePullLlmModel}
+                      className="px-4 py-2 text-white rounded-lg text-sm transition-colors"
+                      style={{
+                        backgroundColor: "var(--accent-primary)",
+                      }}
+                      onMouseEnter={(e) => e.currentTarget.style.backgroundColor = "var(--active-indicator)"}
+                      onMouseLeave={(e) => e.currentTarget.style.backgroundColor = "var(--accent-primary)"}
+                    >
+                      Download Model
+                    </button>
+                  )}
+                  {pullingModel && (
+                    <div>
+                      <div className="bg-gray-600 rounded-full h-2 overflow-hidden mb-1">
+                        <div
+                          className="h-full transition-all duration-300 ease-out"
+                          style={{
+                            width: `${pullProgress?.percentage ?? 0}%`,
+                            backgroundColor: "var(--accent-primary)"
+                          }}
+                        ></div>
+                      </div>
+                      <p className="text-gray-400 text-xs">
+                        {pullProgress?.status ?? "Starting download..."}
+                        {pullProgress?.percentage != null && ` (${pullProgress.percentage.toFixed(1)}%)`}
+                      </p>
+                    </div>
+                  )}
+                </div>
+              </div>
+            )}
+
             {/* Continue Button */}
             <div className="pt-4 flex justify-end">
               <button
@@ -11,6 +11,24 @@ export interface Conversation {
   id: number;
   title: string;
   created_at: string;
+  persona_id?: number | null;   // Persona used for generation (null = default assistant)
+}
+
+export interface LlmResponse {
+  content: string;
+  backend: string;              // Name of the backend in the fallback chain that answered
+}
+
+export interface Persona {
+  id: number;
+  name: string;
+  system_prompt: string;        // Replaces the default system prompt (empty = keep default)
+  temperature: number;          // Sampling temperature (0.0-2.0)
+  max_tokens: number;           // Maximum tokens generated per response
+  top_p?: number | null;        // Nucleus sampling (null = server default)
+  stop: string[];               // Stop sequences
+  model_name?: string | null;   // Model override (null = model from Settings)
+  created_at?: string;
 }
 
 export interface Settings {
@@ -18,12 +36,20 @@ export interface Settings {
   server_address: string;       // Remote server address (legacy field)
   api_key: string;              // API key (stored in keyring, but cached here)
   wake_word_enabled: boolean;   // Enable/disable wake word detection
+  wake_phrases?: string;        // JSON list of wake phrases: [{ phrase, sensitivity (0.0-1.0) }]
+  input_device_name?: string;   // Microphone to capture from ("" = system default)
   api_base_url: string;         // Base URL for OpenAI-compatible API (e.g., "http://localhost:11434/v1")
   model_name: string;           // Model name to use (e.g., "llama3", "phi3:instruct")
   vad_sensitivity: number;      // Voice activity detection sensitivity (0.001-1.0)
   vad_timeout_ms: number;       // Silence timeout in milliseconds (100-10000)
+  vad_engine?: string;          // End-of-speech detection: "energy" or "silero"
+  barge_in_mode?: string;       // Interrupting TTS playback: "off", "voice" or "wake_word"
   stt_model_name: string;       // STT (Whisper) model filename (e.g., "ggml-base.en.bin")
+  stt_language?: string;        // Whisper language: "auto" (detect) or ISO 639-1 code (e.g., "de")
+  stt_partial_interval_ms?: number; // Live caption re-transcribe interval in milliseconds (0 = off)
+  debug_capture_enabled?: boolean; // Save each utterance (WAV + JSON sidecar) for diagnosis
   voice_preference: string;     // TTS voice preference ("male" or "female")
+  voice_model?: string;         // Active Piper voice id from the voice catalog ("" = use voice_preference)
 
   // RAG / Online Mode Settings
   online_mode_enabled: boolean;       // Enable/disable web search for RAG (default: false)
@@ -38,6 +64,23 @@ export interface Settings {
   spotify_auto_play_enabled?: boolean; // Auto-play music via voice commands (optional)
 }
 
+// Wake phrase for keyword spotting (stored as JSON in Settings.wake_phrases)
+export interface WakePhrase {
+  phrase: string;
+  sensitivity: number;  // 0.0-1.0, higher triggers more easily
+}
+
+export const DEFAULT_WAKE_PHRASES = JSON.stringify([{ phrase: "hey aura", sensitivity: 0.75 }]);
+
+export function parseWakePhrases(json: string | undefined): WakePhrase[] {
+  try {
+    const phrases = JSON.parse(json ?? DEFAULT_WAKE_PHRASES);
+    return Array.isArray(phrases) ? phrases : JSON.parse(DEFAULT_WAKE_PHRASES);
+  } catch {
+    return JSON.parse(DEFAULT_WAKE_PHRASES);
+  }
+}
+
 export type AppStatus = "idle" | "listening" | "processing" | "speaking";
 export type InputMethod = "voice" | "text";
 
@@ -134,12 +177,20 @@ export const useChatStore = create<ChatStore>((set) => ({
     server_address: "",
     api_key: "",
     wake_word_enabled: false,
+    wake_phrases: DEFAULT_WAKE_PHRASES,
+    input_device_name: "",
     api_base_url: "http://localhost:11434/v1",
     model_name: "llama3",
     vad_sensitivity: 0.02,
     vad_timeout_ms: 1280,
+    vad_engine: "energy",
+    barge_in_mode: "off",
     stt_model_name: "ggml-base.en.bin",
+    stt_language: "auto",
+    stt_partial_interval_ms: 500,
+    debug_capture_enabled: false,
     voice_preference: "male",
+    voice_model: "",
     online_mode_enabled: false,
     search_backend: "searxng",
     searxng_instance_url: "https://searx.be",